@@ -0,0 +1,254 @@
+// Cameras: one per player on a split screen, following the ships around the world and zoomed
+// by the mouse wheel or the ship's speed
+use bevy::prelude::*;
+use bevy::core_pipeline::clear_color::ClearColorConfig;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::render::camera::Viewport;
+use bevy::window::{PrimaryWindow, WindowResized};
+
+use crate::death::DeathSequence;
+use crate::physics::{InterpolationSet, Velocity, WorldBounds};
+use crate::player::{MAX_PLAYERS, Player, PlayerCount, Starship, player_exists};
+use crate::session::{GameState, not_paused};
+
+// Define the PlayerCamera component tying a camera to the player it shows
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct PlayerCamera {
+    pub id: usize,  // Id of the player whose half of the screen this is
+}
+
+// Setup system that spawns the first player's camera
+fn setup_camera(mut commands: Commands) {
+    // Spawn a 2D camera covering the whole window
+    spawn_player_camera(&mut commands, 0, None);
+}
+
+// Spawn the camera for a player's part of the window
+fn spawn_player_camera(
+    commands: &mut Commands,                                  // For spawning the camera
+    id: usize,                                                // Whose camera it is
+    viewport: Option<Viewport>,                               // Part of the window it draws to
+) {
+    let mut camera = commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                order: id as isize,
+                viewport,
+                ..default()
+            },
+            // Only the first camera clears, so it doesn't wipe the other half
+            camera_2d: Camera2d {
+                clear_color: if id == 0 { ClearColorConfig::Default } else { ClearColorConfig::None },
+            },
+            ..default()
+        },
+        PlayerCamera { id },
+    ));
+    // The HUD is drawn once, by the first camera
+    if id > 0 {
+        camera.insert(UiCameraConfig { show_ui: false });
+    }
+}
+
+// The part of the window a player's camera draws to, or None for the whole window
+fn split_viewport(id: usize, player_count: &PlayerCount, window: &Window) -> Option<Viewport> {
+    let count = player_count.ids().len() as u32;
+    let width = window.physical_width() / count;
+    if count == 1 || width == 0 {
+        return None;
+    }
+    Some(Viewport {
+        physical_position: UVec2::new(id as u32 * width, 0),
+        physical_size: UVec2::new(width, window.physical_height()),
+        ..default()
+    })
+}
+
+// Where the cursor points in the world, through whichever camera's part of the window it is over
+pub fn cursor_world_position<'a>(
+    window: &Window,                                          // The window holding the cursor
+    cameras: impl IntoIterator<Item = (&'a Camera, &'a GlobalTransform)>, // The cameras drawing to it
+) -> Option<Vec2> {
+    let cursor = window.cursor_position()?;
+    cameras.into_iter().find_map(|(camera, camera_transform)| {
+        let rect = camera.logical_viewport_rect()?;
+        if !rect.contains(cursor) {
+            return None;
+        }
+        camera.viewport_to_world_2d(camera_transform, cursor - rect.min)
+    })
+}
+
+// System to give every player a camera and split the window between them
+fn split_screen_system(
+    mut commands: Commands,                                   // For adding and removing cameras
+    player_count: Res<PlayerCount>,                           // How many players share the window
+    window_query: Query<&Window, With<PrimaryWindow>>,        // Get the window size
+    mut resize_events: EventReader<WindowResized>,            // Window size changes
+    mut camera_query: Query<(Entity, &PlayerCamera, &mut Camera, &mut Transform)>, // Get the cameras
+) {
+    let resized = resize_events.read().count() > 0;
+    if !resized && !player_count.is_changed() {
+        return;
+    }
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
+    let ids = player_count.ids();
+    let mut has_camera = [false; MAX_PLAYERS];
+    for (entity, player_camera, mut camera, mut transform) in camera_query.iter_mut() {
+        if !ids.contains(&player_camera.id) {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        has_camera[player_camera.id] = true;
+        camera.viewport = split_viewport(player_camera.id, &player_count, window);
+        // A single camera goes back to the middle of the arena, and follows its ship from there
+        if ids.len() == 1 {
+            transform.translation = Vec3::new(0.0, 0.0, transform.translation.z);
+        }
+    }
+    for id in ids {
+        if !has_camera[id] {
+            spawn_player_camera(&mut commands, id, split_viewport(id, &player_count, window));
+        }
+    }
+}
+
+// Where a camera showing the given half-size of the world can sit closest to a point without
+// looking past the world's edges; a view wider than the world stays on its middle
+fn clamp_to_world(position: Vec2, view_half_size: Vec2, world_half_extents: Vec2) -> Vec2 {
+    let room = (world_half_extents - view_half_size).max(Vec2::ZERO);
+    position.clamp(-room, room)
+}
+
+// System to keep every camera on its player's ship, short of showing anything past the world's
+// edges; a world the size of the window keeps a single camera on its middle
+fn camera_follow_system(
+    world: Res<WorldBounds>,                                  // Where the edges are
+    ship_query: Query<(&Transform, &Player), With<Starship>>, // Get the ships to follow
+    mut camera_query: Query<(&PlayerCamera, &OrthographicProjection, &mut Transform), Without<Starship>>, // Get the cameras
+) {
+    for (player_camera, projection, mut transform) in camera_query.iter_mut() {
+        if let Some((ship_transform, _)) = ship_query.iter().find(|(_, player)| player.id == player_camera.id) {
+            let position = clamp_to_world(
+                ship_transform.translation.truncate(),
+                projection.area.half_size(),
+                world.half_extents(),
+            );
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
+        }
+    }
+}
+
+// What drives the camera zoom
+#[derive(Reflect, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ZoomMode {
+    #[default]
+    Scroll,  // The mouse wheel zooms in and out
+    Speed,   // The camera pulls back as the ship speeds up
+}
+
+// Resource configuring the camera zoom
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct CameraZoom {
+    pub mode: ZoomMode,          // What drives the zoom
+    pub min_scale: f32,          // Closest zoom (smaller shows less)
+    pub max_scale: f32,          // Furthest zoom (larger shows more)
+    pub scroll_step: f32,        // Fraction the scale changes per wheel notch
+    pub speed_for_max: f32,      // Ship speed at which Speed mode is fully zoomed out
+    pub smoothing: f32,          // How quickly the camera eases towards the target scale
+    pub target_scale: f32,       // Scale the camera is easing towards
+}
+
+impl Default for CameraZoom {
+    fn default() -> Self {
+        Self {
+            mode: ZoomMode::Scroll,
+            min_scale: 0.5,
+            max_scale: 2.0,
+            scroll_step: 0.1,
+            speed_for_max: 400.0,
+            smoothing: 8.0,
+            target_scale: 1.0,
+        }
+    }
+}
+
+impl CameraZoom {
+    // Move the target scale by a number of wheel notches, positive zooming in
+    fn scroll(&mut self, notches: f32) {
+        self.target_scale =
+            (self.target_scale * (1.0 - self.scroll_step).powf(notches)).clamp(self.min_scale, self.max_scale);
+    }
+
+    // Scale a camera should have for a ship moving at the given speed
+    fn speed_scale(&self, speed: f32) -> f32 {
+        let t = (speed / self.speed_for_max).clamp(0.0, 1.0);
+        1.0 + (self.max_scale - 1.0) * t
+    }
+}
+
+// System to zoom the cameras with the mouse wheel or the speed of each camera's ship
+fn camera_zoom_system(
+    time: Res<Time<Real>>,                                    // For smooth easing, at the same pace in slow motion
+    mut wheel_events: EventReader<MouseWheel>,                // Mouse wheel movement
+    mut zoom: ResMut<CameraZoom>,                             // Zoom settings and target
+    ship_query: Query<(&Velocity, &Player), With<Starship>>,  // Get every ship's speed
+    mut camera_query: Query<(&mut OrthographicProjection, &PlayerCamera)>, // Get the cameras to zoom
+) {
+    // Read the wheel every frame so notches don't pile up while another mode is active
+    let notches: f32 = wheel_events
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 100.0,
+        })
+        .sum();
+
+    if zoom.mode == ZoomMode::Scroll {
+        zoom.scroll(notches);
+    }
+
+    // Ease towards the target, independently of frame rate
+    let blend = 1.0 - (-zoom.smoothing * time.delta_seconds()).exp();
+    for (mut projection, player_camera) in camera_query.iter_mut() {
+        let speed = ship_query
+            .iter()
+            .find(|(_, player)| player.id == player_camera.id)
+            .map(|(velocity, _)| velocity.as_vec2().length());
+        // Without its ship, a camera holds the last scrolled zoom
+        let target = match (zoom.mode, speed) {
+            (ZoomMode::Speed, Some(speed)) => zoom.speed_scale(speed),
+            _ => zoom.target_scale,
+        };
+        let target = target.clamp(zoom.min_scale, zoom.max_scale);
+        projection.scale += (target - projection.scale) * blend;
+    }
+}
+
+// Plugin for the game camera
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<CameraZoom>()
+            .register_type::<PlayerCamera>()
+            .init_resource::<CameraZoom>()
+            .add_systems(Startup, setup_camera)
+            .add_systems(Update, (
+                split_screen_system,
+                // The view holds still while the game is paused
+                camera_follow_system.after(InterpolationSet).run_if(player_exists).run_if(not_paused),
+                // The death cam takes over the zoom, and holds it through the game over
+                camera_zoom_system
+                    .run_if(not_paused)
+                    .run_if(not(resource_exists::<DeathSequence>()))
+                    .run_if(not(in_state(GameState::GameOver))),
+            ));
+    }
+}
@@ -0,0 +1,80 @@
+// Options given on the command line when the game is launched
+use bevy::prelude::*;
+
+// What the game prints for `--help` or a flag it doesn't know
+pub const USAGE: &str = "\
+Usage: star-ship_game [OPTIONS]
+
+Options:
+  --width <PIXELS>    Width of the window
+  --height <PIXELS>   Height of the window
+  --fullscreen        Fill the screen instead of opening a window
+  --seed <NUMBER>     Seed the run's randomness, to play the same run again
+  --skip-menu         Start straight into play, past any menu
+  --god-mode          Ships never run out of fuel and can't be hurt, for testing
+  --practice          Fly the practice range: target dummies and no hazards
+  --replay            Play back the last recorded replay
+  -h, --help          Print this help";
+
+// Resource holding the options the game was launched with; parsed before the app is built,
+// since the window is set up from them
+#[derive(Reflect, Resource, Default, Clone, PartialEq, Debug)]
+#[reflect(Resource)]
+pub struct CliArgs {
+    pub width: Option<f32>,   // Width of the window, if given
+    pub height: Option<f32>,  // Height of the window, if given
+    pub fullscreen: bool,     // Whether to fill the screen
+    pub seed: Option<u64>,    // Seed of the run's randomness, if given
+    pub skip_menu: bool,      // Whether to start straight into play
+    pub god_mode: bool,       // Whether ships are invulnerable with endless fuel
+    pub practice: bool,       // Whether the run is on the practice range
+    pub replay: bool,         // Whether to play back the last recorded replay
+    pub help: bool,           // Whether only the usage was asked for
+}
+
+impl CliArgs {
+    // Read the options from the arguments, without the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut cli_args = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--width" => cli_args.width = Some(parse_value(&arg, args.next())?),
+                "--height" => cli_args.height = Some(parse_value(&arg, args.next())?),
+                "--fullscreen" => cli_args.fullscreen = true,
+                "--seed" => cli_args.seed = Some(parse_value(&arg, args.next())?),
+                "--skip-menu" => cli_args.skip_menu = true,
+                "--god-mode" => cli_args.god_mode = true,
+                "--practice" => cli_args.practice = true,
+                "--replay" => cli_args.replay = true,
+                "-h" | "--help" => cli_args.help = true,
+                _ => return Err(format!("unknown option {:?}", arg)),
+            }
+        }
+        for (flag, size) in [("--width", cli_args.width), ("--height", cli_args.height)] {
+            if size.is_some_and(|size| !(size.is_finite() && size > 0.0)) {
+                return Err(format!("{} needs a size above zero", flag));
+            }
+        }
+        Ok(cli_args)
+    }
+
+    // Read the options the game was launched with
+    pub fn from_env() -> Result<Self, String> {
+        Self::parse(std::env::args().skip(1))
+    }
+}
+
+// Parse the value following a flag
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String>
+where
+    T::Err: std::fmt::Display,
+{
+    let value = value.ok_or_else(|| format!("{} needs a value", flag))?;
+    value.parse().map_err(|err| format!("invalid value {:?} for {}: {}", value, flag, err))
+}
+
+// Run condition for the testing aids switched on by `--god-mode`
+pub fn god_mode(cli_args: Res<CliArgs>) -> bool {
+    cli_args.god_mode
+}
@@ -0,0 +1,596 @@
+// Health, shields and the damage dealt when things collide
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use crate::config::{GameConfig, ShieldRegenConfig};
+use crate::hostiles::{Asteroid, Hostile};
+use crate::physics::{
+    Collider, InterpolationSet, Mass, SpatialGrid, Velocity, WorldBounds, closing_speed, collision_impulse,
+    nearest_wrapped,
+};
+use crate::palette::ColorScheme;
+use crate::player::{Player, SHIP_SCALE, Starship};
+use crate::pool::{Pooled, release_pooled};
+use crate::session::{GameMode, GameState, NewGameEvent};
+use crate::weapons::Projectile;
+use crate::GameSet;
+
+// Define the Health component for anything that can be destroyed
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Health {
+    pub current: f32,  // Health remaining
+    pub max: f32,      // Health when fully repaired
+}
+
+// Define the Shield component that soaks up damage before Health
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Shield {
+    pub current: f32,    // Shield strength remaining
+    pub max: f32,        // Shield strength when fully charged
+    pub since_hit: f32,  // Seconds since the last damage taken, for holding off the recharge
+}
+
+impl Shield {
+    // Whether the shield is recharging rather than full or waiting out the delay after a hit
+    pub fn recharging(&self, regen: &ShieldRegenConfig, mode: GameMode) -> bool {
+        regen.enabled_in(mode) && self.current < self.max && self.since_hit >= regen.delay
+    }
+}
+
+// Marker for entities that nothing can damage
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Invulnerable;
+
+// Define the DamageFlash component that briefly lights up a ship after a hit
+#[derive(Reflect, Component)]
+#[reflect(Component)]
+pub struct DamageFlash {
+    pub timer: Timer,  // How long the flash has left to fade
+}
+
+impl Default for DamageFlash {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(0.3, TimerMode::Once),
+        }
+    }
+}
+
+// How much bigger a ship is drawn right after a hit
+const DAMAGE_FLASH_PULSE: f32 = 0.25;
+
+// Resource tuning how asteroids collide; how ships bounce and get hurt is in the game config
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+struct CollisionSettings {
+    asteroid_restitution: f32,  // How bouncy asteroids are off one another
+}
+
+impl Default for CollisionSettings {
+    fn default() -> Self {
+        Self {
+            asteroid_restitution: 1.0,
+        }
+    }
+}
+
+// Most new contacts between asteroids taken on in one tick; the rest are found again on the
+// next, so a crowd spawned on top of itself comes apart over a few ticks instead of all at once
+const MAX_ASTEROID_CONTACTS_PER_TICK: usize = 32;
+
+// Overlap in pixels left between asteroids pushed apart, so they still touch on the next tick
+// instead of the contact starting over, and bouncing again, every tick they graze
+const ASTEROID_CONTACT_SLOP: f32 = 0.5;
+
+// What dealt a piece of damage
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DamageSource {
+    PlayerBullet { owner: usize },  // A player's shot or bomb, credited to that player
+    EnemyBullet,                    // A shot fired by an enemy
+    Collision,                      // Running into something
+    Hazard,                         // The environment, like a black hole
+}
+
+impl DamageSource {
+    // Whether Invulnerable keeps this kind of damage out
+    pub fn blocked_by_invulnerability(self) -> bool {
+        !matches!(self, DamageSource::Hazard)
+    }
+}
+
+// Event asking for damage to be dealt to an entity
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DamageEvent {
+    pub target: Entity,        // Who takes the damage
+    pub amount: f32,           // How much, before the shield soaks any up
+    pub source: DamageSource,  // What dealt it
+}
+
+// Event sent when a shield soaks up damage, for showing the hit on it
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ShieldHitEvent {
+    pub target: Entity,  // Whose shield took the hit
+    pub broken: bool,    // Whether the hit emptied the shield
+}
+
+// Event sent for the health a hit actually took off, after the shield soaked up its share and
+// never more than there was left
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DamageTakenEvent {
+    pub target: Entity,  // Who lost the health
+    pub amount: f32,     // How much it lost
+}
+
+// Resource adding up the damage ships have taken this run, by what dealt it
+#[derive(Reflect, Resource, Default)]
+#[reflect(Resource)]
+pub struct DamageStats {
+    pub player_bullet: f32,  // From players' shots and bombs
+    pub enemy_bullet: f32,   // From enemy shots
+    pub collision: f32,      // From running into things
+    pub hazard: f32,         // From the environment
+}
+
+impl DamageStats {
+    // Count damage taken from the given source
+    pub fn record(&mut self, source: DamageSource, amount: f32) {
+        let total = match source {
+            DamageSource::PlayerBullet { .. } => &mut self.player_bullet,
+            DamageSource::EnemyBullet => &mut self.enemy_bullet,
+            DamageSource::Collision => &mut self.collision,
+            DamageSource::Hazard => &mut self.hazard,
+        };
+        *total += amount;
+    }
+}
+
+// Resource holding the asteroids touching the ship, so a contact only hurts once
+#[derive(Resource, Default)]
+pub struct ShipContacts(pub HashSet<(Entity, Entity)>);
+
+// Resource holding the pairs of asteroids touching each other, so a contact only bounces once
+#[derive(Resource, Default)]
+struct AsteroidContacts(HashSet<(Entity, Entity)>);
+
+// Take damage off the shield first, and off health once the shield is empty; any damage at all
+// holds off the shield's recharge. Returns how much the shield soaked up
+fn apply_damage(health: &mut Health, shield: Option<&mut Shield>, amount: f32) -> f32 {
+    let mut remaining = amount;
+    let mut absorbed = 0.0;
+    if let Some(shield) = shield {
+        if amount > 0.0 {
+            shield.since_hit = 0.0;
+        }
+        absorbed = remaining.min(shield.current);
+        shield.current -= absorbed;
+        remaining -= absorbed;
+    }
+    health.current = (health.current - remaining).max(0.0);
+    absorbed
+}
+
+// Define the LastHitBy component remembering which player last damaged a hostile
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct LastHitBy(pub usize);
+
+// Event sent once for every pair of things found overlapping: when a ship first touches a
+// hostile, when a projectile hits one, when two asteroids first touch, and when a ship runs into
+// debris. Detection only sends these; what the overlap does (damage, bouncing, sparks, knocking
+// out an engine) is left to the systems reading them
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CollisionEvent {
+    pub a: Entity,    // The ship, projectile or asteroid
+    pub b: Entity,    // The hostile or debris it ran into
+    pub point: Vec2,  // Where their hit circles meet
+}
+
+// Label for the systems sending CollisionEvent, so the systems reacting to it can run after them
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CollisionDetectionSet;
+
+// Where two overlapping hit circles meet, on the line between their centers
+fn contact_point(position_a: Vec2, radius_a: f32, position_b: Vec2, radius_b: f32) -> Vec2 {
+    position_a.lerp(position_b, radius_a / (radius_a + radius_b).max(f32::EPSILON))
+}
+
+// System to bucket the hostiles by where they are now, for the collision checks after it
+fn spatial_grid_system(
+    mut grid: ResMut<SpatialGrid>,                            // The grid to refill
+    arena: Res<WorldBounds>,                                  // Where the seams are
+    hostile_query: Query<(Entity, &Transform, &Collider), With<Hostile>>, // Get every hostile's hit area
+) {
+    let largest = hostile_query.iter().map(|(_, _, collider)| collider.radius).fold(0.0, f32::max);
+    grid.clear(largest, arena.half_extents());
+    for (entity, transform, collider) in hostile_query.iter() {
+        grid.insert(entity, transform.translation.truncate(), collider.radius);
+    }
+}
+
+// System to find the hostiles each ship has just run into; a contact lasting several ticks is
+// only sent on its first
+fn ship_collision_system(
+    grid: Res<SpatialGrid>,                                   // Hostiles near each ship
+    ship_query: Query<(Entity, &Transform, &Collider), With<Starship>>, // Get every ship's hit area
+    mut contacts: ResMut<ShipContacts>,                       // Hostiles already touching each ship
+    mut collision_events: EventWriter<CollisionEvent>,        // For the systems reacting to the contact
+) {
+    let mut touching = HashSet::new();
+    for (ship, ship_transform, ship_collider) in ship_query.iter() {
+        let ship_position = ship_transform.translation.truncate();
+        for (hostile, hostile_position, hostile_radius) in grid.query(ship_position, ship_collider.radius) {
+            if ship_position.distance(hostile_position) > ship_collider.radius + hostile_radius {
+                continue;
+            }
+            touching.insert((ship, hostile));
+            if !contacts.0.contains(&(ship, hostile)) {
+                collision_events.send(CollisionEvent {
+                    a: ship,
+                    b: hostile,
+                    point: contact_point(ship_position, ship_collider.radius, hostile_position, hostile_radius),
+                });
+            }
+        }
+    }
+    contacts.0 = touching;
+}
+
+// System to find the hostiles each projectile hits, as many as it can still pierce
+fn projectile_collision_system(
+    projectile_query: Query<(Entity, &Transform, &Projectile, &Pooled)>, // Get every projectile
+    grid: Res<SpatialGrid>,                                   // Targets near each projectile
+    mut collision_events: EventWriter<CollisionEvent>,        // For the systems reacting to the hit
+) {
+    for (projectile_entity, projectile_transform, projectile, pooled) in projectile_query.iter() {
+        if !pooled.in_use() {
+            continue;
+        }
+        let projectile_position = projectile_transform.translation.truncate();
+        let mut pierce = projectile.pierce;
+        for (hostile, hostile_position, radius) in grid.query(projectile_position, 0.0) {
+            // A piercing shot passes through what it already hit instead of hitting it again
+            if projectile.hit.contains(&hostile) || projectile_position.distance(hostile_position) > radius {
+                continue;
+            }
+            collision_events.send(CollisionEvent {
+                a: projectile_entity,
+                b: hostile,
+                point: projectile_position,
+            });
+            pierce = pierce.saturating_sub(1);
+            if pierce == 0 {
+                break;
+            }
+        }
+    }
+}
+
+// System to find the asteroids that have just run into one another; each pair is only checked
+// from the asteroid with the lower entity, so it is sent once
+fn asteroid_collision_system(
+    grid: Res<SpatialGrid>,                                   // Hostiles near each asteroid
+    asteroid_query: Query<(Entity, &Transform, &Collider), With<Asteroid>>, // Get every asteroid's hit area
+    mut contacts: ResMut<AsteroidContacts>,                   // Asteroids already touching
+    mut collision_events: EventWriter<CollisionEvent>,        // For the systems reacting to the contact
+) {
+    let mut touching = HashSet::new();
+    let mut new_contacts = 0;
+    for (asteroid, transform, collider) in asteroid_query.iter() {
+        let position = transform.translation.truncate();
+        for (other, other_position, other_radius) in grid.query(position, collider.radius) {
+            if other <= asteroid
+                || !asteroid_query.contains(other)
+                || position.distance(other_position) > collider.radius + other_radius
+            {
+                continue;
+            }
+            if contacts.0.contains(&(asteroid, other)) {
+                touching.insert((asteroid, other));
+                continue;
+            }
+            // Left untouched past the cap, so it is still new on the next tick
+            if new_contacts >= MAX_ASTEROID_CONTACTS_PER_TICK {
+                continue;
+            }
+            new_contacts += 1;
+            touching.insert((asteroid, other));
+            collision_events.send(CollisionEvent {
+                a: asteroid,
+                b: other,
+                point: contact_point(position, collider.radius, other_position, other_radius),
+            });
+        }
+    }
+    contacts.0 = touching;
+}
+
+// Components of an asteroid bouncing off another
+type BouncingAsteroidData<'a> = (&'a mut Transform, &'a mut Velocity, &'a Collider, Option<&'a Mass>);
+
+// System to bounce asteroids that ran into one another apart, trading momentum along the line
+// between them, and push them out of each other down to the slop, the lighter one further
+fn asteroid_bounce_system(
+    mut collision_events: EventReader<CollisionEvent>,        // Overlaps found this tick
+    mut asteroid_query: Query<BouncingAsteroidData, With<Asteroid>>, // Get the asteroids' movement
+    collision_settings: Res<CollisionSettings>,               // Bounce tuning
+    arena: Res<WorldBounds>,                                  // Where the seams are
+) {
+    let mass = |mass: Option<&Mass>| mass.map_or(Mass::default().0, |mass| mass.0).max(f32::EPSILON);
+    for event in collision_events.read() {
+        // Everything else running into a hostile is left to the other systems
+        let Ok([a, b]) = asteroid_query.get_many_mut([event.a, event.b]) else {
+            continue;
+        };
+        let (mut transform_a, mut velocity_a, collider_a, mass_a) = a;
+        let (mut transform_b, mut velocity_b, collider_b, mass_b) = b;
+        let (mass_a, mass_b) = (mass(mass_a), mass(mass_b));
+        let position_a = transform_a.translation.truncate();
+        // An asteroid touching across a seam pushes from that side
+        let position_b = nearest_wrapped(transform_b.translation.truncate(), position_a, arena.half_extents());
+        let (change_a, change_b) = collision_impulse(
+            position_a,
+            velocity_a.as_vec2(),
+            mass_a,
+            position_b,
+            velocity_b.as_vec2(),
+            mass_b,
+            collision_settings.asteroid_restitution,
+        );
+        velocity_a.x += change_a.x;
+        velocity_a.y += change_a.y;
+        velocity_b.x += change_b.x;
+        velocity_b.y += change_b.y;
+
+        // Asteroids right on top of each other are pushed apart sideways
+        let offset = position_a - position_b;
+        let normal = offset.try_normalize().unwrap_or(Vec2::X);
+        let overlap = collider_a.radius + collider_b.radius - offset.length() - ASTEROID_CONTACT_SLOP;
+        if overlap > 0.0 {
+            let push = normal * overlap / (1.0 / mass_a + 1.0 / mass_b);
+            transform_a.translation += (push / mass_a).extend(0.0);
+            transform_b.translation -= (push / mass_b).extend(0.0);
+        }
+    }
+}
+
+// Components of a ship bouncing off a hostile
+type BouncingShipData<'a> = (&'a mut Transform, &'a mut Velocity, &'a Collider, Option<&'a Mass>);
+
+// Components of a hostile a ship bounces off
+type BouncingHostileData<'a> = (&'a Transform, &'a mut Velocity, &'a Collider, Option<&'a Mass>);
+
+// System to damage ships that ran into hostiles, harder the faster they met along the line
+// between them and the heavier the hostile is next to the ship, and bounce the two apart sharing out their momentum; the ship is
+// pushed back out to where the two just touch, so it doesn't drift on through. An invulnerable
+// ship still bounces, the damage it is sent just doesn't land
+fn ship_impact_system(
+    mut collision_events: EventReader<CollisionEvent>,        // Overlaps found this tick
+    mut ship_query: Query<BouncingShipData, (With<Starship>, Without<Hostile>)>, // Get the ships' movement
+    mut hostile_query: Query<BouncingHostileData, (With<Hostile>, Without<Starship>)>, // Get the hostiles' movement
+    config: Res<GameConfig>,                                  // Damage and bounce tuning
+    arena: Res<WorldBounds>,                                  // Where the seams are
+    mut damage_events: EventWriter<DamageEvent>,              // For dealing the damage
+) {
+    let mass = |mass: Option<&Mass>| mass.map_or(Mass::default().0, |mass| mass.0);
+    let collisions = &config.collisions;
+    for event in collision_events.read() {
+        // Hits by projectiles are left to projectile_hit_system
+        let Ok((mut ship_transform, mut ship_velocity, ship_collider, ship_mass)) = ship_query.get_mut(event.a) else {
+            continue;
+        };
+        let Ok((hostile_transform, mut hostile_velocity, hostile_collider, hostile_mass)) = hostile_query.get_mut(event.b) else {
+            continue;
+        };
+        let (ship_mass, hostile_mass) = (mass(ship_mass), mass(hostile_mass));
+        let ship_position = ship_transform.translation.truncate();
+        // A hostile touching the ship across a seam pushes it from that side
+        let hostile_position = nearest_wrapped(hostile_transform.translation.truncate(), ship_position, arena.half_extents());
+        let speed = closing_speed(
+            ship_position,
+            ship_velocity.as_vec2(),
+            hostile_position,
+            hostile_velocity.as_vec2(),
+        );
+        let (ship_change, hostile_change) = collision_impulse(
+            ship_position,
+            ship_velocity.as_vec2(),
+            ship_mass,
+            hostile_position,
+            hostile_velocity.as_vec2(),
+            hostile_mass,
+            collisions.restitution,
+        );
+        ship_velocity.x += ship_change.x * collisions.knockback;
+        ship_velocity.y += ship_change.y * collisions.knockback;
+        hostile_velocity.x += hostile_change.x * collisions.nudge;
+        hostile_velocity.y += hostile_change.y * collisions.nudge;
+
+        // A ship right on top of the hostile is pushed out sideways
+        let offset = ship_position - hostile_position;
+        let overlap = ship_collider.radius + hostile_collider.radius - offset.length();
+        if overlap > 0.0 {
+            ship_transform.translation += (offset.try_normalize().unwrap_or(Vec2::X) * overlap).extend(0.0);
+        }
+
+        // Equal masses share the hit evenly; a heavier hostile hurts more
+        let share = 2.0 * hostile_mass / (ship_mass + hostile_mass).max(f32::EPSILON);
+        let amount = collisions.impact_damage(speed, share);
+        if amount > 0.0 {
+            damage_events.send(DamageEvent {
+                target: event.a,
+                amount,
+                source: DamageSource::Collision,
+            });
+        }
+    }
+}
+
+// System to let projectiles damage the hostiles they hit, using them up once they can pierce no more
+fn projectile_hit_system(
+    mut commands: Commands,                                   // For pooling used up projectiles
+    mut collision_events: EventReader<CollisionEvent>,        // Overlaps found this tick
+    mut projectile_query: Query<&mut Projectile>,             // Get the projectiles that hit
+    mut damage_events: EventWriter<DamageEvent>,              // For dealing the damage
+) {
+    for event in collision_events.read() {
+        // Ships running into things are left to ship_impact_system
+        let Ok(mut projectile) = projectile_query.get_mut(event.a) else {
+            continue;
+        };
+        damage_events.send(DamageEvent {
+            target: event.b,
+            amount: projectile.damage,
+            source: DamageSource::PlayerBullet {
+                owner: projectile.owner,
+            },
+        });
+        projectile.hit.push(event.b);
+        projectile.pierce = projectile.pierce.saturating_sub(1);
+        if projectile.pierce == 0 {
+            release_pooled(&mut commands, event.a);
+        }
+    }
+}
+
+// Components of an entity involved in taking damage
+type DamageTargetData<'a> = (
+    &'a mut Health,
+    Option<&'a mut Shield>,
+    Has<Invulnerable>,
+    Has<Starship>,
+);
+
+// System to deal all the damage asked for this tick
+fn damage_system(
+    mut commands: Commands,                                   // For marking who took the damage
+    mut damage_events: EventReader<DamageEvent>,              // The damage to deal
+    mut target_query: Query<DamageTargetData>,                // Get what can take damage
+    mut stats: ResMut<DamageStats>,                           // Damage taken by the ships
+    mut shield_events: EventWriter<ShieldHitEvent>,           // For showing hits on shields
+    mut taken_events: EventWriter<DamageTakenEvent>,          // For showing the health lost
+) {
+    for event in damage_events.read() {
+        // The target may have been destroyed since the damage was sent
+        let Ok((mut health, mut shield, invulnerable, is_ship)) = target_query.get_mut(event.target) else {
+            continue;
+        };
+        if invulnerable && event.source.blocked_by_invulnerability() {
+            continue;
+        }
+        let health_before = health.current;
+        let absorbed = apply_damage(&mut health, shield.as_deref_mut(), event.amount);
+        if health.current < health_before {
+            taken_events.send(DamageTakenEvent {
+                target: event.target,
+                amount: health_before - health.current,
+            });
+        }
+        if absorbed > 0.0 {
+            shield_events.send(ShieldHitEvent {
+                target: event.target,
+                broken: shield.as_ref().is_some_and(|shield| shield.current <= 0.0),
+            });
+        }
+        // Remember who shot it so the kill is credited to them; other kills score nothing
+        if let DamageSource::PlayerBullet { owner } = event.source {
+            commands.entity(event.target).insert(LastHitBy(owner));
+        }
+        if is_ship {
+            stats.record(event.source, event.amount);
+            // A fresh flash replaces one still fading, so quick hits don't stack up
+            commands.entity(event.target).insert(DamageFlash::default());
+        }
+    }
+}
+
+// System to clear the damage statistics when a new run starts
+fn reset_damage_stats_system(
+    mut new_game_events: EventReader<NewGameEvent>,           // Requests for a new run
+    mut stats: ResMut<DamageStats>,                           // Statistics to clear
+) {
+    if new_game_events.read().count() > 0 {
+        *stats = DamageStats::default();
+    }
+}
+
+// System to recharge shields that have gone long enough without taking damage, up to full
+fn shield_regen_system(
+    time: Res<Time>,                                          // For frame-independent recharging
+    config: Res<GameConfig>,                                  // Recharge delay and rate
+    mode: Res<State<GameMode>>,                               // Kind of run, which decides whether shields recharge
+    mut query: Query<&mut Shield>,                            // Get every shield
+) {
+    let regen = &config.shield_regen;
+    for mut shield in query.iter_mut() {
+        shield.since_hit += time.delta_seconds();
+        if shield.recharging(regen, *mode.get()) {
+            shield.current = (shield.current + regen.rate * time.delta_seconds()).min(shield.max);
+        }
+    }
+}
+
+// System to fade the hit flash on ships back to their normal look
+fn damage_flash_system(
+    mut commands: Commands,                                   // For removing finished flashes
+    time: Res<Time>,                                          // For fading the flash
+    color_scheme: Res<ColorScheme>,                           // Colors of the flash and the ships
+    mut query: Query<(Entity, &mut DamageFlash, &mut TextureAtlasSprite, &mut Transform, &Player)>, // Get flashing ships
+) {
+    for (entity, mut flash, mut sprite, mut transform, player) in query.iter_mut() {
+        flash.timer.tick(time.delta());
+        // Strength of the effect, from 1.0 right after the hit down to 0.0
+        let strength = flash.timer.percent_left();
+        let from = color_scheme.damage_flash.as_rgba_f32();
+        let to = color_scheme.ship(*player).as_rgba_f32();
+        sprite.color = Color::rgba(
+            to[0] + (from[0] - to[0]) * strength,
+            to[1] + (from[1] - to[1]) * strength,
+            to[2] + (from[2] - to[2]) * strength,
+            to[3] + (from[3] - to[3]) * strength,
+        );
+        let scale = SHIP_SCALE * (1.0 + DAMAGE_FLASH_PULSE * strength);
+        transform.scale = Vec3::new(scale, scale, 1.0);
+        if flash.timer.finished() {
+            commands.entity(entity).remove::<DamageFlash>();
+        }
+    }
+}
+
+// Plugin for collision damage
+pub struct CombatPlugin;
+
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Health>()
+            .register_type::<Shield>()
+            .register_type::<Invulnerable>()
+            .register_type::<LastHitBy>()
+            .register_type::<DamageFlash>()
+            .register_type::<DamageStats>()
+            .register_type::<CollisionSettings>()
+            .init_resource::<CollisionSettings>()
+            .init_resource::<ShipContacts>()
+            .init_resource::<AsteroidContacts>()
+            .init_resource::<DamageStats>()
+            .add_event::<CollisionEvent>()
+            .add_event::<DamageEvent>()
+            .add_event::<DamageTakenEvent>()
+            .add_event::<ShieldHitEvent>()
+            // Overlaps are found first, then reacted to; every hit turns into a DamageEvent, dealt
+            // once all of them are known
+            .add_systems(FixedUpdate, (
+                spatial_grid_system,
+                (ship_collision_system, projectile_collision_system, asteroid_collision_system).in_set(CollisionDetectionSet),
+                (ship_impact_system, projectile_hit_system, asteroid_bounce_system),
+                damage_system,
+            ).chain().in_set(GameSet::Collision).run_if(in_state(GameState::Playing)))
+            // Recharged before the hits of the tick land, so a hit stops it straight away
+            .add_systems(FixedUpdate, shield_regen_system.in_set(GameSet::Physics).run_if(in_state(GameState::Playing)))
+            .add_systems(Update, reset_damage_stats_system)
+            // Drawn over the interpolated transform, so the pulse never ends up in the simulation
+            .add_systems(Update, damage_flash_system.after(InterpolationSet).run_if(in_state(GameState::Playing)));
+    }
+}
@@ -0,0 +1,66 @@
+// Combos: kills in quick succession multiply the points they are worth
+use bevy::prelude::*;
+
+use crate::config::ComboConfig;
+use crate::hostiles::HostileDestroySet;
+use crate::session::{GameState, NewGameEvent};
+use crate::GameSet;
+
+// Resource counting the kills of the combo under way; the combo ends once the timer runs out
+// with no kill in between
+#[derive(Reflect, Resource, Default)]
+#[reflect(Resource)]
+pub struct ComboMeter {
+    pub count: u32,    // Kills in the combo so far
+    pub timer: Timer,  // Time left for the next kill
+}
+
+impl ComboMeter {
+    // Count a kill towards the combo and give the multiplier its points get
+    pub fn register_kill(&mut self, config: &ComboConfig) -> u32 {
+        self.count += 1;
+        self.timer = Timer::from_seconds(config.window, TimerMode::Once);
+        config.multiplier(self.count)
+    }
+
+    // Let the time for the next kill run, ending the combo once it is up
+    pub fn tick(&mut self, delta: std::time::Duration) {
+        if self.count > 0 && self.timer.tick(delta).finished() {
+            self.count = 0;
+        }
+    }
+}
+
+// System to run down the time left for the next kill
+fn combo_timer_system(
+    time: Res<Time>,                                          // For the window between kills
+    mut combo: ResMut<ComboMeter>,                            // The combo under way
+) {
+    combo.tick(time.delta());
+}
+
+// System to end the combo with the run it belonged to
+fn combo_reset_system(
+    mut new_game_events: EventReader<NewGameEvent>,           // Requests for a new run
+    mut combo: ResMut<ComboMeter>,                            // The combo to end
+) {
+    if new_game_events.read().count() > 0 {
+        *combo = ComboMeter::default();
+    }
+}
+
+// Plugin for combos
+pub struct ComboPlugin;
+
+impl Plugin for ComboPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ComboMeter>()
+            .init_resource::<ComboMeter>()
+            // Run down before the kills of the tick are counted, so a fresh kill gets its whole window
+            .add_systems(FixedUpdate, combo_timer_system
+                .before(HostileDestroySet)
+                .in_set(GameSet::Cleanup)
+                .run_if(in_state(GameState::Playing)))
+            .add_systems(Update, combo_reset_system);
+    }
+}
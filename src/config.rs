@@ -0,0 +1,460 @@
+// Balance values read from game_config.ron, picked up again whenever the file changes. The web
+// build has no file system to read the file from or watch, so it ignores game_config.ron and
+// plays with the defaults
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use std::error::Error;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::SystemTime;
+
+use crate::garage::{GarageSettings, Upgrades, apply_upgrades};
+use crate::player::{Engine, Loadout, ShipClass, Starship};
+use crate::session::GameMode;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::storage::asset_path;
+use crate::weapons::{Weapon, WeaponType};
+
+// Key reading the file again straight away
+#[cfg(not(target_arch = "wasm32"))]
+const RELOAD_KEY: KeyCode = KeyCode::F6;
+
+// Seconds between two looks at whether the file was changed
+#[cfg(not(target_arch = "wasm32"))]
+const POLL_INTERVAL: f32 = 1.0;
+
+// Starting values of the ships of every class
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct ShipConfig {
+    pub scout: Loadout,  // All-rounder
+    pub tank: Loadout,   // Slow and heavy with a big tank
+    pub racer: Loadout,  // Quick and nimble on a small tank
+}
+
+impl Default for ShipConfig {
+    fn default() -> Self {
+        Self {
+            scout: ShipClass::Scout.loadout(),
+            tank: ShipClass::Tank.loadout(),
+            racer: ShipClass::Racer.loadout(),
+        }
+    }
+}
+
+impl ShipConfig {
+    // Starting values for ships of a class
+    pub fn loadout(&self, ship_class: ShipClass) -> Loadout {
+        match ship_class {
+            ShipClass::Scout => self.scout,
+            ShipClass::Tank => self.tank,
+            ShipClass::Racer => self.racer,
+        }
+    }
+}
+
+// How fast the weapons shoot
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct WeaponConfig {
+    pub standard_speed: f32,  // Muzzle speed of the basic cannon's shots
+    pub boosted_speed: f32,   // Muzzle speed of the boosted cannon's shots
+}
+
+impl Default for WeaponConfig {
+    fn default() -> Self {
+        Self {
+            standard_speed: 400.0,
+            boosted_speed: 600.0,
+        }
+    }
+}
+
+impl WeaponConfig {
+    // Muzzle speed of the projectiles fired by a weapon
+    pub fn projectile_speed(&self, weapon_type: WeaponType) -> f32 {
+        match weapon_type {
+            WeaponType::Standard => self.standard_speed,
+            WeaponType::Boosted => self.boosted_speed,
+            // The beam fires no projectiles
+            WeaponType::Beam => 0.0,
+        }
+    }
+}
+
+// How asteroids appear
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct AsteroidConfig {
+    pub spawn_interval: f32,  // Seconds between two spawns at the start of a run
+    pub max_count: usize,     // No spawns while this many asteroids are alive
+    pub min_speed: f32,       // Slowest an asteroid drifts in, before the difficulty ramp
+    pub max_speed: f32,       // Fastest an asteroid drifts in, before the difficulty ramp
+    pub min_radius: f32,      // Smallest asteroid
+    pub max_radius: f32,      // Biggest asteroid
+    pub emp_chance: f64,      // Chance of an EMP mine coming in instead of an asteroid
+    pub safe_distance: f32,   // Closest to a ship an asteroid or mine comes in
+}
+
+impl Default for AsteroidConfig {
+    fn default() -> Self {
+        Self {
+            spawn_interval: 2.0,
+            max_count: 12,
+            min_speed: 40.0,
+            max_speed: 120.0,
+            min_radius: 15.0,
+            max_radius: 40.0,
+            emp_chance: 0.1,
+            safe_distance: 200.0,
+        }
+    }
+}
+
+// How the game ramps up during a run; the game has no waves, so this is what scales it
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct DifficultyConfig {
+    pub ramp_time: f32,       // Seconds for about two thirds of the full ramp to kick in
+    pub max_spawn_rate: f32,  // Most often hostiles spawn, as a multiple of the starting rate
+    pub max_speed: f32,       // Fastest hostiles fly, as a multiple of their starting speed
+}
+
+impl Default for DifficultyConfig {
+    fn default() -> Self {
+        Self {
+            ramp_time: 180.0,
+            max_spawn_rate: 3.0,
+            max_speed: 1.5,
+        }
+    }
+}
+
+// How quick kills build up a combo, and what it multiplies the points by
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct ComboConfig {
+    pub window: f32,          // Seconds after a kill for the next one to keep the combo going
+    pub tier_kills: u32,      // Kills in a combo for each step up of the multiplier: x2 at this many, x3 at twice as many
+    pub max_multiplier: u32,  // Highest the multiplier goes
+}
+
+impl Default for ComboConfig {
+    fn default() -> Self {
+        Self {
+            window: 2.0,
+            tier_kills: 5,
+            max_multiplier: 5,
+        }
+    }
+}
+
+impl ComboConfig {
+    // What the points of a kill are multiplied by, with this many kills in the combo
+    pub fn multiplier(&self, count: u32) -> u32 {
+        (1 + count.checked_div(self.tier_kills).unwrap_or(0)).min(self.max_multiplier.max(1))
+    }
+}
+
+// How ships bounce off what they run into, and how much it hurts
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct CollisionConfig {
+    pub restitution: f32,       // How bouncy a ship's collision is, from 0.0 (no bounce) to 1.0 (perfectly elastic)
+    pub knockback: f32,         // How much of its bounce a ship takes, 1.0 for all of it
+    pub nudge: f32,             // How much of its bounce the hostile a ship hits takes, 1.0 for all of it
+    pub damage_per_speed: f32,  // Damage per pixel/second of closing speed above the threshold, between bodies of equal mass
+    pub min_damage_speed: f32,  // Closing speed in pixels/second a collision has to beat to hurt at all
+}
+
+impl Default for CollisionConfig {
+    fn default() -> Self {
+        Self {
+            restitution: 0.5,
+            knockback: 1.0,
+            nudge: 0.5,
+            damage_per_speed: 0.2,
+            min_damage_speed: 30.0,
+        }
+    }
+}
+
+impl CollisionConfig {
+    // Damage a ship takes running into something at the given closing speed, where the other
+    // body weighs the given multiple of an equal share of the two masses; gentle nudges are free
+    pub fn impact_damage(&self, closing_speed: f32, mass_share: f32) -> f32 {
+        (closing_speed - self.min_damage_speed).max(0.0) * self.damage_per_speed * mass_share
+    }
+}
+
+// How the safe zone closes in over a run, for a battle royale where staying out of it hurts
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct SafeZoneConfig {
+    pub enabled: bool,            // Whether there is a safe zone at all
+    pub start_radius: f32,        // Radius of the zone when a run starts
+    pub min_radius: f32,          // Smallest the zone gets
+    pub shrink_rate: f32,         // Pixels per second the radius shrinks by
+    pub damage_per_second: f32,   // Damage a ship outside the zone takes every second
+}
+
+impl Default for SafeZoneConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start_radius: 600.0,
+            min_radius: 120.0,
+            shrink_rate: 4.0,
+            damage_per_second: 10.0,
+        }
+    }
+}
+
+// How destroyed hostiles throw off debris that hurts ships flying through it
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct DebrisConfig {
+    pub chance: f64,       // Chance of a destroyed hostile throwing off debris at all
+    pub min_count: u32,    // Fewest pieces thrown off
+    pub max_count: u32,    // Most pieces thrown off
+    pub speed: f32,        // How fast the pieces fly off, in pixels/second
+    pub lifetime: f32,     // Seconds a piece lasts
+    pub damage: f32,       // Damage a piece deals to a ship it hits
+}
+
+impl Default for DebrisConfig {
+    fn default() -> Self {
+        Self {
+            chance: 0.3,
+            min_count: 2,
+            max_count: 3,
+            speed: 220.0,
+            lifetime: 1.5,
+            damage: 5.0,
+        }
+    }
+}
+
+// How ships' shields recharge once they have gone a while without being hit
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct ShieldRegenConfig {
+    pub arcade: bool,    // Whether shields recharge on their own in arcade runs
+    pub practice: bool,  // Whether shields recharge on their own on the practice range
+    pub delay: f32,      // Seconds without taking damage before the shield starts recharging
+    pub rate: f32,       // Shield strength recharged per second
+}
+
+impl ShieldRegenConfig {
+    // Whether shields recharge in the given kind of run
+    pub fn enabled_in(&self, mode: GameMode) -> bool {
+        match mode {
+            GameMode::Arcade => self.arcade,
+            GameMode::Practice => self.practice,
+        }
+    }
+}
+
+impl Default for ShieldRegenConfig {
+    fn default() -> Self {
+        Self {
+            arcade: true,
+            practice: true,
+            delay: 3.0,
+            rate: 8.0,
+        }
+    }
+}
+
+// When the HUD warns of a tank running dry
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct LowFuelConfig {
+    pub threshold: f32,      // Share of a full tank below which the warning is on
+    pub beep_interval: f32,  // Seconds between two beeps, and two blinks of the fuel bar
+}
+
+impl Default for LowFuelConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.2,
+            beep_interval: 0.8,
+        }
+    }
+}
+
+// How big the play area is; a side left at zero takes the window's size, so the default arena
+// is the window and a bigger one has the cameras following the ships around it
+#[derive(Reflect, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct WorldConfig {
+    pub width: f32,   // Width of the play area in pixels, or zero for the window's
+    pub height: f32,  // Height of the play area in pixels, or zero for the window's
+}
+
+impl WorldConfig {
+    // Size of the play area in a window of the given size
+    pub fn size(&self, window_size: Vec2) -> Vec2 {
+        Vec2::new(
+            if self.width > 0.0 { self.width } else { window_size.x },
+            if self.height > 0.0 { self.height } else { window_size.y },
+        )
+    }
+}
+
+// Resource holding the balance values; spawners read it when something new appears, so an
+// edited file changes the next ship or asteroid without recompiling
+#[derive(Reflect, Resource, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Debug)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct GameConfig {
+    pub ships: ShipConfig,            // Starting values of the ships
+    pub weapons: WeaponConfig,        // How fast the weapons shoot
+    pub asteroids: AsteroidConfig,    // How asteroids appear
+    pub difficulty: DifficultyConfig, // How the game ramps up
+    pub combo: ComboConfig,           // How kills build up a combo
+    pub collisions: CollisionConfig,  // How ships bounce off what they hit
+    pub safe_zone: SafeZoneConfig,    // How the safe zone closes in
+    pub low_fuel: LowFuelConfig,      // When the low fuel warning comes on
+    pub shield_regen: ShieldRegenConfig, // How shields recharge between hits
+    pub debris: DebrisConfig,         // What explosions throw off
+    pub world: WorldConfig,           // How big the play area is
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl GameConfig {
+    // Read the config from a RON file, missing fields taking their defaults
+    fn load(path: &PathBuf) -> Result<Self, Box<dyn Error>> {
+        let config: Self = ron::from_str(&fs::read_to_string(path)?)?;
+        // The spawners pick values between these, so an empty range would stop the game
+        let asteroids = &config.asteroids;
+        if asteroids.min_speed > asteroids.max_speed || asteroids.min_radius > asteroids.max_radius {
+            return Err("asteroid minimums must not be above their maximums".into());
+        }
+        if config.debris.min_count > config.debris.max_count {
+            return Err("the debris count minimum must not be above its maximum".into());
+        }
+        if !(config.world.width >= 0.0 && config.world.height >= 0.0) {
+            return Err("the world size must not be negative".into());
+        }
+        Ok(config)
+    }
+
+    // Read the config, falling back to the defaults when there is no file or it can't be read
+    fn load_or_default(path: &PathBuf) -> Self {
+        if !path.exists() {
+            return Self::default();
+        }
+        Self::load(path).unwrap_or_else(|err| {
+            error!("Failed to read the game config from {}, using the defaults: {}", path.display(), err);
+            Self::default()
+        })
+    }
+}
+
+// Where the config lives: next to the game's other assets, where it is easy to find and edit
+#[cfg(not(target_arch = "wasm32"))]
+fn config_path() -> PathBuf {
+    asset_path("game_config.ron")
+}
+
+// When the file was last changed, if it can be told
+#[cfg(not(target_arch = "wasm32"))]
+fn modified(path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+// Resource keeping an eye on the file for changes
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Resource)]
+struct ConfigWatcher {
+    poll: Timer,                      // Time until the next look at the file
+    modified: Option<SystemTime>,     // When the file was changed as of the last read
+}
+
+// System to read the config again when the file changes or its key is pressed; a file that
+// doesn't read keeps the config as it was
+#[cfg(not(target_arch = "wasm32"))]
+fn config_reload_system(
+    keyboard_input: Res<Input<KeyCode>>,                      // For reloading by hand
+    time: Res<Time<Real>>,                                    // For polling the file, even while paused
+    mut watcher: ResMut<ConfigWatcher>,                       // When the file was last read
+    mut config: ResMut<GameConfig>,                           // The config to replace
+) {
+    let path = config_path();
+    let forced = keyboard_input.just_pressed(RELOAD_KEY);
+    if !forced && !watcher.poll.tick(time.delta()).just_finished() {
+        return;
+    }
+    let modified = modified(&path);
+    if !forced && modified == watcher.modified {
+        return;
+    }
+    watcher.modified = modified;
+    match GameConfig::load(&path) {
+        Ok(loaded) => {
+            info!("Reloaded the game config from {}", path.display());
+            if *config != loaded {
+                *config = loaded;
+            }
+        }
+        Err(err) => error!("Failed to reload the game config from {}, keeping the current one: {}", path.display(), err),
+    }
+}
+
+// Components of a live ship the config changes
+type ConfigTargetData<'a> = (&'a mut Starship, &'a mut Engine, &'a mut Weapon);
+
+// System to hand the ships already flying a reloaded config's loadout for their class, along
+// with the upgrades bought. Edits elsewhere in the config leave the ships alone, and only the
+// handling changes: what is left in the tank stays, cut down to a smaller tank if need be
+fn config_ships_system(
+    config: Res<GameConfig>,                                  // The new config
+    ship_class: Res<ShipClass>,                               // Class the ships fly as
+    mut loadout: ResMut<Loadout>,                             // Starting values of the ships
+    upgrades: Res<Upgrades>,                                  // Levels bought
+    garage_settings: Res<GarageSettings>,                     // Improvement per level
+    mut applied: Local<Option<Loadout>>,                      // Loadout the config gave last time
+    mut ship_query: Query<ConfigTargetData>,                  // Get the live ships
+) {
+    let new_loadout = config.ships.loadout(*ship_class);
+    let previous = applied.replace(new_loadout);
+    // The loadout already started from the config the game was launched with
+    if config.is_added() || previous == Some(new_loadout) {
+        return;
+    }
+    *loadout = new_loadout;
+    for (mut starship, mut engine, mut weapon) in ship_query.iter_mut() {
+        let fuel = engine.fuel;
+        apply_upgrades(&upgrades, &garage_settings, &loadout, &mut starship, &mut engine, &mut weapon);
+        engine.fuel = fuel.min(engine.max_fuel);
+        weapon.cooldown.set_duration(std::time::Duration::from_secs_f32(loadout.fire_cooldown));
+    }
+}
+
+// Plugin for the balance config file
+pub struct ConfigPlugin;
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GameConfig>()
+            .add_systems(PreUpdate, config_ships_system.run_if(resource_changed::<GameConfig>()));
+        // Loaded straight away so the first ships already start from it
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let path = config_path();
+            app.insert_resource(GameConfig::load_or_default(&path))
+                .insert_resource(ConfigWatcher {
+                    poll: Timer::from_seconds(POLL_INTERVAL, TimerMode::Repeating),
+                    modified: modified(&path),
+                })
+                .add_systems(PreUpdate, config_reload_system.before(config_ships_system));
+        }
+        #[cfg(target_arch = "wasm32")]
+        app.insert_resource(GameConfig::default());
+    }
+}
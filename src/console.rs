@@ -0,0 +1,575 @@
+// A console for typing developer commands, part of the developer tools. Every command is its own
+// event: the console looks its name up in a registry, parses the arguments into it and sends it,
+// and whatever handles it replies in the scrollback. A feature adds a command of its own by
+// implementing ConsoleCommand and calling add_console_command
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_inspector_egui::bevy_egui::{EguiContexts, EguiPlugin};
+use bevy_inspector_egui::egui;
+use rand::Rng;
+use std::str::FromStr;
+
+use crate::combat::{Health, LastHitBy, Shield};
+use crate::debug::debug_mode;
+use crate::effects::spawn_particle;
+use crate::hostiles::{Hostile, spawn_asteroid, spawn_emp_mine, spawn_enemy};
+use crate::input::PlayerInputSet;
+use crate::physics::{InterpolationSet, PreviousTransform, Velocity, WorldBounds};
+use crate::player::{Engine, Starship};
+use crate::session::{GameRng, GameState};
+
+// Key that opens and closes the console
+const CONSOLE_KEY: KeyCode = KeyCode::Grave;
+
+// Lines of scrollback kept before the oldest drop off
+const SCROLLBACK_LINES: usize = 200;
+
+// Most hostiles one spawn command brings in
+const MAX_SPAWN_COUNT: u32 = 100;
+
+// Seconds every particle of the stress test lasts, so the rate times this is how many are alive
+const STRESS_PARTICLE_LIFETIME: f32 = 1.0;
+
+// How fast the stress test's particles fly out, in pixels/second
+const STRESS_PARTICLE_SPEED: f32 = 150.0;
+
+// A command the console can run, parsed from the words typed after its name
+pub trait ConsoleCommand: Event + Sized {
+    const NAME: &'static str;   // First word of the command
+    const USAGE: &'static str;  // How to type it, shown by help and with a bad argument
+
+    // Read the command from its arguments, or say what is wrong with them
+    fn parse(args: &[&str]) -> Result<Self, String>;
+}
+
+// Parse one argument, naming it in the error if it is missing or not what was expected
+pub fn parse_arg<T: FromStr>(args: &[&str], index: usize, name: &str) -> Result<T, String> {
+    let arg = args.get(index).ok_or_else(|| format!("missing {}", name))?;
+    arg.parse().map_err(|_| format!("'{}' is not a valid {}", arg, name))
+}
+
+// A registered command: how to type it, and how to turn its arguments into its event
+struct RegisteredCommand {
+    usage: &'static str,
+    dispatch: fn(&[&str], &mut Commands) -> Result<(), String>,
+}
+
+// Resource holding every command the console knows, by name
+#[derive(Resource, Default)]
+pub struct ConsoleRegistry {
+    commands: HashMap<&'static str, RegisteredCommand>,
+}
+
+impl ConsoleRegistry {
+    // Parse a command and send it as its event, to be handled once the commands are applied
+    fn dispatch<C: ConsoleCommand>(args: &[&str], commands: &mut Commands) -> Result<(), String> {
+        let command = C::parse(args)?;
+        commands.add(move |world: &mut World| world.send_event(command));
+        Ok(())
+    }
+
+    // Make a command available under its name, replacing one registered before
+    pub fn register<C: ConsoleCommand>(&mut self) {
+        self.commands.insert(C::NAME, RegisteredCommand {
+            usage: C::USAGE,
+            dispatch: Self::dispatch::<C>,
+        });
+    }
+
+    // How to type every command, sorted by name
+    fn usages(&self) -> Vec<&'static str> {
+        let mut usages: Vec<_> = self.commands.values().map(|command| command.usage).collect();
+        usages.sort_unstable();
+        usages
+    }
+}
+
+// Adding console commands while building the app
+pub trait ConsoleAppExt {
+    fn add_console_command<C: ConsoleCommand>(&mut self) -> &mut Self;
+}
+
+impl ConsoleAppExt for App {
+    fn add_console_command<C: ConsoleCommand>(&mut self) -> &mut Self {
+        self.add_event::<C>();
+        self.world.get_resource_or_insert_with(ConsoleRegistry::default).register::<C>();
+        self
+    }
+}
+
+// Resource holding the console's state and what it has printed
+#[derive(Resource, Default)]
+pub struct Console {
+    open: bool,           // Whether the console is shown
+    input: String,        // The line being typed
+    lines: Vec<String>,   // Scrollback, oldest first
+}
+
+impl Console {
+    // Print a line to the scrollback
+    pub fn reply(&mut self, line: impl Into<String>) {
+        self.lines.push(line.into());
+        if self.lines.len() > SCROLLBACK_LINES {
+            let excess = self.lines.len() - SCROLLBACK_LINES;
+            self.lines.drain(..excess);
+        }
+    }
+}
+
+// Run condition for systems that only matter while the console is shown
+fn console_open(console: Res<Console>) -> bool {
+    console.open
+}
+
+// System to open and close the console
+fn console_toggle_system(
+    keyboard_input: Res<Input<KeyCode>>,                      // Access the keyboard input
+    mut console: ResMut<Console>,                             // The console to show or hide
+) {
+    if keyboard_input.just_pressed(CONSOLE_KEY) {
+        console.open = !console.open;
+    }
+}
+
+// System to keep what is typed into the console from also flying the ship, pausing or firing the
+// other developer keys; egui reads the keyboard events themselves, so typing still works
+fn console_capture_system(mut keyboard_input: ResMut<Input<KeyCode>>) {
+    keyboard_input.reset_all();
+}
+
+// Run one line typed into the console, or say why it can't be run
+fn run_line(line: &str, registry: &ConsoleRegistry, commands: &mut Commands, console: &mut Console) {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let Some((&name, args)) = words.split_first() else {
+        return;
+    };
+    console.reply(format!("> {}", line.trim()));
+    if name == "help" {
+        console.reply("Commands:");
+        for usage in registry.usages() {
+            console.reply(format!("  {}", usage));
+        }
+        return;
+    }
+    let Some(command) = registry.commands.get(name) else {
+        console.reply(format!("Unknown command '{}', try help", name));
+        return;
+    };
+    if let Err(err) = (command.dispatch)(args, commands) {
+        console.reply(format!("{}; usage: {}", err, command.usage));
+    }
+}
+
+// System to draw the console, and run each line when Enter is pressed
+fn console_ui_system(
+    mut commands: Commands,                                   // For sending the commands
+    mut contexts: EguiContexts,                               // For drawing the console
+    mut console: ResMut<Console>,                             // Scrollback and the line typed
+    registry: Res<ConsoleRegistry>,                           // The commands there are
+) {
+    let console = &mut *console;
+    let mut submitted = None;
+    egui::Window::new("Console").default_width(420.0).show(contexts.ctx_mut(), |ui| {
+        egui::ScrollArea::vertical()
+            .max_height(240.0)
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for line in &console.lines {
+                    ui.monospace(line);
+                }
+            });
+        let response = ui.add(egui::TextEdit::singleline(&mut console.input).desired_width(f32::INFINITY));
+        // The key opening the console types itself too
+        console.input.retain(|c| c != '`');
+        if response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+            submitted = Some(std::mem::take(&mut console.input));
+        }
+        // Straight back to typing after a line, and on opening
+        response.request_focus();
+    });
+    if let Some(line) = submitted {
+        run_line(&line, &registry, &mut commands, console);
+    }
+}
+
+// What the spawn command can bring in
+#[derive(Clone, Copy, Debug)]
+pub enum SpawnKind {
+    Asteroid,
+    Enemy,
+    Mine,
+}
+
+// Command bringing hostiles in at random places: spawn asteroid 3
+#[derive(Event, Clone, Copy, Debug)]
+pub struct SpawnCommand {
+    pub kind: SpawnKind,  // What to bring in
+    pub count: u32,       // How many
+}
+
+impl ConsoleCommand for SpawnCommand {
+    const NAME: &'static str = "spawn";
+    const USAGE: &'static str = "spawn asteroid|enemy|mine [count]";
+
+    fn parse(args: &[&str]) -> Result<Self, String> {
+        let kind = match args.first().copied() {
+            Some("asteroid") => SpawnKind::Asteroid,
+            Some("enemy") => SpawnKind::Enemy,
+            Some("mine") => SpawnKind::Mine,
+            Some(other) => return Err(format!("'{}' is not something to spawn", other)),
+            None => return Err("missing what to spawn".to_string()),
+        };
+        let count = if args.len() > 1 { parse_arg(args, 1, "count")? } else { 1 };
+        Ok(Self {
+            kind,
+            count: count.min(MAX_SPAWN_COUNT),
+        })
+    }
+}
+
+// What the give command tops up
+#[derive(Clone, Copy, Debug)]
+pub enum GiveKind {
+    Fuel,
+    Health,
+    Shield,
+}
+
+// Command topping up every ship, up to what it can hold: give fuel 100
+#[derive(Event, Clone, Copy, Debug)]
+pub struct GiveCommand {
+    pub kind: GiveKind,  // What to top up
+    pub amount: f32,     // How much
+}
+
+impl ConsoleCommand for GiveCommand {
+    const NAME: &'static str = "give";
+    const USAGE: &'static str = "give fuel|health|shield <amount>";
+
+    fn parse(args: &[&str]) -> Result<Self, String> {
+        let kind = match args.first().copied() {
+            Some("fuel") => GiveKind::Fuel,
+            Some("health") => GiveKind::Health,
+            Some("shield") => GiveKind::Shield,
+            Some(other) => return Err(format!("'{}' is not something to give", other)),
+            None => return Err("missing what to give".to_string()),
+        };
+        Ok(Self {
+            kind,
+            amount: parse_arg(args, 1, "amount")?,
+        })
+    }
+}
+
+// What the set command tunes
+#[derive(Clone, Copy, Debug)]
+pub enum SetStat {
+    Thrust,
+    Strafe,
+    MaxSpeed,
+    Rotation,
+}
+
+// Command tuning every ship in play: set thrust 200
+#[derive(Event, Clone, Copy, Debug)]
+pub struct SetCommand {
+    pub stat: SetStat,  // What to tune
+    pub value: f32,     // Its new value
+}
+
+impl ConsoleCommand for SetCommand {
+    const NAME: &'static str = "set";
+    const USAGE: &'static str = "set thrust|strafe|max_speed|rotation <value>";
+
+    fn parse(args: &[&str]) -> Result<Self, String> {
+        let stat = match args.first().copied() {
+            Some("thrust") => SetStat::Thrust,
+            Some("strafe") => SetStat::Strafe,
+            Some("max_speed") => SetStat::MaxSpeed,
+            Some("rotation") => SetStat::Rotation,
+            Some(other) => return Err(format!("'{}' is not something to set", other)),
+            None => return Err("missing what to set".to_string()),
+        };
+        let value: f32 = parse_arg(args, 1, "value")?;
+        if !value.is_finite() || value < 0.0 {
+            return Err(format!("'{}' is not a valid value", value));
+        }
+        Ok(Self { stat, value })
+    }
+}
+
+// Command destroying every hostile, scoring nothing: kill all
+#[derive(Event, Clone, Copy, Debug)]
+pub struct KillCommand;
+
+impl ConsoleCommand for KillCommand {
+    const NAME: &'static str = "kill";
+    const USAGE: &'static str = "kill all";
+
+    fn parse(args: &[&str]) -> Result<Self, String> {
+        match args {
+            ["all"] => Ok(Self),
+            _ => Err("only 'kill all' is supported".to_string()),
+        }
+    }
+}
+
+// Command moving every ship to a point, at rest: tp 100 -50
+#[derive(Event, Clone, Copy, Debug)]
+pub struct TeleportCommand(pub Vec2);
+
+impl ConsoleCommand for TeleportCommand {
+    const NAME: &'static str = "tp";
+    const USAGE: &'static str = "tp <x> <y>";
+
+    fn parse(args: &[&str]) -> Result<Self, String> {
+        let position = Vec2::new(parse_arg(args, 0, "x")?, parse_arg(args, 1, "y")?);
+        if !position.is_finite() {
+            return Err("the position must be finite".to_string());
+        }
+        Ok(Self(position))
+    }
+}
+
+// Command restarting the game's random numbers from a seed: seed 42
+#[derive(Event, Clone, Copy, Debug)]
+pub struct SeedCommand(pub u64);
+
+impl ConsoleCommand for SeedCommand {
+    const NAME: &'static str = "seed";
+    const USAGE: &'static str = "seed <number>";
+
+    fn parse(args: &[&str]) -> Result<Self, String> {
+        Ok(Self(parse_arg(args, 0, "seed")?))
+    }
+}
+
+// Command spraying particles out of the middle of the arena, to see how the game holds up under
+// them: stress 5000, or stress 0 to stop
+#[derive(Event, Clone, Copy, Debug)]
+pub struct StressCommand(pub f32);
+
+impl ConsoleCommand for StressCommand {
+    const NAME: &'static str = "stress";
+    const USAGE: &'static str = "stress <particles per second>";
+
+    fn parse(args: &[&str]) -> Result<Self, String> {
+        let rate: f32 = parse_arg(args, 0, "rate")?;
+        if !rate.is_finite() || rate < 0.0 {
+            return Err(format!("'{}' is not a valid rate", rate));
+        }
+        Ok(Self(rate))
+    }
+}
+
+// Resource holding the stress test running
+#[derive(Resource, Default)]
+struct StressTest {
+    rate: f32,     // Particles a second, 0.0 when off
+    owed: f32,     // Particles due but not spawned yet, from rates that don't divide into frames
+    spawned: u32,  // Particles spawned so far, for spreading them all around
+}
+
+// Run condition for the stress test
+fn stress_running(stress: Res<StressTest>) -> bool {
+    stress.rate > 0.0
+}
+
+// System to handle the spawn command
+fn spawn_command_system(
+    mut commands: Commands,                                   // For spawning hostiles
+    mut spawn_events: EventReader<SpawnCommand>,              // Spawn commands typed
+    arena: Res<WorldBounds>,                                  // Where there is room
+    mut rng: ResMut<GameRng>,                                 // For random positions and sizes
+    mut console: ResMut<Console>,                             // For replying
+) {
+    for event in spawn_events.read() {
+        for _ in 0..event.count {
+            let position = Vec2::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)) * arena.half_extents();
+            match event.kind {
+                SpawnKind::Asteroid => {
+                    let velocity = Vec2::from_angle(rng.gen_range(0.0..std::f32::consts::TAU)) * rng.gen_range(40.0..120.0);
+                    spawn_asteroid(&mut commands, position, velocity, rng.gen_range(15.0..40.0));
+                }
+                SpawnKind::Enemy => {
+                    spawn_enemy(&mut commands, position);
+                }
+                SpawnKind::Mine => {
+                    spawn_emp_mine(&mut commands, position, Vec2::ZERO);
+                }
+            }
+        }
+        console.reply(format!("Spawned {} {:?}", event.count, event.kind));
+    }
+}
+
+// System to handle the give command
+fn give_command_system(
+    mut give_events: EventReader<GiveCommand>,                // Give commands typed
+    mut ship_query: Query<(&mut Engine, &mut Health, Option<&mut Shield>), With<Starship>>, // Get the ships
+    mut console: ResMut<Console>,                             // For replying
+) {
+    for event in give_events.read() {
+        for (mut engine, mut health, shield) in ship_query.iter_mut() {
+            match event.kind {
+                GiveKind::Fuel => engine.fuel = (engine.fuel + event.amount).clamp(0.0, engine.max_fuel),
+                GiveKind::Health => health.current = (health.current + event.amount).clamp(0.0, health.max),
+                GiveKind::Shield => {
+                    if let Some(mut shield) = shield {
+                        shield.current = (shield.current + event.amount).clamp(0.0, shield.max);
+                    }
+                }
+            }
+        }
+        console.reply(format!("Gave {} {:?}", event.amount, event.kind));
+    }
+}
+
+// System to handle the set command
+fn set_command_system(
+    mut set_events: EventReader<SetCommand>,                  // Set commands typed
+    mut ship_query: Query<(&mut Engine, &mut Starship)>,      // Get the ships
+    mut console: ResMut<Console>,                             // For replying
+) {
+    for event in set_events.read() {
+        for (mut engine, mut starship) in ship_query.iter_mut() {
+            match event.stat {
+                SetStat::Thrust => engine.thrust = event.value,
+                SetStat::Strafe => engine.strafe_thrust = event.value,
+                SetStat::MaxSpeed => starship.max_speed = event.value,
+                SetStat::Rotation => starship.rotation_speed = event.value,
+            }
+        }
+        console.reply(format!("Set {:?} to {}", event.stat, event.value));
+    }
+}
+
+// System to handle the kill command; hostiles out of health are cleared away like any other, and
+// with the last hit forgotten nobody scores for them
+fn kill_command_system(
+    mut commands: Commands,                                   // For forgetting the last hits
+    mut kill_events: EventReader<KillCommand>,                // Kill commands typed
+    mut hostile_query: Query<(Entity, &mut Health), With<Hostile>>, // Get every hostile
+    mut console: ResMut<Console>,                             // For replying
+) {
+    for _ in kill_events.read() {
+        let mut count = 0;
+        for (entity, mut health) in hostile_query.iter_mut() {
+            health.current = 0.0;
+            commands.entity(entity).remove::<LastHitBy>();
+            count += 1;
+        }
+        console.reply(format!("Killed {} hostiles", count));
+    }
+}
+
+// Components of a ship moved by the tp command
+type TeleportData<'a> = (&'a mut Transform, &'a mut Velocity, Option<&'a mut PreviousTransform>);
+
+// System to handle the tp command; the ships jump straight there instead of being drawn
+// sliding across from where they were
+fn teleport_command_system(
+    mut teleport_events: EventReader<TeleportCommand>,        // Teleport commands typed
+    mut ship_query: Query<TeleportData, With<Starship>>,      // Get the ships
+    mut console: ResMut<Console>,                             // For replying
+) {
+    for event in teleport_events.read() {
+        for (mut transform, mut velocity, interpolation) in ship_query.iter_mut() {
+            transform.translation = event.0.extend(transform.translation.z);
+            *velocity = Velocity::default();
+            if let Some(mut interpolation) = interpolation {
+                *interpolation = PreviousTransform {
+                    previous: *transform,
+                    simulated: *transform,
+                    blended: false,
+                };
+            }
+        }
+        console.reply(format!("Teleported to {}", event.0));
+    }
+}
+
+// System to handle the seed command
+fn seed_command_system(
+    mut seed_events: EventReader<SeedCommand>,                // Seed commands typed
+    mut rng: ResMut<GameRng>,                                 // The generator to restart
+    mut console: ResMut<Console>,                             // For replying
+) {
+    for event in seed_events.read() {
+        rng.reseed(event.0);
+        console.reply(format!("Seeded the game's random numbers with {}", event.0));
+    }
+}
+
+// System to handle the stress command
+fn stress_command_system(
+    mut stress_events: EventReader<StressCommand>,            // Stress commands typed
+    mut stress: ResMut<StressTest>,                           // The test to start or stop
+    mut console: ResMut<Console>,                             // For replying
+) {
+    for event in stress_events.read() {
+        stress.rate = event.0;
+        stress.owed = 0.0;
+        if event.0 > 0.0 {
+            console.reply(format!("Spawning {} particles a second", event.0));
+        } else {
+            console.reply("Stress test stopped");
+        }
+    }
+}
+
+// System to spray the stress test's particles; they fly out evenly all around, so they take
+// nothing from the game's random numbers
+fn stress_test_system(
+    mut commands: Commands,                                   // For spawning the particles
+    time: Res<Time>,                                          // For the rate
+    mut stress: ResMut<StressTest>,                           // The test running
+) {
+    stress.owed += stress.rate * time.delta_seconds();
+    while stress.owed >= 1.0 {
+        stress.owed -= 1.0;
+        stress.spawned = stress.spawned.wrapping_add(1);
+        // Turning by the golden angle every particle keeps them from bunching up
+        let angle = stress.spawned as f32 * 2.399_963;
+        let velocity = Vec2::from_angle(angle) * STRESS_PARTICLE_SPEED;
+        spawn_particle(&mut commands, Vec2::ZERO, velocity, Color::CYAN, 2.0, STRESS_PARTICLE_LIFETIME);
+    }
+}
+
+// Plugin for the developer console
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugins(EguiPlugin);
+        }
+        app.init_resource::<Console>()
+            .init_resource::<ConsoleRegistry>()
+            .init_resource::<StressTest>()
+            .add_console_command::<SpawnCommand>()
+            .add_console_command::<GiveCommand>()
+            .add_console_command::<SetCommand>()
+            .add_console_command::<KillCommand>()
+            .add_console_command::<TeleportCommand>()
+            .add_console_command::<SeedCommand>()
+            .add_console_command::<StressCommand>()
+            // Ahead of everything reading the keyboard, so typing doesn't reach the game
+            .add_systems(PreUpdate, (
+                console_toggle_system.run_if(debug_mode),
+                console_capture_system.run_if(console_open),
+            ).chain().after(InputSystem).before(PlayerInputSet))
+            .add_systems(Update, console_ui_system.run_if(debug_mode.and_then(console_open)))
+            .add_systems(Update, (
+                spawn_command_system,
+                give_command_system,
+                set_command_system,
+                kill_command_system,
+                teleport_command_system.before(InterpolationSet),
+                seed_command_system,
+                stress_command_system,
+            ))
+            .add_systems(Update, stress_test_system.run_if(stress_running).run_if(in_state(GameState::Playing)));
+    }
+}
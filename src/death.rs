@@ -0,0 +1,114 @@
+// The death cam: once the last ship is lost, time slows and the cameras close in on its explosion
+// for a moment before the game-over screen comes up
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+
+use crate::camera::PlayerCamera;
+use crate::input::PlayerInputSet;
+use crate::player::{PlayerCount, ShipDestroyed, Starship};
+use crate::replay::replay_playing;
+use crate::session::{GameState, Lives, TimeScale};
+
+// Real seconds the death cam lasts before the game over
+const DEATH_SEQUENCE_TIME: f32 = 1.5;
+
+// Speed of the game clock while the death cam runs
+const DEATH_SLOW_MOTION: f32 = 0.25;
+
+// Camera scale the death cam zooms in to (smaller shows less)
+const DEATH_ZOOM_SCALE: f32 = 0.6;
+
+// How quickly the cameras ease towards the explosion
+const DEATH_CAM_SMOOTHING: f32 = 4.0;
+
+// Resource present while the death cam runs, taken away as the game over begins
+#[derive(Resource)]
+pub struct DeathSequence {
+    pub timer: Timer,   // Real time left before the game over
+    pub focus: Vec2,    // Where the last ship blew up
+}
+
+// System to start the death cam when the last ship with no lives to come back is destroyed. A
+// replayed run ends without a game over, as it doesn't belong in the high scores
+fn death_start_system(
+    mut commands: Commands,                                   // For starting the sequence
+    mut destroyed_events: EventReader<ShipDestroyed>,         // Ships destroyed
+    lives: Res<Lives>,                                        // Lives left of every player
+    player_count: Res<PlayerCount>,                           // Who is taking part
+    ship_query: Query<(), With<Starship>>,                    // Whether any ship is still flying
+    sequence: Option<Res<DeathSequence>>,                     // A sequence already running
+    mut time_scale: ResMut<TimeScale>,                        // For slowing time
+) {
+    let Some(last) = destroyed_events.read().last() else {
+        return;
+    };
+    let over = ship_query.is_empty() && player_count.ids().all(|id| lives.0[id] == 0);
+    if !over || sequence.is_some() {
+        return;
+    }
+    time_scale.cinematic_speed = DEATH_SLOW_MOTION;
+    commands.insert_resource(DeathSequence {
+        timer: Timer::from_seconds(DEATH_SEQUENCE_TIME, TimerMode::Once),
+        focus: last.position,
+    });
+}
+
+// System to close the cameras in on the explosion, and bring the game over once the time is up;
+// counts real time, so the slow motion doesn't draw it out
+fn death_sequence_system(
+    mut commands: Commands,                                   // For ending the sequence
+    time: Res<Time<Real>>,                                    // For the timer and easing
+    mut sequence: ResMut<DeathSequence>,                      // The sequence running
+    mut time_scale: ResMut<TimeScale>,                        // For bringing time back
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<PlayerCamera>>, // Get the cameras
+    mut next_state: ResMut<NextState<GameState>>,             // For switching state
+) {
+    let blend = 1.0 - (-DEATH_CAM_SMOOTHING * time.delta_seconds()).exp();
+    for (mut transform, mut projection) in camera_query.iter_mut() {
+        let position = transform.translation.truncate().lerp(sequence.focus, blend);
+        transform.translation = position.extend(transform.translation.z);
+        projection.scale += (DEATH_ZOOM_SCALE - projection.scale) * blend;
+    }
+    if sequence.timer.tick(time.delta()).finished() {
+        time_scale.cinematic_speed = 1.0;
+        commands.remove_resource::<DeathSequence>();
+        next_state.set(GameState::GameOver);
+    }
+}
+
+// System to ignore the keyboard, mouse and gamepad buttons while the death cam runs, so nothing
+// pauses, saves or starts over in the middle of it
+fn death_input_system(
+    mut keyboard_input: ResMut<Input<KeyCode>>,               // For keyboard input
+    mut mouse_input: ResMut<Input<MouseButton>>,              // For mouse input
+    mut gamepad_input: ResMut<Input<GamepadButton>>,          // For gamepad input
+) {
+    keyboard_input.reset_all();
+    mouse_input.reset_all();
+    gamepad_input.reset_all();
+}
+
+// System to put the cameras back over the middle of the arena for the next run; the cameras go
+// on following their ships from there
+fn death_cam_reset_system(mut camera_query: Query<&mut Transform, With<PlayerCamera>>) {
+    for mut transform in camera_query.iter_mut() {
+        transform.translation = Vec3::new(0.0, 0.0, transform.translation.z);
+    }
+}
+
+// Plugin for the death cam
+pub struct DeathSequencePlugin;
+
+impl Plugin for DeathSequencePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, death_input_system
+                .after(InputSystem)
+                .before(PlayerInputSet)
+                .run_if(resource_exists::<DeathSequence>()))
+            .add_systems(Update, (
+                death_start_system.run_if(not(replay_playing)),
+                death_sequence_system.run_if(resource_exists::<DeathSequence>()),
+            ).chain().run_if(in_state(GameState::Playing)))
+            .add_systems(OnExit(GameState::GameOver), death_cam_reset_system);
+    }
+}
@@ -0,0 +1,151 @@
+// Debris thrown off by destroyed hostiles: small spinning pieces flying fast for a moment, hurting
+// ships that fly through them. They aren't hostiles, so they can't be shot, don't score, don't
+// split and pass through asteroids
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::combat::{CollisionDetectionSet, CollisionEvent, DamageEvent, DamageSource};
+use crate::config::GameConfig;
+use crate::hostiles::{HostileDestroySet, HostileDestroyed};
+use crate::palette::Tint;
+use crate::physics::{Collider, Lifetime, Velocity};
+use crate::player::Starship;
+use crate::session::{GameRng, GameState, GameplayEntity};
+use crate::GameSet;
+
+// Width of a piece of debris
+const DEBRIS_SIZE: f32 = 5.0;
+
+// Fastest a piece of debris spins, in radians per second
+const DEBRIS_MAX_SPIN: f32 = 12.0;
+
+// Define the Debris component for a piece thrown off by an explosion
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Debris {
+    pub damage: f32,  // Damage dealt to a ship it hits
+    pub spin: f32,    // Radians per second it turns, clockwise when negative
+}
+
+// Spawn a piece of debris, gone once its lifetime runs out
+pub fn spawn_debris(
+    commands: &mut Commands,                                  // For spawning the piece
+    position: Vec2,                                           // Where it starts
+    velocity: Vec2,                                           // How fast it flies off
+    spin: f32,                                                // How fast it turns
+    lifetime: f32,                                            // Seconds it lasts
+    damage: f32,                                              // Damage dealt to a ship it hits
+) -> Entity {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(DEBRIS_SIZE)),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(0.4)),
+                ..default()
+            },
+            Tint::Debris,
+            GameplayEntity,
+            Debris { damage, spin },
+            Velocity {
+                x: velocity.x,
+                y: velocity.y,
+            },
+            Collider { radius: DEBRIS_SIZE / 2.0 },
+            Lifetime(Timer::from_seconds(lifetime, TimerMode::Once)),
+        ))
+        .id()
+}
+
+// System to sometimes throw debris off where a hostile was destroyed
+fn debris_spawn_system(
+    mut commands: Commands,                                   // For spawning the debris
+    mut destroyed_events: EventReader<HostileDestroyed>,      // Destroyed hostiles
+    mut rng: ResMut<GameRng>,                                 // For the roll, count and directions
+    config: Res<GameConfig>,                                  // How much debris and how fast
+) {
+    let debris = &config.debris;
+    for event in destroyed_events.read() {
+        if !rng.gen_bool(debris.chance.clamp(0.0, 1.0)) {
+            continue;
+        }
+        let count = rng.gen_range(debris.min_count..=debris.max_count.max(debris.min_count));
+        for _ in 0..count {
+            let velocity = Vec2::from_angle(rng.gen_range(0.0..std::f32::consts::TAU)) * debris.speed;
+            let spin = rng.gen_range(-DEBRIS_MAX_SPIN..DEBRIS_MAX_SPIN);
+            spawn_debris(&mut commands, event.position, velocity, spin, debris.lifetime, debris.damage);
+        }
+    }
+}
+
+// System to turn debris as it flies
+fn debris_spin_system(
+    time: Res<Time>,                                          // For frame-independent turning
+    mut query: Query<(&Debris, &mut Transform)>,              // Get every piece
+) {
+    for (debris, mut transform) in query.iter_mut() {
+        transform.rotate_z(debris.spin * time.delta_seconds());
+    }
+}
+
+// System to find the debris each ship runs into
+fn debris_collision_system(
+    ship_query: Query<(Entity, &Transform, &Collider), With<Starship>>, // Get every ship's hit area
+    debris_query: Query<(Entity, &Transform, &Collider), With<Debris>>, // Get every piece
+    mut collision_events: EventWriter<CollisionEvent>,        // For the systems reacting to the hit
+) {
+    for (ship, ship_transform, ship_collider) in ship_query.iter() {
+        let ship_position = ship_transform.translation.truncate();
+        for (debris, transform, collider) in debris_query.iter() {
+            let position = transform.translation.truncate();
+            if ship_position.distance(position) > ship_collider.radius + collider.radius {
+                continue;
+            }
+            collision_events.send(CollisionEvent {
+                a: ship,
+                b: debris,
+                point: position,
+            });
+        }
+    }
+}
+
+// System to let debris damage the ships it hits, breaking up on the first
+fn debris_hit_system(
+    mut commands: Commands,                                   // For removing spent debris
+    mut collision_events: EventReader<CollisionEvent>,        // Overlaps found this tick
+    debris_query: Query<&Debris>,                             // Get the debris that hit
+    mut damage_events: EventWriter<DamageEvent>,              // For dealing the damage
+) {
+    for event in collision_events.read() {
+        let Ok(debris) = debris_query.get(event.b) else {
+            continue;
+        };
+        damage_events.send(DamageEvent {
+            target: event.a,
+            amount: debris.damage,
+            source: DamageSource::Collision,
+        });
+        // Two ships touching the same piece in one tick would otherwise despawn it twice
+        if let Some(mut entity) = commands.get_entity(event.b) {
+            entity.despawn();
+        }
+    }
+}
+
+// Plugin for explosion debris
+pub struct DebrisPlugin;
+
+impl Plugin for DebrisPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Debris>()
+            .add_systems(FixedUpdate, (
+                debris_spin_system.in_set(GameSet::Physics),
+                debris_collision_system.in_set(CollisionDetectionSet).in_set(GameSet::Collision),
+                debris_hit_system.after(CollisionDetectionSet).in_set(GameSet::Collision),
+                debris_spawn_system.after(HostileDestroySet).in_set(GameSet::Cleanup),
+            ).run_if(in_state(GameState::Playing)));
+    }
+}
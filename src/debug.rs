@@ -0,0 +1,372 @@
+// Developer tools, only compiled in with the `debug-inspector` feature
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use rand::Rng;
+use bevy::window::PrimaryWindow;
+use bevy_inspector_egui::quick::WorldInspectorPlugin;
+
+use crate::camera::cursor_world_position;
+use crate::combat::CollisionEvent;
+use crate::console::ConsolePlugin;
+use crate::hostiles::{spawn_asteroid, spawn_enemy};
+use crate::physics::{Collider, InterpolationSet, Velocity, WorldBounds};
+use crate::player::Starship;
+use crate::powerups::{PowerUpKind, PowerUpSettings, spawn_power_up};
+use crate::session::{GameRng, GameState, GameplayEntity, TimeScale};
+use crate::GameSet;
+
+// Resource switching every developer tool on or off at runtime
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct DebugMode(pub bool);
+
+impl Default for DebugMode {
+    fn default() -> Self {
+        // On wherever the tools are added: every development build, and release builds asking
+        // for them with the `release-inspector` feature
+        Self(cfg!(any(debug_assertions, feature = "release-inspector")))
+    }
+}
+
+// Run condition for the developer tools
+pub fn debug_mode(mode: Res<DebugMode>) -> bool {
+    mode.0
+}
+
+// Key that shows and hides the world inspector
+const INSPECTOR_TOGGLE_KEY: KeyCode = KeyCode::F12;
+
+// Resource tracking whether the world inspector window is shown
+#[derive(Reflect, Resource, Default)]
+#[reflect(Resource)]
+struct InspectorToggle {
+    visible: bool,  // Hidden by default so it stays out of the way during play
+}
+
+// Run condition for the world inspector
+fn inspector_visible(toggle: Res<InspectorToggle>) -> bool {
+    toggle.visible
+}
+
+// System to show or hide the world inspector
+fn inspector_toggle_system(
+    keyboard_input: Res<Input<KeyCode>>,                      // Access the keyboard input
+    mut toggle: ResMut<InspectorToggle>,                      // Whether the inspector is shown
+) {
+    if keyboard_input.just_pressed(INSPECTOR_TOGGLE_KEY) {
+        toggle.visible = !toggle.visible;
+    }
+}
+
+// Keys that spawn test entities or clear the arena
+const DEBUG_SPAWN_ASTEROID_KEY: KeyCode = KeyCode::F1;
+const DEBUG_SPAWN_FUEL_KEY: KeyCode = KeyCode::F2;
+const DEBUG_SPAWN_ENEMY_KEY: KeyCode = KeyCode::F3;
+const DEBUG_CLEAR_KEY: KeyCode = KeyCode::F4;
+
+// System to spawn test entities on demand, at the cursor or somewhere random
+fn debug_spawn_system(
+    mut commands: Commands,                                   // For spawning test entities
+    keyboard_input: Res<Input<KeyCode>>,                      // Access the keyboard input
+    window_query: Query<&Window, With<PrimaryWindow>>,        // Get the cursor position
+    camera_query: Query<(&Camera, &GlobalTransform)>,         // For converting to world space
+    arena: Res<WorldBounds>,                                  // Size of the play area
+    powerup_settings: Res<PowerUpSettings>,                   // Pickup lifetime
+    mut rng: ResMut<GameRng>,                                 // For random positions and sizes
+) {
+    let spawn_asteroid_pressed = keyboard_input.just_pressed(DEBUG_SPAWN_ASTEROID_KEY);
+    let spawn_fuel_pressed = keyboard_input.just_pressed(DEBUG_SPAWN_FUEL_KEY);
+    // Shift+F3 is the overlay's, leaving F3 alone to the enemy
+    let spawn_enemy_pressed = keyboard_input.just_pressed(DEBUG_SPAWN_ENEMY_KEY) && !shift_held(&keyboard_input);
+    if !spawn_asteroid_pressed && !spawn_fuel_pressed && !spawn_enemy_pressed {
+        return;
+    }
+
+    let cursor = window_query
+        .get_single()
+        .ok()
+        .and_then(|window| cursor_world_position(window, camera_query.iter()));
+    let position = cursor.unwrap_or_else(|| {
+        Vec2::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)) * arena.half_extents()
+    });
+
+    if spawn_asteroid_pressed {
+        let velocity = Vec2::from_angle(rng.gen_range(0.0..std::f32::consts::TAU)) * rng.gen_range(40.0..120.0);
+        let entity = spawn_asteroid(&mut commands, position, velocity, rng.gen_range(15.0..40.0));
+        info!("Debug spawned asteroid {:?} at {}", entity, position);
+    }
+    if spawn_fuel_pressed {
+        let entity = spawn_power_up(&mut commands, PowerUpKind::Fuel, position, Vec2::ZERO, powerup_settings.lifetime);
+        info!("Debug spawned fuel pickup {:?} at {}", entity, position);
+    }
+    if spawn_enemy_pressed {
+        let entity = spawn_enemy(&mut commands, position);
+        info!("Debug spawned enemy {:?} at {}", entity, position);
+    }
+}
+
+// System to despawn every gameplay entity except the ship
+fn debug_clear_system(
+    mut commands: Commands,                                   // For despawning entities
+    keyboard_input: Res<Input<KeyCode>>,                      // Access the keyboard input
+    query: Query<Entity, (With<GameplayEntity>, Without<Starship>)>, // Get everything but the ship
+) {
+    if !keyboard_input.just_pressed(DEBUG_CLEAR_KEY) {
+        return;
+    }
+    let mut count = 0;
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+        count += 1;
+    }
+    info!("Debug despawned {} entities", count);
+}
+
+// Key that, with Shift held, shows and hides the debug overlay; F3 alone spawns an enemy
+const DEBUG_OVERLAY_KEY: KeyCode = KeyCode::F3;
+
+// Seconds of movement the velocity lines reach ahead, so slow drifts still show
+const VELOCITY_LINE_SECONDS: f32 = 0.25;
+
+// Length of the line showing which way a ship faces, in pixels
+const FACING_LINE_LENGTH: f32 = 40.0;
+
+// Resource switching the debug overlay drawn over the arena on or off
+#[derive(Reflect, Resource, Default)]
+#[reflect(Resource)]
+pub struct DebugOverlay(pub bool);
+
+// Whether either Shift key is held
+fn shift_held(keyboard_input: &Input<KeyCode>) -> bool {
+    keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+}
+
+// Run condition for the debug overlay
+fn overlay_visible(overlay: Res<DebugOverlay>) -> bool {
+    overlay.0
+}
+
+// System to show or hide the debug overlay
+fn overlay_toggle_system(
+    keyboard_input: Res<Input<KeyCode>>,                      // Access the keyboard input
+    mut overlay: ResMut<DebugOverlay>,                        // Whether the overlay is shown
+) {
+    if keyboard_input.just_pressed(DEBUG_OVERLAY_KEY) && shift_held(&keyboard_input) {
+        overlay.0 = !overlay.0;
+    }
+}
+
+// System to draw where everything moving is headed, and which way each ship points; one line
+// per mover and nothing kept between frames, so hundreds of asteroids stay cheap
+fn overlay_vectors_system(
+    mut gizmos: Gizmos,                                       // For drawing the lines
+    mover_query: Query<(&Transform, &Velocity)>,              // Get everything moving
+    ship_query: Query<&Transform, With<Starship>>,            // Get the ships
+) {
+    for (transform, velocity) in mover_query.iter() {
+        let position = transform.translation.truncate();
+        gizmos.line_2d(position, position + velocity.as_vec2() * VELOCITY_LINE_SECONDS, Color::CYAN);
+    }
+    // Straight from the rotation, the same way the engine reads it
+    for transform in ship_query.iter() {
+        let position = transform.translation.truncate();
+        gizmos.line_2d(position, position + transform.up().truncate() * FACING_LINE_LENGTH, Color::FUCHSIA);
+    }
+}
+
+// System to outline every hit circle, green, or red on the frame a collision involves it. The
+// circle is the radius the collision checks use, which is in world pixels and ignores the
+// transform's scale, so the outline always matches what actually touches
+fn overlay_colliders_system(
+    mut gizmos: Gizmos,                                       // For drawing the circles
+    mut collision_events: EventReader<CollisionEvent>,        // Overlaps found since the last frame
+    collider_query: Query<(Entity, &Transform, &Collider)>,   // Get every hit circle
+) {
+    let colliding: HashSet<Entity> = collision_events.read().flat_map(|event| [event.a, event.b]).collect();
+    for (entity, transform, collider) in collider_query.iter() {
+        let color = if colliding.contains(&entity) { Color::RED } else { Color::GREEN };
+        gizmos.circle_2d(transform.translation.truncate(), collider.radius, color);
+    }
+}
+
+// Keys that slow the game clock down and speed it up, for watching collisions up close or
+// skipping through waves
+const DEBUG_SLOWER_KEY: KeyCode = KeyCode::BracketLeft;
+const DEBUG_FASTER_KEY: KeyCode = KeyCode::BracketRight;
+
+// Speeds the game clock steps through, stopping at either end
+const DEBUG_TIME_SCALES: [f32; 6] = [0.1, 0.25, 0.5, 1.0, 2.0, 4.0];
+
+// Marker for the text showing the game clock runs at another speed
+#[derive(Component)]
+struct TimeScaleText;
+
+// System to step the game clock through the debug speeds; everything on the game clock follows,
+// including the fixed ticks, while whatever reads Time<Real> keeps its pace
+fn debug_time_scale_system(
+    keyboard_input: Res<Input<KeyCode>>,                      // Access the keyboard input
+    mut time_scale: ResMut<TimeScale>,                        // The speed to change
+) {
+    let slower = keyboard_input.just_pressed(DEBUG_SLOWER_KEY);
+    let faster = keyboard_input.just_pressed(DEBUG_FASTER_KEY);
+    if slower == faster {
+        return;
+    }
+    // A speed set some other way, like in the inspector, steps from normal speed
+    let current = DEBUG_TIME_SCALES
+        .iter()
+        .position(|&speed| speed == time_scale.debug_speed)
+        .unwrap_or(DEBUG_TIME_SCALES.len() / 2);
+    let index = if slower {
+        current.saturating_sub(1)
+    } else {
+        (current + 1).min(DEBUG_TIME_SCALES.len() - 1)
+    };
+    time_scale.debug_speed = DEBUG_TIME_SCALES[index];
+    info!("Debug time scale: {}x", time_scale.debug_speed);
+}
+
+// Setup system that adds the debug speed to the top right corner, hidden at normal speed
+fn setup_time_scale_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::YELLOW,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            top: Val::Px(10.0),
+            ..default()
+        }),
+        TimeScaleText,
+    ));
+}
+
+// System to show the debug speed whenever it isn't 1.0, even with the developer tools switched
+// off, since the game still runs at it
+fn time_scale_text_system(
+    time_scale: Res<TimeScale>,                               // The speed to show
+    mut text_query: Query<(&mut Text, &mut Visibility), With<TimeScaleText>>, // Get the text
+) {
+    for (mut text, mut visibility) in text_query.iter_mut() {
+        let scaled = time_scale.debug_speed != 1.0;
+        *visibility = if scaled { Visibility::Inherited } else { Visibility::Hidden };
+        if scaled {
+            text.sections[0].value = format!("Time {}x", time_scale.debug_speed);
+        }
+    }
+}
+
+// Key running a single fixed tick while the game is paused
+const DEBUG_STEP_KEY: KeyCode = KeyCode::Period;
+
+// Resource counting the fixed ticks simulated since the game started, for lining a step up with the logs
+#[derive(Reflect, Resource, Default)]
+#[reflect(Resource)]
+struct SimulationTick(u64);
+
+// Marker for the text showing the tick while paused
+#[derive(Component)]
+struct SimulationTickText;
+
+// System to count every tick the simulation runs
+fn simulation_tick_system(mut tick: ResMut<SimulationTick>) {
+    tick.0 += 1;
+}
+
+// System to run exactly one fixed tick while paused, then freeze again. The tick's systems only
+// run while playing, so the state reads Playing for the length of the tick; it is swapped in
+// place rather than transitioned to, so nothing reacting to a pause or resume notices. The tick
+// moves on by the fixed timestep without advancing the fixed clock's elapsed time
+fn debug_step_system(world: &mut World) {
+    if !world.resource::<Input<KeyCode>>().just_pressed(DEBUG_STEP_KEY) {
+        return;
+    }
+    world.insert_resource(State::new(GameState::Playing));
+    *world.resource_mut::<Time>() = world.resource::<Time<Fixed>>().as_generic();
+    world.run_schedule(FixedUpdate);
+    *world.resource_mut::<Time>() = world.resource::<Time<Virtual>>().as_generic();
+    world.insert_resource(State::new(GameState::Paused));
+    info!("Stepped to tick {}", world.resource::<SimulationTick>().0);
+}
+
+// Setup system that adds the tick under the debug speed, hidden until the game is paused
+fn setup_simulation_tick_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::YELLOW,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            top: Val::Px(34.0),
+            ..default()
+        }),
+        SimulationTickText,
+    ));
+}
+
+// System to show the tick while paused with the developer tools on, where it can be stepped
+fn simulation_tick_text_system(
+    mode: Res<DebugMode>,                                     // Whether the tools are on
+    state: Res<State<GameState>>,                             // Whether the game is paused
+    tick: Res<SimulationTick>,                                // The tick to show
+    mut text_query: Query<(&mut Text, &mut Visibility), With<SimulationTickText>>, // Get the text
+) {
+    for (mut text, mut visibility) in text_query.iter_mut() {
+        let stepping = mode.0 && *state.get() == GameState::Paused;
+        *visibility = if stepping { Visibility::Inherited } else { Visibility::Hidden };
+        if stepping {
+            text.sections[0].value = format!("Tick {}   ({:?} to step)", tick.0, DEBUG_STEP_KEY);
+        }
+    }
+}
+
+// Plugin bundling the developer tools, only compiled in with the `debug-inspector` feature
+pub struct DebugToolsPlugin;
+
+impl Plugin for DebugToolsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<DebugMode>()
+            .register_type::<InspectorToggle>()
+            .register_type::<SimulationTick>()
+            .register_type::<DebugOverlay>()
+            .init_resource::<DebugMode>()
+            .init_resource::<InspectorToggle>()
+            .init_resource::<SimulationTick>()
+            .init_resource::<DebugOverlay>()
+            .add_plugins(WorldInspectorPlugin::new().run_if(debug_mode.and_then(inspector_visible)))
+            .add_plugins(ConsolePlugin)
+            .add_systems(Startup, (setup_time_scale_text, setup_simulation_tick_text))
+            .add_systems(Update, (
+                inspector_toggle_system,
+                overlay_toggle_system,
+                debug_time_scale_system,
+            ).run_if(debug_mode))
+            .add_systems(Update, time_scale_text_system.run_if(resource_changed::<TimeScale>()))
+            // Counted last in every tick, so a step shows the tick it just ran
+            .add_systems(FixedUpdate, simulation_tick_system.after(GameSet::Cleanup).run_if(in_state(GameState::Playing)))
+            // Before the drawn positions are blended, so the step shows up the same frame
+            .add_systems(Update, debug_step_system.before(InterpolationSet).run_if(debug_mode).run_if(in_state(GameState::Paused)))
+            .add_systems(Update, simulation_tick_text_system)
+            // Over the drawn positions, so the lines stay on the sprites between ticks
+            .add_systems(Update, (
+                overlay_vectors_system,
+                overlay_colliders_system,
+            ).after(InterpolationSet).run_if(debug_mode.and_then(overlay_visible)))
+            .add_systems(Update, (
+                debug_spawn_system,
+                debug_clear_system,
+            ).run_if(debug_mode).run_if(in_state(GameState::Playing)));
+    }
+}
@@ -0,0 +1,156 @@
+// The game getting harder the longer a run survives
+use bevy::prelude::*;
+
+use crate::config::GameConfig;
+use crate::player::player_exists;
+use crate::session::{GameState, NewGameEvent};
+use crate::settings::Settings;
+use crate::GameSet;
+
+// Set for counting up the survival time, for systems reacting to it in the same tick
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SurvivalSet;
+
+// Resource counting how long the current run has survived
+#[derive(Reflect, Resource, Default)]
+#[reflect(Resource)]
+pub struct SurvivalTimer {
+    pub elapsed: f32,  // Seconds survived so far
+}
+
+impl SurvivalTimer {
+    // Whether the last tick, taking the given time, carried the run past another multiple of the interval
+    pub fn crossed(&self, interval: f32, delta_seconds: f32) -> bool {
+        if interval <= 0.0 {
+            return false;
+        }
+        let before = ((self.elapsed - delta_seconds) / interval).floor();
+        before >= 0.0 && (self.elapsed / interval).floor() > before
+    }
+}
+
+// Resource shaping how the game ramps up with survival time
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct DifficultyCurve {
+    pub enabled: bool,          // Whether the game ramps up at all
+    pub ramp_time: f32,         // Seconds for about two thirds of the full ramp to kick in
+    pub max_spawn_rate: f32,    // Most often hostiles spawn, as a multiple of the starting rate
+    pub max_speed: f32,         // Fastest hostiles fly, as a multiple of their starting speed
+}
+
+impl Default for DifficultyCurve {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ramp_time: 180.0,
+            max_spawn_rate: 3.0,
+            max_speed: 1.5,
+        }
+    }
+}
+
+impl DifficultyCurve {
+    // How far along the ramp a run is, from 0.0 at the start towards 1.0 but never past it
+    pub fn ramp(&self, elapsed: f32) -> f32 {
+        if !self.enabled || self.ramp_time <= 0.0 {
+            return 0.0;
+        }
+        1.0 - (-elapsed / self.ramp_time).exp()
+    }
+
+    // How many times more often hostiles spawn after surviving for the given time
+    pub fn spawn_rate(&self, elapsed: f32) -> f32 {
+        1.0 + (self.max_spawn_rate - 1.0).max(0.0) * self.ramp(elapsed)
+    }
+
+    // How many times faster hostiles fly after surviving for the given time
+    pub fn speed(&self, elapsed: f32) -> f32 {
+        1.0 + (self.max_speed - 1.0).max(0.0) * self.ramp(elapsed)
+    }
+}
+
+// Resource holding how hard the game currently is, for the spawn and steering logic to read
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct Difficulty {
+    pub spawn_rate: f32,  // Multiplier on how often hostiles spawn
+    pub speed: f32,       // Multiplier on how fast hostiles fly
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Self {
+            spawn_rate: 1.0,
+            speed: 1.0,
+        }
+    }
+}
+
+// System to count up the survival time and work out the difficulty for this tick
+fn difficulty_system(
+    time: Res<Time>,                                          // For counting survival time
+    curve: Res<DifficultyCurve>,                              // How the game ramps up
+    mut survival: ResMut<SurvivalTimer>,                      // Time survived so far
+    mut difficulty: ResMut<Difficulty>,                       // The difficulty to update
+) {
+    survival.elapsed += time.delta_seconds();
+    difficulty.spawn_rate = curve.spawn_rate(survival.elapsed);
+    difficulty.speed = curve.speed(survival.elapsed);
+}
+
+// System to switch the ramp on or off as the settings ask
+fn difficulty_settings_system(
+    settings: Res<Settings>,                                  // The player's preferences
+    mut curve: ResMut<DifficultyCurve>,                       // The ramp to switch
+) {
+    curve.enabled = settings.difficulty_ramp;
+}
+
+// System to take the shape of the ramp from the game config as it is loaded or reloaded
+fn difficulty_config_system(
+    config: Res<GameConfig>,                                  // The balance values
+    mut curve: ResMut<DifficultyCurve>,                       // The ramp to shape
+) {
+    curve.ramp_time = config.difficulty.ramp_time;
+    curve.max_spawn_rate = config.difficulty.max_spawn_rate;
+    curve.max_speed = config.difficulty.max_speed;
+}
+
+// System to start the ramp over when a new run starts
+fn reset_difficulty_system(
+    mut new_game_events: EventReader<NewGameEvent>,           // Requests for a new run
+    mut survival: ResMut<SurvivalTimer>,                      // Time survived to clear
+    mut difficulty: ResMut<Difficulty>,                       // Difficulty to bring back down
+) {
+    if new_game_events.read().count() > 0 {
+        *survival = SurvivalTimer::default();
+        *difficulty = Difficulty::default();
+    }
+}
+
+// Plugin for the difficulty ramp
+pub struct DifficultyPlugin;
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SurvivalTimer>()
+            .register_type::<DifficultyCurve>()
+            .register_type::<Difficulty>()
+            .init_resource::<SurvivalTimer>()
+            .init_resource::<DifficultyCurve>()
+            .init_resource::<Difficulty>()
+            .add_systems(Update, (
+                difficulty_settings_system.run_if(resource_changed::<Settings>()),
+                reset_difficulty_system,
+            ))
+            // Shaped before the frame's ticks, so the ramp follows the config from the first one
+            .add_systems(PreUpdate, difficulty_config_system.run_if(resource_changed::<GameConfig>()))
+            // Only time with a ship in play counts as surviving
+            .add_systems(FixedUpdate, difficulty_system
+                .in_set(SurvivalSet)
+                .in_set(GameSet::Input)
+                .run_if(player_exists)
+                .run_if(in_state(GameState::Playing)));
+    }
+}
@@ -0,0 +1,156 @@
+// Purely visual effects: explosions and the debris they throw out
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::combat::CollisionEvent;
+use crate::physics::Velocity;
+use crate::pool::{PoolKind, Pooled, release_pooled, spawn_pooled};
+use crate::session::{GameRng, GameState, GameplayEntity};
+
+// Define the Explosion component for an expanding, fading ring
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Explosion {
+    pub timer: Timer,  // How long the ring takes to fade out
+    pub radius: f32,   // Size of the ring when fully expanded
+}
+
+// Define the Particle component for a short-lived piece of debris
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Particle {
+    pub timer: Timer,  // Despawns the particle when finished
+}
+
+// Spawn a square of debris flying off and fading out, reusing a particle from the pool if there is one
+pub fn spawn_particle(
+    commands: &mut Commands,                                  // For spawning the particle
+    position: Vec2,                                           // Where it starts
+    velocity: Vec2,                                           // How fast it flies off
+    color: Color,                                             // Color it starts out in
+    size: f32,                                                // Width of the square
+    lifetime: f32,                                            // Seconds until it has faded out
+) {
+    spawn_pooled(commands, PoolKind::Particle, (
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::splat(size)),
+                ..default()
+            },
+            transform: Transform::from_translation(position.extend(0.5)),
+            ..default()
+        },
+        GameplayEntity,
+        Velocity {
+            x: velocity.x,
+            y: velocity.y,
+        },
+        Particle {
+            timer: Timer::from_seconds(lifetime, TimerMode::Once),
+        },
+    ));
+}
+
+// Blow up whatever was at the given position, sized after its hit radius
+pub fn spawn_explosion(
+    commands: &mut Commands,                                  // For spawning the effect
+    rng: &mut GameRng,                                        // For scattering the debris
+    position: Vec2,                                           // Where the explosion happens
+    radius: f32,                                              // Hit radius of what blew up
+) -> Entity {
+    // Fling a handful of debris outward, more and faster for bigger explosions
+    let count = (radius / 4.0).clamp(4.0, 16.0) as usize;
+    for _ in 0..count {
+        let velocity = Vec2::from_angle(rng.gen_range(0.0..std::f32::consts::TAU)) * rng.gen_range(1.0..4.0) * radius;
+        let color = Color::rgb(1.0, rng.gen_range(0.5..0.9), 0.3);
+        let lifetime = rng.gen_range(0.3..0.7);
+        spawn_particle(commands, position, velocity, color, 3.0, lifetime);
+    }
+
+    commands
+        .spawn((
+            GameplayEntity,
+            Explosion {
+                timer: Timer::from_seconds(0.4, TimerMode::Once),
+                radius: radius * 2.0,
+            },
+            TransformBundle::from_transform(Transform::from_translation(position.extend(0.0))),
+        ))
+        .id()
+}
+
+// Sparks thrown out where two things meet
+const IMPACT_SPARKS: usize = 6;
+
+// How fast the sparks fly off, in pixels/second
+const IMPACT_SPARK_SPEED: f32 = 90.0;
+
+// System to throw a few sparks out wherever something hits something else; they fly out evenly
+// all around, so they take nothing from the game's random numbers and replays stay the same
+fn impact_spark_system(
+    mut commands: Commands,                                   // For spawning the sparks
+    mut collision_events: EventReader<CollisionEvent>,        // Overlaps found this frame
+) {
+    for event in collision_events.read() {
+        for index in 0..IMPACT_SPARKS {
+            let angle = index as f32 / IMPACT_SPARKS as f32 * std::f32::consts::TAU;
+            let velocity = Vec2::from_angle(angle) * IMPACT_SPARK_SPEED;
+            spawn_particle(&mut commands, event.point, velocity, Color::rgb(1.0, 0.95, 0.7), 2.0, 0.2);
+        }
+    }
+}
+
+// System to expand and fade explosion rings, and remove them once faded
+fn explosion_system(
+    mut commands: Commands,                                   // For despawning finished rings
+    time: Res<Time>,                                          // For animating the ring
+    mut gizmos: Gizmos,                                       // For drawing the ring
+    mut query: Query<(Entity, &Transform, &mut Explosion)>,   // Get every explosion
+) {
+    for (entity, transform, mut explosion) in query.iter_mut() {
+        if explosion.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let progress = explosion.timer.percent();
+        gizmos.circle_2d(
+            transform.translation.truncate(),
+            explosion.radius * progress,
+            Color::rgba(1.0, 0.6, 0.2, 1.0 - progress),
+        );
+    }
+}
+
+// System to fade debris out and put it back in the pool once it has burned out
+fn particle_system(
+    mut commands: Commands,                                   // For pooling finished particles
+    time: Res<Time>,                                          // For ticking lifetimes
+    mut query: Query<(Entity, &mut Particle, &mut Sprite, &Pooled)>, // Get every particle
+) {
+    for (entity, mut particle, mut sprite, pooled) in query.iter_mut() {
+        if !pooled.in_use() {
+            continue;
+        }
+        if particle.timer.tick(time.delta()).finished() {
+            release_pooled(&mut commands, entity);
+            continue;
+        }
+        sprite.color.set_a(particle.timer.percent_left());
+    }
+}
+
+// Plugin for visual effects
+pub struct EffectsPlugin;
+
+impl Plugin for EffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Explosion>()
+            .register_type::<Particle>()
+            .add_systems(Update, (
+                impact_spark_system,
+                explosion_system,
+                particle_system,
+            ).run_if(in_state(GameState::Playing)));
+    }
+}
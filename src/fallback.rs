@@ -0,0 +1,70 @@
+// Textures drawn in code, standing in for image files that fail to load
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::storage::asset_path;
+
+// Shape drawn in every cell of a placeholder
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlaceholderShape {
+    Triangle,  // Pointing up, the way a sprite's nose points
+    Circle,    // Filling the cell
+}
+
+// How to draw the stand-in for an image, sized like the image so sprite sheets cut from it still line up
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Placeholder {
+    pub cell: UVec2,              // Size of one frame in pixels
+    pub frames: UVec2,            // Columns and rows of frames
+    pub shape: PlaceholderShape,  // What every frame shows
+    pub color: Color,             // Color of the shape; the rest is transparent
+}
+
+impl Placeholder {
+    // Whether a pixel, relative to the center of its cell with +Y up, falls inside the shape
+    fn covers(&self, point: Vec2) -> bool {
+        // Leave a pixel of margin so neighbouring frames don't bleed into each other
+        let half = self.cell.as_vec2() / 2.0 - 1.0;
+        match self.shape {
+            PlaceholderShape::Triangle => {
+                // Tip at the top middle, base along the bottom
+                let height = (half.y - point.y) / (2.0 * half.y);
+                point.y >= -half.y && point.y <= half.y && point.x.abs() <= half.x * height
+            }
+            PlaceholderShape::Circle => point.length() <= half.min_element(),
+        }
+    }
+
+    // Draw the placeholder into an image
+    pub fn image(&self) -> Image {
+        let size = self.cell * self.frames;
+        let color = self.color.as_rgba_u8();
+        let mut data = Vec::with_capacity((size.x * size.y * 4) as usize);
+        for y in 0..size.y {
+            for x in 0..size.x {
+                // Pixel rows run top to bottom, so flip them for +Y up
+                let within = UVec2::new(x % self.cell.x, y % self.cell.y).as_vec2() + 0.5;
+                let point = Vec2::new(within.x - self.cell.x as f32 / 2.0, self.cell.y as f32 / 2.0 - within.y);
+                data.extend_from_slice(if self.covers(point) { &color } else { &[0; 4] });
+            }
+        }
+        Image::new(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+}
+
+// Put a placeholder in place of an image that failed to load, so everything drawn with the
+// handle shows the placeholder instead of nothing
+pub fn substitute(images: &mut Assets<Image>, handle: &Handle<Image>, path: &str, placeholder: &Placeholder) {
+    let searched = asset_path(path);
+    error!("Failed to load {}, drawing a placeholder instead", searched.display());
+    images.insert(handle.id(), placeholder.image());
+}
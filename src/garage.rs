@@ -0,0 +1,386 @@
+// The garage between waves, where points scored buy upgrades for the rest of the run
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::difficulty::{SurvivalSet, SurvivalTimer};
+use crate::hostiles::HostileDestroySet;
+use crate::player::{Engine, Loadout, Starship, player_exists};
+use crate::replay::replay_playing;
+use crate::session::{GameState, NewGameEvent, Score};
+use crate::weapons::Weapon;
+use crate::GameSet;
+
+// Key leaving the garage, as well as its button
+const GARAGE_CLOSE_KEY: KeyCode = KeyCode::Return;
+
+// What can be improved in the garage
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Upgrade {
+    Thrust,         // More powerful engine
+    MaxSpeed,       // Higher top speed
+    RotationSpeed,  // Faster turning
+    MaxFuel,        // Bigger fuel tank
+    Damage,         // Harder-hitting weapon
+}
+
+impl Upgrade {
+    // Every upgrade, in the order the garage lists them
+    pub const ALL: [Upgrade; 5] = [
+        Upgrade::Thrust,
+        Upgrade::MaxSpeed,
+        Upgrade::RotationSpeed,
+        Upgrade::MaxFuel,
+        Upgrade::Damage,
+    ];
+
+    // What the garage calls the upgrade
+    pub fn label(self) -> &'static str {
+        match self {
+            Upgrade::Thrust => "Thrust",
+            Upgrade::MaxSpeed => "Top speed",
+            Upgrade::RotationSpeed => "Turning",
+            Upgrade::MaxFuel => "Fuel tank",
+            Upgrade::Damage => "Damage",
+        }
+    }
+}
+
+// Resource configuring the garage
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct GarageSettings {
+    pub interval: f32,   // Seconds survived between two visits, 0.0 to never open it
+    pub step: f32,       // Improvement per level, as a fraction of the ship's starting value
+    pub base_cost: u32,  // Credits the first level of any upgrade costs; each level after costs more
+}
+
+impl Default for GarageSettings {
+    fn default() -> Self {
+        Self {
+            interval: 120.0,
+            step: 0.15,
+            base_cost: 200,
+        }
+    }
+}
+
+// Resource holding the credits the players have to spend, earned one for every point scored
+#[derive(Reflect, Resource, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[reflect(Resource)]
+pub struct Credits {
+    pub balance: u32,  // Credits left to spend
+    pub earned: u32,   // Total score already turned into credits
+}
+
+// Resource holding the upgrades bought this run, shared by every ship
+#[derive(Reflect, Resource, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[reflect(Resource)]
+pub struct Upgrades {
+    pub levels: [u32; Upgrade::ALL.len()],  // Levels bought, in the order of Upgrade::ALL
+}
+
+impl Upgrades {
+    // Levels bought of an upgrade
+    pub fn level(&self, upgrade: Upgrade) -> u32 {
+        self.levels[upgrade as usize]
+    }
+
+    // How many times the starting value the upgrade currently gives
+    pub fn multiplier(&self, upgrade: Upgrade, garage_settings: &GarageSettings) -> f32 {
+        1.0 + self.level(upgrade) as f32 * garage_settings.step
+    }
+
+    // Credits the next level of an upgrade costs
+    pub fn cost(&self, upgrade: Upgrade, garage_settings: &GarageSettings) -> u32 {
+        garage_settings.base_cost.saturating_mul(self.level(upgrade) + 1)
+    }
+
+    // Buy the next level of an upgrade, returning false when the credits don't cover it
+    pub fn buy(&mut self, upgrade: Upgrade, credits: &mut Credits, garage_settings: &GarageSettings) -> bool {
+        let cost = self.cost(upgrade, garage_settings);
+        if credits.balance < cost {
+            return false;
+        }
+        credits.balance -= cost;
+        self.levels[upgrade as usize] += 1;
+        true
+    }
+}
+
+// Set a ship's handling and weapon from its loadout and the upgrades bought
+pub fn apply_upgrades(
+    upgrades: &Upgrades,                                      // Levels bought
+    garage_settings: &GarageSettings,                         // Improvement per level
+    loadout: &Loadout,                                        // The ship's starting values
+    starship: &mut Starship,                                  // The ship's handling
+    engine: &mut Engine,                                      // The ship's engine
+    weapon: &mut Weapon,                                      // The ship's weapon
+) {
+    let multiplier = |upgrade| upgrades.multiplier(upgrade, garage_settings);
+    engine.thrust = loadout.thrust * multiplier(Upgrade::Thrust);
+    engine.strafe_thrust = loadout.strafe_thrust * multiplier(Upgrade::Thrust);
+    starship.max_speed = loadout.max_speed * multiplier(Upgrade::MaxSpeed);
+    starship.rotation_speed = loadout.rotation_speed * multiplier(Upgrade::RotationSpeed);
+    // The extra room in a bigger tank comes filled
+    let max_fuel = loadout.max_fuel * multiplier(Upgrade::MaxFuel);
+    engine.fuel = (engine.fuel + (max_fuel - engine.max_fuel).max(0.0)).min(max_fuel);
+    engine.max_fuel = max_fuel;
+    weapon.damage_bonus = multiplier(Upgrade::Damage) - 1.0;
+}
+
+// Components of a ship the upgrades change
+type UpgradeTargetData<'a> = (&'a mut Starship, &'a mut Engine, &'a mut Weapon);
+
+// Marker for the garage screen
+#[derive(Component)]
+struct GarageRoot;
+
+// Marker for the text showing the credits left
+#[derive(Component)]
+struct CreditsText;
+
+// Define the GarageButton component for the button buying one upgrade
+#[derive(Component)]
+pub struct GarageButton(pub Upgrade);
+
+// Marker for the button leaving the garage
+#[derive(Component)]
+struct GarageCloseButton;
+
+// Background of a button, depending on whether it can be pressed
+fn button_color(enabled: bool, interaction: Interaction) -> Color {
+    match (enabled, interaction) {
+        (false, _) => Color::rgb(0.2, 0.2, 0.2),
+        (true, Interaction::Pressed) => Color::rgb(0.2, 0.6, 0.3),
+        (true, Interaction::Hovered) => Color::rgb(0.3, 0.4, 0.6),
+        (true, Interaction::None) => Color::rgb(0.2, 0.3, 0.5),
+    }
+}
+
+// Spawn a button with a line of text on it
+fn spawn_button(parent: &mut ChildBuilder, marker: impl Bundle) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(420.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    margin: UiRect::all(Val::Px(4.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: button_color(true, Interaction::None).into(),
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+}
+
+// System to turn the points scored into credits as they come in
+fn credit_system(
+    score: Res<Score>,                                        // Points of every player
+    mut credits: ResMut<Credits>,                             // Credits to add to
+) {
+    let total = score.0.iter().sum::<u32>();
+    if total > credits.earned {
+        credits.balance += total - credits.earned;
+    }
+    if total != credits.earned {
+        credits.earned = total;
+    }
+}
+
+// System to open the garage every time the run survives another wave
+fn garage_open_system(
+    time: Res<Time>,                                          // For spotting a finished wave
+    survival: Res<SurvivalTimer>,                             // Time survived this run
+    garage_settings: Res<GarageSettings>,                     // How long a wave lasts
+    mut next_state: ResMut<NextState<GameState>>,             // For opening the garage
+) {
+    if survival.crossed(garage_settings.interval, time.delta_seconds()) {
+        next_state.set(GameState::Upgrade);
+    }
+}
+
+// System to give ships just spawned or respawned the upgrades bought so far
+fn upgrade_new_ships_system(
+    upgrades: Res<Upgrades>,                                  // Levels bought
+    garage_settings: Res<GarageSettings>,                     // Improvement per level
+    loadout: Res<Loadout>,                                    // Starting values of the ships
+    mut ship_query: Query<UpgradeTargetData, Added<Starship>>, // Get the new ships
+) {
+    for (mut starship, mut engine, mut weapon) in ship_query.iter_mut() {
+        apply_upgrades(&upgrades, &garage_settings, &loadout, &mut starship, &mut engine, &mut weapon);
+    }
+}
+
+// Setup system that builds the garage screen when it opens
+fn setup_garage(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                ..default()
+            },
+            GarageRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "GARAGE",
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::rgb(1.0, 0.85, 0.3),
+                        ..default()
+                    },
+                ),
+                CreditsText,
+            ));
+            for upgrade in Upgrade::ALL {
+                spawn_button(parent, GarageButton(upgrade));
+            }
+            spawn_button(parent, GarageCloseButton);
+        });
+}
+
+// System to buy upgrades as their buttons are clicked
+fn garage_button_system(
+    upgrade_query: Query<(&Interaction, &GarageButton), Changed<Interaction>>, // Get clicked upgrades
+    mut upgrades: ResMut<Upgrades>,                           // Levels bought
+    mut credits: ResMut<Credits>,                             // Credits to pay with
+    garage_settings: Res<GarageSettings>,                     // Costs and improvement per level
+    loadout: Res<Loadout>,                                    // Starting values of the ships
+    mut ship_query: Query<UpgradeTargetData>,                 // Get the ships to improve
+) {
+    for (interaction, button) in upgrade_query.iter() {
+        if *interaction != Interaction::Pressed || !upgrades.buy(button.0, &mut credits, &garage_settings) {
+            continue;
+        }
+        info!("Bought {} level {}", button.0.label(), upgrades.level(button.0));
+        for (mut starship, mut engine, mut weapon) in ship_query.iter_mut() {
+            apply_upgrades(&upgrades, &garage_settings, &loadout, &mut starship, &mut engine, &mut weapon);
+        }
+    }
+}
+
+// System to go back to the fight when the leave button is clicked or its key pressed
+fn garage_close_system(
+    keyboard_input: Res<Input<KeyCode>>,                      // For leaving with the keyboard
+    close_query: Query<&Interaction, (Changed<Interaction>, With<GarageCloseButton>)>, // Get the leave button
+    mut next_state: ResMut<NextState<GameState>>,             // For leaving the garage
+) {
+    let clicked = close_query.iter().any(|interaction| *interaction == Interaction::Pressed);
+    if clicked || keyboard_input.just_pressed(GARAGE_CLOSE_KEY) {
+        next_state.set(GameState::Playing);
+    }
+}
+
+// System to show the credits, the cost of every upgrade and which ones can be afforded
+fn garage_text_system(
+    upgrades: Res<Upgrades>,                                  // Levels bought
+    credits: Res<Credits>,                                    // Credits left
+    garage_settings: Res<GarageSettings>,                     // Costs per level
+    mut credits_query: Query<&mut Text, With<CreditsText>>,   // Get the credits line
+    mut button_query: Query<(&Interaction, &mut BackgroundColor, &Children, Option<&GarageButton>), With<Button>>, // Get the buttons
+    mut text_query: Query<&mut Text, Without<CreditsText>>,   // Get the words on the buttons
+) {
+    for mut text in credits_query.iter_mut() {
+        text.sections[0].value = format!("Credits: {}", credits.balance);
+    }
+    for (interaction, mut background, children, button) in button_query.iter_mut() {
+        let (label, enabled) = match button {
+            Some(GarageButton(upgrade)) => {
+                let cost = upgrades.cost(*upgrade, &garage_settings);
+                (
+                    format!("{} (level {})   {} credits", upgrade.label(), upgrades.level(*upgrade), cost),
+                    credits.balance >= cost,
+                )
+            }
+            None => (format!("Back to the fight ({:?})", GARAGE_CLOSE_KEY), true),
+        };
+        *background = button_color(enabled, *interaction).into();
+        for &child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                text.sections[0].value = label.clone();
+            }
+        }
+    }
+}
+
+// System to take the garage screen down when it closes
+fn close_garage(mut commands: Commands, query: Query<Entity, With<GarageRoot>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// System to take back every upgrade and credit when a new run starts
+fn reset_garage_system(
+    mut new_game_events: EventReader<NewGameEvent>,           // Requests for a new run
+    mut upgrades: ResMut<Upgrades>,                           // Upgrades to take back
+    mut credits: ResMut<Credits>,                             // Credits to clear
+) {
+    if new_game_events.read().count() > 0 {
+        *upgrades = Upgrades::default();
+        *credits = Credits::default();
+    }
+}
+
+// Plugin for the garage between waves
+pub struct GaragePlugin;
+
+impl Plugin for GaragePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GarageSettings>()
+            .register_type::<Credits>()
+            .register_type::<Upgrades>()
+            .init_resource::<GarageSettings>()
+            .init_resource::<Credits>()
+            .init_resource::<Upgrades>()
+            .add_systems(OnEnter(GameState::Upgrade), setup_garage)
+            .add_systems(OnExit(GameState::Upgrade), close_garage)
+            .add_systems(Update, (reset_garage_system, upgrade_new_ships_system))
+            .add_systems(Update, (
+                garage_button_system,
+                garage_close_system,
+                garage_text_system,
+            ).chain().run_if(in_state(GameState::Upgrade)))
+            .add_systems(FixedUpdate, (
+                // A replay has no one to click through the garage
+                garage_open_system
+                    .after(SurvivalSet)
+                    .in_set(GameSet::Input)
+                    .run_if(player_exists)
+                    .run_if(not(replay_playing)),
+                // Points are scored as hostiles are destroyed
+                credit_system.after(HostileDestroySet).in_set(GameSet::Cleanup),
+            ).run_if(in_state(GameState::Playing)));
+    }
+}
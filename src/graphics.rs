@@ -0,0 +1,133 @@
+// How frames are presented, and a cap on how many are drawn a second
+use bevy::prelude::*;
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::utils::{Duration, Instant};
+use bevy::window::{PresentMode, PrimaryWindow};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+// Key switching to the next present mode
+const PRESENT_MODE_KEY: KeyCode = KeyCode::V;
+
+// Key switching to the next frame rate cap
+const FPS_CAP_KEY: KeyCode = KeyCode::L;
+
+// Frame rate caps the key goes through, starting over at no cap
+const FPS_CAPS: [Option<f32>; 5] = [None, Some(30.0), Some(60.0), Some(120.0), Some(144.0)];
+
+// Whether frames wait for the display before they are shown
+#[derive(Reflect, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VsyncMode {
+    #[default]
+    On,       // Wait for the display, never tearing
+    Off,      // Show frames as soon as they are done, tearing if need be
+    Mailbox,  // Keep drawing, but only show the newest frame when the display is ready
+}
+
+impl VsyncMode {
+    // The window's present mode for this choice, falling back when the display can't do it
+    pub fn present_mode(self) -> PresentMode {
+        match self {
+            VsyncMode::On => PresentMode::AutoVsync,
+            VsyncMode::Off => PresentMode::AutoNoVsync,
+            VsyncMode::Mailbox => PresentMode::Mailbox,
+        }
+    }
+
+    // The mode after this one, for cycling through them
+    fn next(self) -> Self {
+        match self {
+            VsyncMode::On => VsyncMode::Off,
+            VsyncMode::Off => VsyncMode::Mailbox,
+            VsyncMode::Mailbox => VsyncMode::On,
+        }
+    }
+}
+
+// Resource holding the graphics options in use, following the settings
+#[derive(Reflect, Resource, Serialize, Deserialize, Default, Clone, PartialEq, Debug)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct GraphicsSettings {
+    pub vsync: VsyncMode,        // How frames are presented
+    pub fps_cap: Option<f32>,    // Most frames drawn a second, or None to draw as many as possible
+}
+
+// System to follow the graphics options in the settings
+fn graphics_settings_system(
+    settings: Res<Settings>,                                  // The player's preferences
+    mut graphics_settings: ResMut<GraphicsSettings>,          // The options in use
+) {
+    if *graphics_settings != settings.graphics {
+        *graphics_settings = settings.graphics.clone();
+    }
+}
+
+// System to switch the present mode and frame rate cap from the keyboard, kept in the settings
+fn graphics_keys_system(
+    keyboard_input: Res<Input<KeyCode>>,                      // For keyboard input
+    mut settings: ResMut<Settings>,                           // Where the options are kept
+) {
+    if keyboard_input.just_pressed(PRESENT_MODE_KEY) {
+        settings.graphics.vsync = settings.graphics.vsync.next();
+        info!("Present mode: {:?}", settings.graphics.vsync);
+    }
+    if keyboard_input.just_pressed(FPS_CAP_KEY) {
+        let current = FPS_CAPS.iter().position(|&cap| cap == settings.graphics.fps_cap);
+        settings.graphics.fps_cap = FPS_CAPS[current.map_or(0, |index| (index + 1) % FPS_CAPS.len())];
+        match settings.graphics.fps_cap {
+            Some(cap) => info!("Frame rate capped at {}", cap),
+            None => info!("Frame rate uncapped"),
+        }
+    }
+}
+
+// System to present frames the way the options ask, on the window already open
+fn present_mode_system(
+    graphics_settings: Res<GraphicsSettings>,                 // The options in use
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>, // Get the window to change
+) {
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+    let present_mode = graphics_settings.vsync.present_mode();
+    if window.present_mode != present_mode {
+        window.present_mode = present_mode;
+    }
+}
+
+// System to hold each frame back until its share of a second under the cap is up; runs last so
+// the wait covers everything the frame did. The browser paces frames itself, so the web build
+// goes without it
+#[cfg(not(target_arch = "wasm32"))]
+fn frame_limiter_system(
+    graphics_settings: Res<GraphicsSettings>,                 // The cap in use
+    mut frame_end: Local<Option<Instant>>,                    // When the last frame was let go
+) {
+    if let (Some(cap), Some(frame_end)) = (graphics_settings.fps_cap, *frame_end) {
+        let frame_time = Duration::from_secs_f32(1.0 / cap.max(1.0));
+        let elapsed = frame_end.elapsed();
+        if elapsed < frame_time {
+            std::thread::sleep(frame_time - elapsed);
+        }
+    }
+    *frame_end = Some(Instant::now());
+}
+
+// Plugin for the present mode and frame rate cap
+pub struct GraphicsPlugin;
+
+impl Plugin for GraphicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GraphicsSettings>()
+            .init_resource::<GraphicsSettings>()
+            .add_systems(Update, (
+                graphics_keys_system,
+                graphics_settings_system.run_if(resource_changed::<Settings>()),
+                present_mode_system.run_if(resource_changed::<GraphicsSettings>()),
+            ).chain());
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(Last, frame_limiter_system);
+    }
+}
@@ -0,0 +1,205 @@
+// Running the simulation without a window, one fixed tick per update, for checking gameplay
+// from code: no rendering, no audio and nothing written to disk
+use bevy::asset::AssetPlugin;
+use bevy::ecs::system::SystemState;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy::render::render_resource::Shader;
+use bevy::time::TimeUpdateStrategy;
+use bevy::utils::Duration;
+use bevy::window::{WindowFocused, WindowResized};
+
+use crate::cli::CliArgs;
+use crate::combat::CombatPlugin;
+use crate::combo::ComboPlugin;
+use crate::config::GameConfig;
+use crate::debris::DebrisPlugin;
+use crate::difficulty::DifficultyPlugin;
+use crate::hostiles::{AsteroidSpawner, HostilesPlugin};
+use crate::input::{InputPlugin, PlayerInput, PlayerInputSet, PlayerInputs};
+use crate::loading::LoadingPlugin;
+use crate::palette::PalettePlugin;
+use crate::physics::{FIXED_TICK_RATE, PhysicsPlugin, WorldBounds};
+use crate::player::{Loadout, MAX_PLAYERS, Player, PlayerPlugin, ShipAssets, spawn_ship};
+use crate::pool::PoolPlugin;
+use crate::practice::PracticePlugin;
+use crate::session::{GameState, GameplayEntity, SessionPlugin};
+use crate::settings::Settings;
+use crate::trail::{TrailPlugin, TrailSettings};
+use crate::weapons::WeaponsPlugin;
+use crate::zone::SafeZonePlugin;
+use crate::configure_game_sets;
+
+// Size of the arena in a headless app, since there is no window to size it after
+pub const HEADLESS_ARENA: Vec2 = Vec2::new(800.0, 600.0);
+
+// Resource holding the input a test drives the players with, in place of whatever the keyboard
+// gave; a player without any is left to the keyboard
+#[derive(Resource, Default)]
+pub struct InjectedInputs(pub [Option<PlayerInput>; MAX_PLAYERS]);
+
+// System to write the injected input over what the input systems read this frame
+fn injected_input_system(
+    injected: Res<InjectedInputs>,                            // Input the test holds
+    mut player_inputs: ResMut<PlayerInputs>,                  // Where the intent is written
+) {
+    for (player_input, injected) in player_inputs.iter_mut().zip(injected.0) {
+        if let Some(injected) = injected {
+            *player_input = injected;
+        }
+    }
+}
+
+// An app running the simulation of a run and nothing else, already playing with an empty arena;
+// every update advances the game clock by exactly one fixed tick, so the same updates with the
+// same input always end the same way
+pub fn headless_app() -> App {
+    let mut app = simulation_app(AssetPlugin::default());
+
+    // Through the startup systems and the loading screen, keeping none of what they spawned
+    app.world.resource_mut::<NextState<GameState>>().set(GameState::Playing);
+    app.update();
+    clear_arena(&mut app);
+    app
+}
+
+// An app like headless_app, but still to boot: it goes through the loading screen on its own,
+// reading its images from the given folder
+pub fn loading_app(asset_root: &str) -> App {
+    let mut app = simulation_app(AssetPlugin {
+        file_path: asset_root.to_string(),
+        ..default()
+    });
+    app.add_plugins(LoadingPlugin);
+    app
+}
+
+// The engine and gameplay plugins of a headless app, with its assets read as the given plugin says
+fn simulation_app(asset_plugin: AssetPlugin) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        HierarchyPlugin,
+        bevy::input::InputPlugin,
+        asset_plugin,
+    ))
+    // What the gameplay expects the rest of the engine to have set up
+    .init_asset::<Image>()
+    .init_asset::<TextureAtlas>()
+    .init_asset::<Shader>()
+    .add_plugins(bevy::gizmos::GizmoPlugin)
+    .add_event::<WindowResized>()
+    .add_event::<WindowFocused>()
+    // Defaults in place of what would be read from disk
+    .init_resource::<CliArgs>()
+    // Nothing is drawn, so the transforms are left where the simulation puts them
+    .insert_resource(Settings {
+        smooth_motion: false,
+        ..default()
+    })
+    .init_resource::<GameConfig>()
+    .insert_resource(WorldBounds {
+        width: HEADLESS_ARENA.x,
+        height: HEADLESS_ARENA.y,
+    })
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / FIXED_TICK_RATE)))
+    .add_plugins((
+        SessionPlugin,
+        InputPlugin,
+        PalettePlugin,
+        PlayerPlugin,
+        PhysicsPlugin,
+        CombatPlugin,
+        WeaponsPlugin,
+        HostilesPlugin,
+        DifficultyPlugin,
+        TrailPlugin,
+        PoolPlugin,
+        ComboPlugin,
+        SafeZonePlugin,
+        DebrisPlugin,
+        PracticePlugin,
+    ))
+    .init_resource::<InjectedInputs>()
+    .add_systems(PreUpdate, injected_input_system.after(PlayerInputSet));
+    configure_game_sets(&mut app);
+    app
+}
+
+// Run the given number of fixed ticks
+pub fn step(app: &mut App, ticks: u32) {
+    for _ in 0..ticks {
+        app.update();
+    }
+}
+
+// Despawn every ship, hostile, shot and effect, for a test to set up exactly what it needs
+pub fn clear_arena(app: &mut App) {
+    let entities: Vec<Entity> = app
+        .world
+        .query_filtered::<Entity, With<GameplayEntity>>()
+        .iter(&app.world)
+        .collect();
+    for entity in entities {
+        despawn_with_children_recursive(&mut app.world, entity);
+    }
+}
+
+// What spawning a ship from outside a system needs
+type ShipSpawnParams<'w, 's> = (Commands<'w, 's>, Res<'w, ShipAssets>, Res<'w, TrailSettings>, Res<'w, Loadout>);
+
+// Spawn the first player's ship at the middle of the arena, fitted out like a new run's
+pub fn spawn_test_ship(app: &mut App) -> Entity {
+    let mut system_state: SystemState<ShipSpawnParams> = SystemState::new(&mut app.world);
+    let (mut commands, ship_assets, trail_settings, loadout) = system_state.get_mut(&mut app.world);
+    let ship = spawn_ship(&mut commands, &ship_assets, &trail_settings, &loadout, Player { id: 0 }, Vec2::ZERO);
+    system_state.apply(&mut app.world);
+    ship
+}
+
+// Spawn or change entities through Commands, the way a system would, and apply it straight away
+pub fn with_commands<T>(app: &mut App, f: impl FnOnce(&mut Commands) -> T) -> T {
+    let mut system_state: SystemState<Commands> = SystemState::new(&mut app.world);
+    let mut commands = system_state.get_mut(&mut app.world);
+    let result = f(&mut commands);
+    system_state.apply(&mut app.world);
+    result
+}
+
+// Drive a player with the given input from the next update on, until release_input
+pub fn hold_input(app: &mut App, player_id: usize, input: PlayerInput) {
+    app.world.resource_mut::<InjectedInputs>().0[player_id] = Some(input);
+}
+
+// Hand a player driven by hold_input back to the keyboard
+pub fn release_input(app: &mut App, player_id: usize) {
+    app.world.resource_mut::<InjectedInputs>().0[player_id] = None;
+}
+
+// Stop asteroids and mines coming in, for a test that wants the arena to itself
+pub fn stop_spawning(app: &mut App) {
+    app.world.resource_mut::<GameConfig>().asteroids.max_count = 0;
+    app.world.resource_mut::<AsteroidSpawner>().max_count = 0;
+}
+
+// Hold a key down from the next update on, as if it was pressed on the keyboard
+pub fn press_key(app: &mut App, key: KeyCode) {
+    send_key(app, key, ButtonState::Pressed);
+}
+
+// Let go of a key held with press_key
+pub fn release_key(app: &mut App, key: KeyCode) {
+    send_key(app, key, ButtonState::Released);
+}
+
+// Send a key change through the engine's input handling, so just_pressed works as in the game
+fn send_key(app: &mut App, key: KeyCode, state: ButtonState) {
+    app.world.send_event(KeyboardInput {
+        scan_code: 0,
+        key_code: Some(key),
+        state,
+        window: Entity::PLACEHOLDER,
+    });
+}
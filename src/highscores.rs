@@ -0,0 +1,320 @@
+// The best runs kept on disk, with arcade-style name entry when a run ends
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use crate::player::{MAX_PLAYERS, PlayerCount};
+use crate::session::{GameMode, GameState, NewGameEvent, Score};
+use crate::storage::{self, data_path, unix_time};
+
+// How many runs the table keeps
+const MAX_HIGH_SCORES: usize = 10;
+
+// Letters in a name
+const NAME_LENGTH: usize = 3;
+
+// Key showing and hiding the table during a run
+const HIGH_SCORES_KEY: KeyCode = KeyCode::H;
+
+// Key starting the next run on the practice range instead of a normal run
+const PRACTICE_KEY: KeyCode = KeyCode::R;
+
+// One run in the table
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct HighScore {
+    pub name: String,  // Initials entered after the run
+    pub score: u32,    // Points the run ended with
+    pub when: u64,     // Seconds since the Unix epoch when the run ended
+}
+
+// The best runs, highest score first and the most recent first among equal scores
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Eq, Debug)]
+pub struct HighScoreTable {
+    pub entries: Vec<HighScore>,  // At most MAX_HIGH_SCORES runs, best first
+}
+
+impl HighScoreTable {
+    // Write the table to a RON file
+    fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        storage::write(path, &ron::to_string(self)?)?;
+        Ok(())
+    }
+
+    // Read a table back from a RON file, putting it back in order in case it was edited by hand
+    fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let mut table: Self = ron::from_str(&storage::read(path)?)?;
+        table.entries.sort_by(|a, b| b.score.cmp(&a.score).then(b.when.cmp(&a.when)));
+        table.entries.truncate(MAX_HIGH_SCORES);
+        Ok(table)
+    }
+
+    // Read the table, starting a fresh one when there is none yet or it can't be read
+    fn load_or_default(path: &Path) -> Self {
+        if !storage::exists(path) {
+            return Self::default();
+        }
+        Self::load(path).unwrap_or_else(|err| {
+            warn!("Failed to read the high scores from {}, starting a new table: {}", path.display(), err);
+            Self::default()
+        })
+    }
+
+    // Whether a run ending with this score makes it into the table
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < MAX_HIGH_SCORES
+                || self.entries.last().is_some_and(|last| score >= last.score))
+    }
+
+    // Add a run ahead of every run it ties with, returning its place if it made the table
+    pub fn insert(&mut self, entry: HighScore) -> Option<usize> {
+        let place = self.entries.iter().position(|other| other.score <= entry.score).unwrap_or(self.entries.len());
+        if place >= MAX_HIGH_SCORES {
+            return None;
+        }
+        self.entries.insert(place, entry);
+        self.entries.truncate(MAX_HIGH_SCORES);
+        Some(place)
+    }
+}
+
+// Where the table lives
+fn high_scores_path() -> PathBuf {
+    data_path("high_scores.ron")
+}
+
+// Where the game-over screen is up to
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+enum GameOverStage {
+    #[default]
+    Hidden,  // The run is still going
+    // A player with a qualifying score picks their initials
+    NameEntry {
+        player: usize,                // Whose score is being entered
+        letters: [u8; NAME_LENGTH],   // Initials so far, as offsets from 'A'
+        cursor: usize,                // Letter being changed
+    },
+    Table,   // Every name is in; the table is shown until a new run starts
+}
+
+// Resource holding the table and the game-over screen showing it
+#[derive(Resource, Default)]
+struct HighScores {
+    table: HighScoreTable,          // The best runs so far
+    stage: GameOverStage,           // Where the game-over screen is up to
+    entered: [bool; MAX_PLAYERS],   // Players whose score this game over has already dealt with
+    highlight: Vec<usize>,          // Places of the runs that just made the table
+    browsing: bool,                 // Whether the table is shown during a run
+}
+
+impl HighScores {
+    // Ask the next player with a qualifying score for their name, or show the table
+    fn advance(&mut self, score: &Score, player_count: &PlayerCount) {
+        let next = player_count.ids().find(|&id| !self.entered[id] && self.table.qualifies(score.0[id]));
+        self.stage = match next {
+            Some(player) => GameOverStage::NameEntry {
+                player,
+                letters: [0; NAME_LENGTH],
+                cursor: 0,
+            },
+            None => GameOverStage::Table,
+        };
+    }
+}
+
+// Marker for the panel showing name entry and the table
+#[derive(Component)]
+struct HighScoreText;
+
+// Setup system that reads the table and builds its panel
+fn setup_high_scores(mut commands: Commands, mut high_scores: ResMut<HighScores>) {
+    high_scores.table = HighScoreTable::load_or_default(&high_scores_path());
+
+    // Spawn the panel in the middle of the screen, hidden while a run is going
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        })
+        .insert(HighScoreText)
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 28.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+}
+
+// System to bring up the game-over screen as the game over begins
+fn game_over_system(
+    score: Res<Score>,                                        // Final score of every player
+    player_count: Res<PlayerCount>,                           // Who took part
+    mut high_scores: ResMut<HighScores>,                      // The screen to update
+) {
+    high_scores.entered = [false; MAX_PLAYERS];
+    high_scores.highlight.clear();
+    high_scores.browsing = false;
+    high_scores.advance(&score, &player_count);
+}
+
+// System to take the game-over screen down once a run is going again
+fn game_over_exit_system(mut high_scores: ResMut<HighScores>) {
+    high_scores.stage = GameOverStage::Hidden;
+}
+
+// System to pick initials with the arrow keys, and start a new run or the practice range once
+// the table is shown
+fn game_over_input_system(
+    keyboard_input: Res<Input<KeyCode>>,                      // For keyboard input
+    score: Res<Score>,                                        // Score the name goes with
+    player_count: Res<PlayerCount>,                           // Who else may have a name to enter
+    mut high_scores: ResMut<HighScores>,                      // The screen and table to update
+    mut new_game_events: EventWriter<NewGameEvent>,           // For starting the next run
+    mut next_mode: ResMut<NextState<GameMode>>,               // For picking the kind of run
+) {
+    let high_scores = &mut *high_scores;
+    match &mut high_scores.stage {
+        GameOverStage::Hidden => {
+            if keyboard_input.just_pressed(HIGH_SCORES_KEY) {
+                high_scores.browsing = !high_scores.browsing;
+            }
+        }
+        GameOverStage::NameEntry { player, letters, cursor } => {
+            let letter = &mut letters[*cursor];
+            if keyboard_input.just_pressed(KeyCode::Up) {
+                *letter = (*letter + 1) % 26;
+            }
+            if keyboard_input.just_pressed(KeyCode::Down) {
+                *letter = (*letter + 25) % 26;
+            }
+            if keyboard_input.just_pressed(KeyCode::Left) {
+                *cursor = cursor.saturating_sub(1);
+            }
+            if keyboard_input.just_pressed(KeyCode::Right) {
+                *cursor = (*cursor + 1).min(NAME_LENGTH - 1);
+            }
+            if !keyboard_input.just_pressed(KeyCode::Return) {
+                return;
+            }
+
+            // The most recent run goes first among equal scores, so its time breaks the tie
+            let player = *player;
+            let entry = HighScore {
+                name: letters.iter().map(|letter| (b'A' + letter) as char).collect(),
+                score: score.0[player],
+                when: unix_time(),
+            };
+            if let Some(place) = high_scores.table.insert(entry) {
+                // Earlier highlights slide down when this run goes in ahead of them
+                for other in high_scores.highlight.iter_mut().filter(|other| **other >= place) {
+                    *other += 1;
+                }
+                high_scores.highlight.retain(|other| *other < MAX_HIGH_SCORES);
+                high_scores.highlight.push(place);
+            }
+            let path = high_scores_path();
+            if let Err(err) = high_scores.table.save(&path) {
+                warn!("Failed to save the high scores to {}: {}", path.display(), err);
+            }
+            high_scores.entered[player] = true;
+            high_scores.advance(&score, &player_count);
+        }
+        GameOverStage::Table => {
+            let mode = if keyboard_input.just_pressed(KeyCode::Return) {
+                GameMode::Arcade
+            } else if keyboard_input.just_pressed(PRACTICE_KEY) {
+                GameMode::Practice
+            } else {
+                return;
+            };
+            next_mode.set(mode);
+            new_game_events.send(NewGameEvent::default());
+        }
+    }
+}
+
+// System to show name entry or the table, whichever the screen is up to
+fn high_score_text_system(
+    high_scores: Res<HighScores>,                             // What to show
+    score: Res<Score>,                                        // Score the name goes with
+    mut node_query: Query<(&mut Visibility, &Children), With<HighScoreText>>, // Get the panel
+    mut text_query: Query<&mut Text>,                         // Get the words on the panel
+) {
+    let Ok((mut visibility, children)) = node_query.get_single_mut() else {
+        return;
+    };
+    let value = match &high_scores.stage {
+        GameOverStage::Hidden if !high_scores.browsing => {
+            *visibility = Visibility::Hidden;
+            return;
+        }
+        GameOverStage::NameEntry { player, letters, cursor } => {
+            let name = letters
+                .iter()
+                .enumerate()
+                .map(|(index, letter)| {
+                    let letter = (b'A' + letter) as char;
+                    if index == *cursor { format!("[{}]", letter) } else { format!(" {} ", letter) }
+                })
+                .collect::<String>();
+            format!(
+                "GAME OVER\n\nPlayer {} made the high scores with {}\n\n{}\n\nUp/Down to change a letter, Left/Right to move, Enter to confirm",
+                player + 1,
+                score.0[*player],
+                name
+            )
+        }
+        stage => {
+            let mut lines = vec!["HIGH SCORES".to_string(), String::new()];
+            if high_scores.table.entries.is_empty() {
+                lines.push("No runs yet".to_string());
+            }
+            for (place, entry) in high_scores.table.entries.iter().enumerate() {
+                let marker = if high_scores.highlight.contains(&place) { "  <" } else { "" };
+                lines.push(format!("{:>2}. {}  {:>8}{}", place + 1, entry.name, entry.score, marker));
+            }
+            lines.push(String::new());
+            lines.push(if *stage == GameOverStage::Table {
+                format!("GAME OVER - press Enter to play again, or {:?} for the practice range", PRACTICE_KEY)
+            } else {
+                format!("Press {:?} to return to the game", HIGH_SCORES_KEY)
+            });
+            lines.join("\n")
+        }
+    };
+    *visibility = Visibility::Inherited;
+    for &child in children.iter() {
+        if let Ok(mut text) = text_query.get_mut(child) {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+// Plugin for the high-score table
+pub struct HighScoresPlugin;
+
+impl Plugin for HighScoresPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HighScores>()
+            .add_systems(Startup, setup_high_scores)
+            .add_systems(OnEnter(GameState::GameOver), game_over_system)
+            .add_systems(OnExit(GameState::GameOver), game_over_exit_system)
+            .add_systems(Update, (
+                game_over_input_system,
+                high_score_text_system,
+            ).chain().run_if(in_state(GameState::Playing).or_else(in_state(GameState::GameOver))));
+    }
+}
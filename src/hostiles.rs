@@ -0,0 +1,420 @@
+// Asteroids and enemy drones, how they spawn and how they are destroyed
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use rand::Rng;
+use std::time::Duration;
+
+use crate::combat::{CollisionDetectionSet, CollisionEvent, Health, LastHitBy};
+use crate::combo::ComboMeter;
+use crate::config::GameConfig;
+use crate::difficulty::Difficulty;
+use crate::effects::spawn_explosion;
+use crate::physics::{Collider, Interpolated, Mass, Velocity, WorldBounds, nearest_wrapped};
+use crate::palette::Tint;
+use crate::player::{Disabled, Starship, player_exists};
+use crate::session::{GameMode, GameRng, GameState, GameplayEntity, NewGameEvent, Score};
+use crate::GameSet;
+
+// Define the ScoreValue component holding the points awarded for destroying an entity
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+struct ScoreValue(u32);
+
+// Marker for asteroid entities
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Asteroid;
+
+// Define the Enemy component for drones that chase the ship
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Enemy {
+    thrust: f32,     // Acceleration towards the ship
+    max_speed: f32,  // Fastest the drone can fly
+}
+
+// Define the EmpMine component for drifting mines that knock out the engine of a ship touching them
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct EmpMine {
+    duration: f32,  // Seconds the engine stays out
+}
+
+// Marker for anything that hurts the ship and can be shot down
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Hostile;
+
+// Event sent when an asteroid or enemy is destroyed
+#[derive(Event)]
+pub struct HostileDestroyed {
+    pub position: Vec2,  // Where the hostile was
+}
+
+// Resource controlling when new asteroids appear and how they come in
+#[derive(Resource)]
+pub struct AsteroidSpawner {
+    pub timer: Timer,               // Time between two spawns
+    pub interval: f32,              // Seconds between two spawns at the start of a run
+    pub max_count: usize,           // No spawns while this many asteroids are alive
+    pub speed: (f32, f32),          // Slowest and fastest an asteroid drifts in, before the ramp
+    pub radius: (f32, f32),         // Smallest and biggest asteroid
+    pub emp_chance: f64,            // Chance of an EMP mine coming in instead of an asteroid
+    pub safe_distance: f32,         // Closest to a ship an asteroid or mine comes in
+}
+
+impl Default for AsteroidSpawner {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(2.0, TimerMode::Repeating),
+            interval: 2.0,
+            max_count: 12,
+            speed: (40.0, 120.0),
+            radius: (15.0, 40.0),
+            emp_chance: 0.1,
+            safe_distance: 200.0,
+        }
+    }
+}
+
+impl AsteroidSpawner {
+    // Seconds between two spawns at the given difficulty
+    pub fn current_interval(&self, difficulty: &Difficulty) -> f32 {
+        self.interval / difficulty.spawn_rate
+    }
+}
+
+// Tries at a random spot on the edges before settling for the farthest one from the ships
+const MAX_SPAWN_ATTEMPTS: usize = 8;
+
+// Spots along each edge checked for the farthest one from the ships
+const SPAWN_EDGE_SAMPLES: usize = 16;
+
+// The point on the given edge (left, right, bottom, top) of the play area, `along` it from -1.0 to 1.0
+fn edge_point(edge: usize, along: f32, half_extents: Vec2) -> Vec2 {
+    match edge {
+        0 => Vec2::new(-half_extents.x, along * half_extents.y),
+        1 => Vec2::new(half_extents.x, along * half_extents.y),
+        2 => Vec2::new(along * half_extents.x, -half_extents.y),
+        _ => Vec2::new(along * half_extents.x, half_extents.y),
+    }
+}
+
+// Pick a random point along the edges of the play area at least the given distance from every
+// ship, measured across the seams the ships wrap over. When none of the tries gets that far, the
+// spot along the edges farthest from the ships is used instead
+pub fn random_spawn_position(rng: &mut impl Rng, half_extents: Vec2, ships: &[Vec2], min_distance: f32) -> Vec2 {
+    let clearance = |position: Vec2| {
+        ships
+            .iter()
+            .map(|&ship| ship.distance(nearest_wrapped(position, ship, half_extents)))
+            .fold(f32::INFINITY, f32::min)
+    };
+    for _ in 0..MAX_SPAWN_ATTEMPTS {
+        let along = rng.gen_range(-1.0..1.0);
+        let position = edge_point(rng.gen_range(0..4), along, half_extents);
+        if clearance(position) >= min_distance {
+            return position;
+        }
+    }
+    (0..4)
+        .flat_map(|edge| {
+            (0..SPAWN_EDGE_SAMPLES).map(move |sample| {
+                let along = 2.0 * sample as f32 / (SPAWN_EDGE_SAMPLES - 1) as f32 - 1.0;
+                edge_point(edge, along, half_extents)
+            })
+        })
+        .max_by(|a, b| clearance(*a).total_cmp(&clearance(*b)))
+        .unwrap_or(Vec2::ZERO)
+}
+
+// The edges of the play area and the ships to keep new hostiles away from
+#[derive(SystemParam)]
+struct SpawnArea<'w, 's> {
+    arena: Res<'w, WorldBounds>,                              // Size of the play area
+    ship_query: Query<'w, 's, &'static Transform, With<Starship>>, // Get the ships to keep clear of
+}
+
+impl SpawnArea<'_, '_> {
+    // A random point along the edges, at least the given distance from every ship where it can be
+    fn position(&self, rng: &mut impl Rng, min_distance: f32) -> Vec2 {
+        let ships: Vec<Vec2> = self.ship_query.iter().map(|transform| transform.translation.truncate()).collect();
+        random_spawn_position(rng, self.arena.half_extents(), &ships, min_distance)
+    }
+}
+
+// System to spawn asteroids drifting in from the screen edges
+fn asteroid_spawn_system(
+    mut commands: Commands,                                   // For spawning asteroids
+    time: Res<Time>,                                          // For the spawn timer
+    mut spawner: ResMut<AsteroidSpawner>,                     // Spawn timing and limits
+    asteroid_query: Query<(), With<Asteroid>>,                // Count live asteroids
+    spawn_area: SpawnArea,                                    // Where asteroids can come in
+    mut rng: ResMut<GameRng>,                                 // For random positions and sizes
+    difficulty: Res<Difficulty>,                              // How often and how fast they come
+) {
+    // Keep up with the difficulty ramp without restarting the countdown
+    let interval = Duration::from_secs_f32(spawner.current_interval(&difficulty));
+    spawner.timer.set_duration(interval);
+    if !spawner.timer.tick(time.delta()).just_finished()
+        || asteroid_query.iter().count() >= spawner.max_count
+    {
+        return;
+    }
+    let half = spawn_area.arena.half_extents();
+
+    // Pick a random point along a random edge of the screen, clear of the ships
+    let position = spawn_area.position(&mut rng.rng, spawner.safe_distance);
+
+    // Drift roughly towards the middle of the screen
+    let target = Vec2::new(rng.gen_range(-0.5..0.5), rng.gen_range(-0.5..0.5)) * half;
+    let speed = rng.gen_range(spawner.speed.0..=spawner.speed.1) * difficulty.speed;
+    let velocity = (target - position).normalize_or_zero() * speed;
+    let radius = rng.gen_range(spawner.radius.0..=spawner.radius.1);
+
+    if rng.gen_bool(spawner.emp_chance.clamp(0.0, 1.0)) {
+        spawn_emp_mine(&mut commands, position, velocity);
+    } else {
+        spawn_asteroid(&mut commands, position, velocity, radius);
+    }
+}
+
+// Radius of an asteroid as heavy as a ship; bigger ones weigh more by their area
+const ASTEROID_UNIT_RADIUS: f32 = 20.0;
+
+// Spawn an asteroid, tougher and heavier the bigger it is
+pub fn spawn_asteroid(commands: &mut Commands, position: Vec2, velocity: Vec2, radius: f32) -> Entity {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(radius * 2.0)),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(0.0)),
+                ..default()
+            },
+            Tint::Asteroid,
+            GameplayEntity,
+            Asteroid,
+            Hostile,
+            Interpolated,
+            Velocity {
+                x: velocity.x,
+                y: velocity.y,
+            },
+            Collider { radius },
+            Mass((radius / ASTEROID_UNIT_RADIUS).powi(2)),
+            Health {
+                current: radius,
+                max: radius,
+            },
+            ScoreValue(10),
+        ))
+        .id()
+}
+
+// Spawn an enemy drone that chases the ship
+pub fn spawn_enemy(commands: &mut Commands, position: Vec2) -> Entity {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::new(16.0, 22.0)),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(0.0)),
+                ..default()
+            },
+            Tint::Enemy,
+            GameplayEntity,
+            Enemy {
+                thrust: 80.0,
+                max_speed: 160.0,
+            },
+            Hostile,
+            Interpolated,
+            Velocity { x: 0.0, y: 0.0 },
+            Collider { radius: 12.0 },
+            Mass::default(),
+            Health {
+                current: 30.0,
+                max: 30.0,
+            },
+            ScoreValue(25),
+        ))
+        .id()
+}
+
+// Spawn an EMP mine, drifting along until a ship runs into it or it is shot down
+pub fn spawn_emp_mine(commands: &mut Commands, position: Vec2, velocity: Vec2) -> Entity {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(20.0)),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(0.0)),
+                ..default()
+            },
+            Tint::EmpMine,
+            GameplayEntity,
+            EmpMine { duration: 3.0 },
+            Hostile,
+            Interpolated,
+            Velocity {
+                x: velocity.x,
+                y: velocity.y,
+            },
+            Collider { radius: 12.0 },
+            Mass::default(),
+            Health {
+                current: 20.0,
+                max: 20.0,
+            },
+            ScoreValue(15),
+        ))
+        .id()
+}
+
+// System to knock out the engine of ships running into an EMP mine, using the mine up; the impact
+// itself still deals collision damage
+fn emp_contact_system(
+    mut commands: Commands,                                   // For disabling ships
+    mut collision_events: EventReader<CollisionEvent>,        // Overlaps found this tick
+    ship_query: Query<(), With<Starship>>,                    // Tell the ships from projectiles
+    mut mine_query: Query<(&EmpMine, &mut Health)>,           // Get the mines
+) {
+    for event in collision_events.read() {
+        let Ok((mine, mut health)) = mine_query.get_mut(event.b) else {
+            continue;
+        };
+        if !ship_query.contains(event.a) {
+            continue;
+        }
+        // Another mine restarts the timer rather than adding to it
+        commands.entity(event.a).insert(Disabled {
+            timer: Timer::from_seconds(mine.duration, TimerMode::Once),
+        });
+        // Destroyed like any other hostile, without scoring for whoever flew into it or shot it before
+        health.current = 0.0;
+        commands.entity(event.b).remove::<LastHitBy>();
+    }
+}
+
+// System to steer enemy drones towards the nearest ship
+fn enemy_seek_system(
+    time: Res<Time>,                                          // For frame-independent steering
+    ship_query: Query<&Transform, With<Starship>>,            // Get the ships to chase
+    mut enemy_query: Query<(&Enemy, &mut Transform, &mut Velocity), Without<Starship>>, // Get the drones
+    difficulty: Res<Difficulty>,                              // How fast the drones fly
+) {
+    for (enemy, mut transform, mut velocity) in enemy_query.iter_mut() {
+        let position = transform.translation.truncate();
+        // Drift on while there's no ship to chase
+        let Some(ship_position) = ship_query
+            .iter()
+            .map(|ship_transform| ship_transform.translation.truncate())
+            .min_by(|a, b| a.distance_squared(position).total_cmp(&b.distance_squared(position)))
+        else {
+            continue;
+        };
+        let direction = (ship_position - position).normalize_or_zero();
+        let steered = (velocity.as_vec2() + direction * enemy.thrust * difficulty.speed * time.delta_seconds())
+            .clamp_length_max(enemy.max_speed * difficulty.speed);
+        velocity.x = steered.x;
+        velocity.y = steered.y;
+        // Point the nose (local +Y) along the direction of travel
+        if steered.length_squared() > f32::EPSILON {
+            transform.rotation = Quat::from_rotation_z((-steered.x).atan2(steered.y));
+        }
+    }
+}
+
+// Components of a hostile involved in its destruction
+type HostileDestroyData<'a> = (
+    Entity,
+    &'a Transform,
+    &'a Health,
+    &'a Collider,
+    Option<&'a ScoreValue>,
+    Option<&'a LastHitBy>,
+);
+
+// System to remove asteroids and enemies that have run out of health
+fn hostile_destroy_system(
+    mut commands: Commands,                                   // For despawning asteroids
+    query: Query<HostileDestroyData, With<Hostile>>,          // Get every hostile
+    mut score: ResMut<Score>,                                 // For awarding points
+    mut destroyed_events: EventWriter<HostileDestroyed>,      // For reacting to the kill
+    mut rng: ResMut<GameRng>,                                 // For the explosion debris
+    mut combo: ResMut<ComboMeter>,                            // Kills in quick succession
+    config: Res<GameConfig>,                                  // What a combo multiplies by
+) {
+    for (entity, transform, health, collider, score_value, last_hit_by) in query.iter() {
+        if health.current <= 0.0 {
+            // The points go to whoever landed the last hit, multiplied by the combo it adds to
+            if let (Some(value), Some(last_hit_by)) = (score_value, last_hit_by) {
+                score.0[last_hit_by.0] += value.0 * combo.register_kill(&config.combo);
+            }
+            let position = transform.translation.truncate();
+            destroyed_events.send(HostileDestroyed { position });
+            spawn_explosion(&mut commands, &mut rng, position, collider.radius);
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// System to restart the asteroid timing when a new run starts
+fn reset_spawner_system(
+    mut new_game_events: EventReader<NewGameEvent>,           // Requests for a new run
+    mut spawner: ResMut<AsteroidSpawner>,                     // Asteroid timing to restart
+) {
+    if new_game_events.read().count() > 0 {
+        spawner.timer.reset();
+    }
+}
+
+// System to take the asteroid spawning from the game config as it is loaded or reloaded
+fn asteroid_config_system(
+    config: Res<GameConfig>,                                  // The balance values
+    mut spawner: ResMut<AsteroidSpawner>,                     // Spawning to tune
+) {
+    let asteroids = &config.asteroids;
+    spawner.interval = asteroids.spawn_interval;
+    spawner.max_count = asteroids.max_count;
+    spawner.speed = (asteroids.min_speed, asteroids.max_speed);
+    spawner.radius = (asteroids.min_radius, asteroids.max_radius);
+    spawner.emp_chance = asteroids.emp_chance;
+    spawner.safe_distance = asteroids.safe_distance;
+}
+
+// Label for the system sending HostileDestroyed, so readers in the same tick can run after it
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HostileDestroySet;
+
+// Plugin for asteroids and enemies
+pub struct HostilesPlugin;
+
+impl Plugin for HostilesPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Asteroid>()
+            .register_type::<Enemy>()
+            .register_type::<EmpMine>()
+            .register_type::<Hostile>()
+            .register_type::<ScoreValue>()
+            .init_resource::<AsteroidSpawner>()
+            .add_event::<HostileDestroyed>()
+            .add_systems(Update, reset_spawner_system)
+            // Tuned before the frame's ticks, so the first spawn already follows the config
+            .add_systems(PreUpdate, asteroid_config_system.run_if(resource_changed::<GameConfig>()))
+            .add_systems(FixedUpdate, (
+                enemy_seek_system.run_if(player_exists).in_set(GameSet::Input),
+                // The last hit is forgotten before the mine is cleared away in the same tick
+                (emp_contact_system, apply_deferred).chain().after(CollisionDetectionSet).in_set(GameSet::Collision),
+                hostile_destroy_system.in_set(HostileDestroySet).in_set(GameSet::Cleanup),
+                asteroid_spawn_system.in_set(GameSet::Cleanup).run_if(in_state(GameMode::Arcade)),
+            ).run_if(in_state(GameState::Playing)));
+    }
+}
@@ -0,0 +1,839 @@
+// On-screen gauges, counters and threat indicators
+use bevy::audio::{Pitch, PitchBundle};
+use bevy::prelude::*;
+use bevy::utils::Duration;
+use std::f32::consts::TAU;
+
+use crate::camera::PlayerCamera;
+use crate::combat::{Health, Shield};
+use crate::combo::ComboMeter;
+use crate::config::GameConfig;
+use crate::difficulty::SurvivalTimer;
+use crate::hostiles::Hostile;
+use crate::input::{Action, KeyBindings, ThrustLatch};
+use crate::inventory::{Inventory, Item};
+use crate::palette::ColorScheme;
+use crate::physics::{Collider, InterpolationSet};
+use crate::player::{Disabled, Engine, Player, PlayerCount, Starship, player_exists};
+use crate::session::{AutoPause, GameMode, GameState, Lives, Score};
+use crate::weapons::{AmmoPool, BombSettings, Bombs, HEAT_WARNING_FRACTION, Heat, Weapon};
+
+// Size of the combo text when it isn't pulsing
+const COMBO_FONT_SIZE: f32 = 24.0;
+
+// How much bigger the combo text grows at the start of a pulse
+const COMBO_PULSE_GROWTH: f32 = 0.5;
+
+// Seconds a pulse takes to shrink back
+const COMBO_PULSE_TIME: f32 = 0.25;
+
+// Fewest kills in a combo before it shows
+const COMBO_SHOWN_FROM: u32 = 2;
+
+// Pitch in hertz and length in seconds of the low fuel beep
+const LOW_FUEL_BEEP_PITCH: f32 = 880.0;
+const LOW_FUEL_BEEP_LENGTH: f32 = 0.12;
+
+// How fast the shield bar pulses while recharging, in radians per second
+const SHIELD_PULSE_SPEED: f32 = 8.0;
+
+// Opacity of the shield bar while the shield waits to recharge
+const SHIELD_WAITING_OPACITY: f32 = 0.35;
+
+// Resource controlling the arrows pointing at off-screen threats
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+struct ThreatIndicatorSettings {
+    margin: f32,         // Distance of the arrow from the window edge, in pixels
+    size: f32,           // Length of the arrow, in pixels
+    fade_distance: f32,  // World distance beyond which the arrow stays at its faintest
+    color: Color,        // Color of the arrow at full opacity
+}
+
+impl Default for ThreatIndicatorSettings {
+    fn default() -> Self {
+        Self {
+            margin: 24.0,
+            size: 16.0,
+            fade_distance: 1200.0,
+            color: Color::rgb(1.0, 0.3, 0.2),
+        }
+    }
+}
+
+// Resource controlling the rings drawn around each ship while its abilities recharge
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct CooldownRingSettings {
+    pub radius: f32,          // Radius of the innermost ring
+    pub spacing: f32,         // Gap between two rings
+    pub bomb_color: Color,    // Ring of the smart bomb, innermost
+    pub engine_color: Color,  // Ring of an engine knocked out by an EMP
+    pub weapon_color: Color,  // Ring of the gun between two shots, outermost
+}
+
+impl Default for CooldownRingSettings {
+    fn default() -> Self {
+        Self {
+            radius: 36.0,
+            spacing: 5.0,
+            bomb_color: Color::rgba(1.0, 0.8, 0.4, 0.8),
+            engine_color: Color::rgba(0.5, 0.7, 1.0, 0.8),
+            weapon_color: Color::rgba(1.0, 1.0, 1.0, 0.4),
+        }
+    }
+}
+
+// Resource controlling the health bars floating above damaged hostiles
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct HealthBarSettings {
+    pub width: f32,          // Length of a full bar
+    pub height: f32,         // Thickness of the bar
+    pub gap: f32,            // Space between the top of the hostile and the bar
+    pub hide_at_full: bool,  // Whether untouched hostiles go without a bar
+    pub fill_color: Color,   // Color of the health left
+    pub back_color: Color,   // Color of the health lost
+}
+
+impl Default for HealthBarSettings {
+    fn default() -> Self {
+        Self {
+            width: 32.0,
+            height: 4.0,
+            gap: 6.0,
+            hide_at_full: true,
+            fill_color: Color::rgba(0.3, 1.0, 0.4, 0.9),
+            back_color: Color::rgba(0.3, 0.1, 0.1, 0.7),
+        }
+    }
+}
+
+// Resource holding the low fuel warning for the first player's ship; while it is on the fuel bar
+// blinks, lit for the first half of every interval, and a beep marks the start of each
+#[derive(Reflect, Resource, Default)]
+#[reflect(Resource)]
+pub struct LowFuelWarning {
+    pub active: bool,  // Whether the tank is below the threshold
+    pub timer: Timer,  // Time into the current blink
+}
+
+// Event sent for every beep of the low fuel warning
+#[derive(Event)]
+pub struct LowFuelBeep;
+
+// Marker for the HUD text shown while thrust is latched
+#[derive(Component)]
+struct ThrustLatchText;
+
+// Marker for the text shown while the game is paused
+#[derive(Component)]
+struct PausedText;
+
+// Marker for the words on the pause banner
+#[derive(Component)]
+struct PausedLabel;
+
+// Marker for the HUD text counting the items carried
+#[derive(Component)]
+struct InventoryText;
+
+// Marker for the HUD text showing score and bombs
+#[derive(Component)]
+struct StatusText;
+
+// Define the ComboText component for the HUD text showing the combo
+#[derive(Component)]
+struct ComboText {
+    shown: u32,    // Kills in the combo as last shown, to tell when another one lands
+    pulse: Timer,  // Time left of the pulse after a kill
+}
+
+// Marker for the HUD node showing the ammo pool's energy
+#[derive(Component)]
+struct AmmoBar;
+
+// Marker for the HUD node showing the weapon's heat
+#[derive(Component)]
+struct HeatGauge;
+
+// Marker for the HUD node showing the fuel left in the tank
+#[derive(Component)]
+struct FuelBar;
+
+// Marker for the HUD node showing the shield's strength
+#[derive(Component)]
+struct ShieldBar;
+
+// Setup system that builds the heads-up display
+fn setup_hud(mut commands: Commands) {
+    // Spawn the frame of the ammo bar in the top-left corner
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(10.0),
+                top: Val::Px(10.0),
+                width: Val::Px(200.0),
+                height: Val::Px(12.0),
+                ..default()
+            },
+            background_color: Color::rgb(0.2, 0.2, 0.2).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            // Spawn the fill that shrinks as energy is spent
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                AmmoBar,
+            ));
+        });
+
+    // Spawn the frame of the heat gauge below the ammo bar
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(10.0),
+                top: Val::Px(26.0),
+                width: Val::Px(200.0),
+                height: Val::Px(6.0),
+                ..default()
+            },
+            background_color: Color::rgb(0.2, 0.2, 0.2).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            // Spawn the fill that grows as the weapon heats up
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                HeatGauge,
+            ));
+        });
+
+    // Spawn the frame of the fuel bar below the heat gauge
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(10.0),
+                top: Val::Px(36.0),
+                width: Val::Px(200.0),
+                height: Val::Px(8.0),
+                ..default()
+            },
+            background_color: Color::rgb(0.2, 0.2, 0.2).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            // Spawn the fill that shrinks as fuel is burned
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                FuelBar,
+            ));
+        });
+
+    // Spawn the frame of the shield bar below the fuel bar
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(10.0),
+                top: Val::Px(48.0),
+                width: Val::Px(200.0),
+                height: Val::Px(6.0),
+                ..default()
+            },
+            background_color: Color::rgb(0.2, 0.2, 0.2).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            // Spawn the fill that shrinks as the shield soaks up hits
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                ShieldBar,
+            ));
+        });
+
+    // Spawn the score and bomb counter below the gauges
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            top: Val::Px(60.0),
+            ..default()
+        }),
+        StatusText,
+    ));
+
+    // Spawn the latched thrust indicator, shown only while the engine is latched on
+    commands.spawn((
+        TextBundle::from_section(
+            "THRUST LATCHED",
+            TextStyle {
+                font_size: 16.0,
+                color: Color::rgb(1.0, 0.6, 0.2),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            top: Val::Px(84.0),
+            ..default()
+        }),
+        ThrustLatchText,
+    ));
+
+    // Spawn the item counters below the thrust indicator
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 16.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            top: Val::Px(106.0),
+            ..default()
+        }),
+        InventoryText,
+    ));
+
+    // Spawn the combo counter below the item counters, hidden until a combo builds up
+    let mut pulse = Timer::from_seconds(COMBO_PULSE_TIME, TimerMode::Once);
+    pulse.tick(pulse.duration());
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: COMBO_FONT_SIZE,
+                color: Color::GOLD,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            top: Val::Px(128.0),
+            ..default()
+        }),
+        Visibility::Hidden,
+        ComboText { shown: 0, pulse },
+    ));
+
+    // Spawn the pause banner in the middle of the screen, hidden while playing
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        })
+        .insert(PausedText)
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "PAUSED",
+                    TextStyle {
+                        font_size: 48.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                PausedLabel,
+            ));
+        });
+}
+
+// Share of a cooldown already recharged, or None once it is ready and there's nothing to show
+pub fn recharged_fraction(cooldown: &Timer) -> Option<f32> {
+    (!cooldown.finished()).then(|| cooldown.percent())
+}
+
+// System to draw a ring around every ship for each of its abilities still recharging, filling
+// clockwise from the nose as the cooldown runs down; every ability keeps its own ring so they
+// don't swap places as one finishes
+fn cooldown_ring_system(
+    mut gizmos: Gizmos,                                       // For drawing the rings
+    settings: Res<CooldownRingSettings>,                      // Ring size and colors
+    bomb_settings: Res<BombSettings>,                         // The bomb's cooldown
+    ship_query: Query<(&Transform, &Weapon, Option<&Disabled>), With<Starship>>, // Get the ships' cooldowns
+) {
+    for (transform, weapon, disabled) in ship_query.iter() {
+        let position = transform.translation.truncate();
+        let rings = [
+            (recharged_fraction(&bomb_settings.cooldown), settings.bomb_color),
+            (disabled.and_then(|disabled| recharged_fraction(&disabled.timer)), settings.engine_color),
+            (recharged_fraction(&weapon.cooldown), settings.weapon_color),
+        ];
+        for (index, (fraction, color)) in rings.into_iter().enumerate() {
+            let Some(fraction) = fraction else {
+                continue;
+            };
+            let radius = settings.radius + settings.spacing * index as f32;
+            // The arc is placed by its middle, so half its length clockwise from straight up
+            let arc = TAU * fraction;
+            gizmos.arc_2d(position, arc / 2.0, arc, radius, color).segments(48);
+        }
+    }
+}
+
+// Where in a camera's view the bar above a hostile at the given world position sits, or None when
+// the bar falls outside the view or behind the camera
+pub fn health_bar_position(
+    camera: &Camera,                                          // The view the bar would show in
+    camera_transform: &GlobalTransform,                       // Where the view is
+    world: Vec3,                                              // Where the bar's middle is in the world
+) -> Option<Vec2> {
+    let viewport = camera.logical_viewport_size()?;
+    let screen = camera.world_to_viewport(camera_transform, world)?;
+    (screen.cmpge(Vec2::ZERO).all() && screen.cmple(viewport).all()).then_some(screen)
+}
+
+// System to draw a bar above every damaged hostile in view, filled by the share of health it has
+// left
+fn health_bar_system(
+    mut gizmos: Gizmos,                                       // For drawing the bars
+    settings: Res<HealthBarSettings>,                         // Bar size and colors
+    camera_query: Query<(&Camera, &GlobalTransform)>,         // For leaving out bars nobody sees
+    hostile_query: Query<(&Transform, &Health, &Collider), With<Hostile>>, // Get every hostile's health
+) {
+    for (transform, health, collider) in hostile_query.iter() {
+        let fraction = (health.current / health.max.max(f32::EPSILON)).clamp(0.0, 1.0);
+        if settings.hide_at_full && fraction >= 1.0 {
+            continue;
+        }
+        let center = transform.translation.truncate() + Vec2::Y * (collider.radius + settings.gap);
+        let in_view = camera_query.iter().any(|(camera, camera_transform)| {
+            health_bar_position(camera, camera_transform, center.extend(0.0)).is_some()
+        });
+        if !in_view {
+            continue;
+        }
+
+        // Gizmo lines are a pixel thick, so stack them to give the bar its height
+        let left = center.x - settings.width / 2.0;
+        let filled = left + settings.width * fraction;
+        let rows = settings.height.max(1.0) as u32;
+        for row in 0..rows {
+            let y = center.y - settings.height / 2.0 + row as f32;
+            gizmos.line_2d(Vec2::new(filled, y), Vec2::new(left + settings.width, y), settings.back_color);
+            gizmos.line_2d(Vec2::new(left, y), Vec2::new(filled, y), settings.fill_color);
+        }
+    }
+}
+
+// System to point an arrow from the edge of every player's view at their nearest off-screen threat
+fn threat_indicator_system(
+    mut gizmos: Gizmos,                                       // For drawing the arrow
+    camera_query: Query<(&Camera, &GlobalTransform, &PlayerCamera)>, // For converting between spaces
+    ship_query: Query<(&Transform, &Player), With<Starship>>, // Measure proximity from each ship
+    hostile_query: Query<&Transform, With<Hostile>>,          // Get every threat
+    settings: Res<ThreatIndicatorSettings>,                   // Arrow look and fade
+) {
+    for (camera, camera_transform, player_camera) in camera_query.iter() {
+        let origin = ship_query
+            .iter()
+            .find(|(_, player)| player.id == player_camera.id)
+            .map_or(camera_transform.translation(), |(transform, _)| transform.translation)
+            .truncate();
+        draw_threat_indicator(&mut gizmos, camera, camera_transform, origin, &hostile_query, &settings);
+    }
+}
+
+// Draw the arrow at the edge of one camera's view
+fn draw_threat_indicator(
+    gizmos: &mut Gizmos,                                      // For drawing the arrow
+    camera: &Camera,                                          // The view the arrow sits in
+    camera_transform: &GlobalTransform,                       // Where the view is
+    origin: Vec2,                                             // Where proximity is measured from
+    hostile_query: &Query<&Transform, With<Hostile>>,         // Get every threat
+    settings: &ThreatIndicatorSettings,                       // Arrow look and fade
+) {
+    let Some(viewport) = camera.logical_viewport_size() else {
+        return;
+    };
+
+    // Find the closest threat whose position falls outside the view
+    let nearest = hostile_query
+        .iter()
+        .filter_map(|transform| {
+            let world = transform.translation;
+            let screen = camera.world_to_viewport(camera_transform, world)?;
+            let on_screen = screen.cmpge(Vec2::ZERO).all() && screen.cmple(viewport).all();
+            (!on_screen).then(|| (screen, origin.distance(world.truncate())))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+    let Some((screen, distance)) = nearest else {
+        return;
+    };
+
+    // Push the arrow out from the middle of the view until it sits on the inset border
+    let center = viewport / 2.0;
+    let inset = (center - Vec2::splat(settings.margin)).max(Vec2::ONE);
+    let offset = screen - center;
+    let direction = offset.normalize_or_zero();
+    if direction == Vec2::ZERO {
+        return;
+    }
+    let scale = (inset.x / offset.x.abs()).min(inset.y / offset.y.abs());
+    let tip = center + offset * scale;
+    let base = tip - direction * settings.size;
+    let side = direction.perp() * settings.size * 0.5;
+
+    // Draw in world space, converting back from the view's pixels
+    let to_world = |point: Vec2| camera.viewport_to_world_2d(camera_transform, point);
+    let (Some(tip), Some(left), Some(right)) = (to_world(tip), to_world(base + side), to_world(base - side)) else {
+        return;
+    };
+    let opacity = (1.0 - distance / settings.fade_distance).clamp(0.15, 1.0);
+    let color = settings.color.with_a(opacity);
+    gizmos.line_2d(left, tip, color);
+    gizmos.line_2d(right, tip, color);
+    gizmos.line_2d(left, right, color);
+}
+
+// System to keep the score and bomb counter up to date
+fn status_text_system(
+    score: Res<Score>,                                        // Points so far
+    lives: Res<Lives>,                                        // Lives left
+    player_count: Res<PlayerCount>,                           // Whose counters to show
+    bombs: Res<Bombs>,                                        // Bombs left
+    bomb_settings: Res<BombSettings>,                         // Bomb cooldown
+    survival: Res<SurvivalTimer>,                             // Time survived this run
+    mut query: Query<&mut Text, With<StatusText>>,            // Get the text to update
+) {
+    for mut text in query.iter_mut() {
+        let cooldown = if bomb_settings.cooldown.finished() {
+            String::new()
+        } else {
+            format!(" ({:.0}s)", bomb_settings.cooldown.remaining_secs().ceil())
+        };
+        // Label each player's counters once there is more than one
+        let players = if player_count.ids().len() == 1 {
+            format!("Score: {}   Lives: {}", score.0[0], lives.0[0])
+        } else {
+            player_count
+                .ids()
+                .map(|id| format!("P{} Score: {}   Lives: {}", id + 1, score.0[id], lives.0[id]))
+                .collect::<Vec<_>>()
+                .join("   ")
+        };
+        let seconds = survival.elapsed as u32;
+        text.sections[0].value = format!(
+            "{}   Bombs: {}{}   Time: {}:{:02}",
+            players,
+            bombs.0,
+            cooldown,
+            seconds / 60,
+            seconds % 60
+        );
+    }
+}
+
+// System to count the items carried, naming the first player's key for each
+fn inventory_text_system(
+    inventory: Res<Inventory>,                                // Items carried
+    key_bindings: Res<KeyBindings>,                           // Keys to name next to the counters
+    mut query: Query<&mut Text, With<InventoryText>>,         // Get the text to update
+) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = Item::ALL
+            .into_iter()
+            .map(|item| {
+                let key = key_bindings
+                    .first()
+                    .and_then(|input_map| input_map.keys(Action::UseItem(item)).first())
+                    .map_or_else(String::new, |key| format!("[{:?}] ", key));
+                format!("{}{}: {}", key, item.label(), inventory.count(item))
+            })
+            .collect::<Vec<_>>()
+            .join("   ");
+    }
+}
+
+// System to show the combo and its multiplier, pulsing the text each time a kill adds to it
+fn combo_text_system(
+    time: Res<Time>,                                          // For the pulse
+    combo: Res<ComboMeter>,                                   // The combo under way
+    config: Res<GameConfig>,                                  // What the combo multiplies by
+    mut query: Query<(&mut Text, &mut Visibility, &mut ComboText)>, // Get the text to update
+) {
+    for (mut text, mut visibility, mut combo_text) in query.iter_mut() {
+        if combo.count > combo_text.shown {
+            combo_text.pulse.reset();
+        }
+        combo_text.shown = combo.count;
+        combo_text.pulse.tick(time.delta());
+        *visibility = if combo.count >= COMBO_SHOWN_FROM {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        let section = &mut text.sections[0];
+        let value = format!("{} KILL COMBO  x{}", combo.count, config.combo.multiplier(combo.count));
+        if section.value != value {
+            section.value = value;
+        }
+        // Grows on a kill and shrinks back over the pulse
+        let font_size = COMBO_FONT_SIZE * (1.0 + COMBO_PULSE_GROWTH * combo_text.pulse.percent_left());
+        if section.style.font_size != font_size {
+            section.style.font_size = font_size;
+        }
+    }
+}
+
+// System to show the latched thrust indicator only while thrust is latched
+fn thrust_latch_text_system(
+    thrust_latch: Res<ThrustLatch>,                           // Latched thrust state
+    mut query: Query<&mut Visibility, With<ThrustLatchText>>, // Get the indicator
+) {
+    for mut visibility in query.iter_mut() {
+        *visibility = if thrust_latch.engaged.iter().any(|&engaged| engaged) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+// System to show the pause banner while the game is paused
+fn paused_text_system(
+    state: Res<State<GameState>>,                             // The current state
+    mut query: Query<&mut Visibility, With<PausedText>>,      // Get the banner
+) {
+    for mut visibility in query.iter_mut() {
+        *visibility = if *state.get() == GameState::Paused {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+// System to show the countdown to resuming on the pause banner
+fn paused_label_system(
+    auto_pause: Res<AutoPause>,                               // Countdown to resuming, if any
+    mut query: Query<&mut Text, With<PausedLabel>>,           // Get the banner's words
+) {
+    let label = match &auto_pause.countdown {
+        Some(countdown) => format!("RESUMING IN {:.0}", countdown.remaining_secs().ceil()),
+        None => "PAUSED".to_string(),
+    };
+    for mut text in query.iter_mut() {
+        if text.sections[0].value != label {
+            text.sections[0].value = label.clone();
+        }
+    }
+}
+
+// System to keep the ammo bar in sync with the first player's energy
+fn ammo_bar_system(
+    color_scheme: Res<ColorScheme>,                           // Color of the bar
+    ammo_query: Query<(&AmmoPool, &Player), With<Starship>>,  // Get the ships' energy
+    mut bar_query: Query<(&mut Style, &mut BackgroundColor), With<AmmoBar>>, // Get the bar to resize
+) {
+    let Some((ammo, _)) = ammo_query.iter().find(|(_, player)| player.id == 0) else {
+        return;
+    };
+    for (mut style, mut background) in bar_query.iter_mut() {
+        style.width = Val::Percent(100.0 * ammo.energy / ammo.max);
+        *background = color_scheme.ammo_bar.into();
+    }
+}
+
+// System to keep the heat gauge in sync with the first player's weapon heat
+fn heat_gauge_system(
+    color_scheme: Res<ColorScheme>,                           // Colors of the gauge
+    heat_query: Query<(&Heat, &Player), With<Starship>>,      // Get the ships' heat
+    mut gauge_query: Query<(&mut Style, &mut BackgroundColor), With<HeatGauge>>, // Get the gauge to resize
+) {
+    let Some((heat, _)) = heat_query.iter().find(|(_, player)| player.id == 0) else {
+        return;
+    };
+    // Turn red when close to overheating, and stay red while locked
+    let color = if heat.overheated || heat.fraction() >= HEAT_WARNING_FRACTION {
+        color_scheme.heat_warning
+    } else {
+        color_scheme.heat_bar
+    };
+    for (mut style, mut background) in gauge_query.iter_mut() {
+        style.width = Val::Percent(100.0 * heat.fraction());
+        *background = color.into();
+    }
+}
+
+// System to keep the fuel bar in sync with the first player's tank
+fn fuel_bar_system(
+    color_scheme: Res<ColorScheme>,                           // Color of the bar
+    engine_query: Query<(&Engine, &Player), With<Starship>>,  // Get the ships' fuel
+    mut bar_query: Query<(&mut Style, &mut BackgroundColor), With<FuelBar>>, // Get the bar to resize
+) {
+    let Some((engine, _)) = engine_query.iter().find(|(_, player)| player.id == 0) else {
+        return;
+    };
+    for (mut style, mut background) in bar_query.iter_mut() {
+        style.width = Val::Percent(100.0 * engine.fuel / engine.max_fuel.max(f32::EPSILON));
+        *background = color_scheme.fuel_bar.into();
+    }
+}
+
+// System to keep the shield bar in sync with the first player's shield; it pulses while the
+// shield recharges and is dimmed while it waits out the delay after a hit
+fn shield_bar_system(
+    time: Res<Time>,                                          // For the pulse
+    config: Res<GameConfig>,                                  // Recharge delay
+    mode: Res<State<GameMode>>,                               // Kind of run, which decides whether shields recharge
+    color_scheme: Res<ColorScheme>,                           // Color of the bar
+    shield_query: Query<(&Shield, &Player), With<Starship>>,  // Get the ships' shields
+    mut bar_query: Query<(&mut Style, &mut BackgroundColor), With<ShieldBar>>, // Get the bar to resize
+) {
+    let Some((shield, _)) = shield_query.iter().find(|(_, player)| player.id == 0) else {
+        return;
+    };
+    let opacity = if shield.recharging(&config.shield_regen, *mode.get()) {
+        0.65 + 0.35 * (time.elapsed_seconds() * SHIELD_PULSE_SPEED).sin()
+    } else if shield.current < shield.max {
+        SHIELD_WAITING_OPACITY
+    } else {
+        1.0
+    };
+    for (mut style, mut background) in bar_query.iter_mut() {
+        style.width = Val::Percent(100.0 * shield.current / shield.max.max(f32::EPSILON));
+        *background = color_scheme.shield_bar.with_a(opacity).into();
+    }
+}
+
+// System to switch the low fuel warning on below the threshold and off again the moment the
+// tank is back above it, blinking the fuel bar and beeping while it is on
+fn low_fuel_warning_system(
+    time: Res<Time>,                                          // For the blink, held while paused
+    config: Res<GameConfig>,                                  // Threshold and beep interval
+    engine_query: Query<(&Engine, &Player), With<Starship>>,  // Get the ships' fuel
+    mut warning: ResMut<LowFuelWarning>,                      // The warning to update
+    mut bar_query: Query<&mut Visibility, With<FuelBar>>,     // Get the bar to blink
+    mut beep_events: EventWriter<LowFuelBeep>,                // For sounding the beep
+) {
+    let low_fuel = &config.low_fuel;
+    // Without a ship there is no tank to warn about
+    let low = engine_query
+        .iter()
+        .find(|(_, player)| player.id == 0)
+        .is_some_and(|(engine, _)| engine.fuel < engine.max_fuel * low_fuel.threshold);
+    if low && !warning.active {
+        warning.active = true;
+        warning.timer = Timer::from_seconds(low_fuel.beep_interval.max(0.1), TimerMode::Repeating);
+        beep_events.send(LowFuelBeep);
+    } else if low && warning.timer.tick(time.delta()).just_finished() {
+        beep_events.send(LowFuelBeep);
+    } else if !low {
+        warning.active = false;
+    }
+    let visibility = if warning.active && warning.timer.percent() >= 0.5 {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    for mut bar_visibility in bar_query.iter_mut() {
+        if *bar_visibility != visibility {
+            *bar_visibility = visibility;
+        }
+    }
+}
+
+// System to sound a short beep for every beep of the low fuel warning
+fn low_fuel_beep_system(
+    mut commands: Commands,                                   // For playing the beep
+    mut beep_events: EventReader<LowFuelBeep>,                // Beeps asked for
+    mut pitches: ResMut<Assets<Pitch>>,                       // For making the tone
+) {
+    if beep_events.read().count() > 0 {
+        commands.spawn(PitchBundle {
+            source: pitches.add(Pitch::new(LOW_FUEL_BEEP_PITCH, Duration::from_secs_f32(LOW_FUEL_BEEP_LENGTH))),
+            settings: PlaybackSettings::DESPAWN,
+        });
+    }
+}
+
+// Plugin for the heads-up display
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ThreatIndicatorSettings>()
+            .register_type::<CooldownRingSettings>()
+            .init_resource::<ThreatIndicatorSettings>()
+            .init_resource::<CooldownRingSettings>()
+            .register_type::<HealthBarSettings>()
+            .init_resource::<HealthBarSettings>()
+            .register_type::<LowFuelWarning>()
+            .init_resource::<LowFuelWarning>()
+            .add_event::<LowFuelBeep>()
+            .add_systems(Startup, setup_hud)
+            // Every widget is only updated while it exists, and the bars only while there's a ship
+            .add_systems(Update, (
+                ammo_bar_system.run_if(any_with_component::<AmmoBar>()).run_if(player_exists),
+                heat_gauge_system.run_if(any_with_component::<HeatGauge>()).run_if(player_exists),
+                fuel_bar_system.run_if(any_with_component::<FuelBar>()).run_if(player_exists),
+                shield_bar_system.run_if(any_with_component::<ShieldBar>()).run_if(player_exists),
+                (low_fuel_warning_system, low_fuel_beep_system).chain(),
+                status_text_system.run_if(any_with_component::<StatusText>()),
+                thrust_latch_text_system.run_if(any_with_component::<ThrustLatchText>()),
+                inventory_text_system.run_if(any_with_component::<InventoryText>()),
+                combo_text_system.run_if(any_with_component::<ComboText>()),
+                paused_text_system.run_if(any_with_component::<PausedText>()),
+                paused_label_system.run_if(any_with_component::<PausedLabel>()),
+                threat_indicator_system,
+                // Drawn around the interpolated ships the player sees
+                cooldown_ring_system.after(InterpolationSet).run_if(in_state(GameState::Playing)),
+                health_bar_system.after(InterpolationSet).run_if(in_state(GameState::Playing)),
+            ));
+    }
+}
@@ -0,0 +1,838 @@
+// Turning keyboard, gamepad and touch input into player intent
+use bevy::prelude::*;
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent};
+use bevy::input::InputSystem;
+use bevy::window::PrimaryWindow;
+use serde::{Deserialize, Serialize};
+
+use crate::camera::cursor_world_position;
+use crate::inventory::Item;
+use crate::player::{Engine, MAX_PLAYERS, Player, PlayerCount, Starship};
+use crate::session::GameState;
+use crate::settings::Settings;
+
+// Resource holding options that make the game easier to play
+#[derive(Reflect, Resource, Default)]
+#[reflect(Resource)]
+struct AccessibilitySettings {
+    toggle_thrust: bool,  // Tap thrust to latch the engine on, tap again to cut it
+    strafing: bool,       // Whether the strafe keys fire the side thrusters
+}
+
+// Resource remembering whether toggle-thrust currently holds each player's engine on
+#[derive(Resource, Default)]
+pub struct ThrustLatch {
+    pub engaged: [bool; MAX_PLAYERS],  // Whether each player's engine is latched on
+}
+
+// One player's intent for this frame, written by the input systems
+// and read by the gameplay systems so keyboard, gamepad, replays or an AI share one path
+#[derive(Reflect, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct PlayerInput {
+    pub rotate: f32,         // -1.0 (full right) to 1.0 (full left)
+    pub thrust: f32,         // -1.0 (full reverse) to 1.0 (full thrust)
+    pub strafe: f32,         // -1.0 (full left) to 1.0 (full right), square to the facing
+    pub fire: bool,          // Whether the trigger is held
+    pub dock: bool,          // Whether the docking key is held
+    pub bomb: bool,          // Whether a smart bomb was triggered since the last fixed tick
+    pub item: Option<Item>,  // Item asked to be used since the last fixed tick, if any
+    pub aim: Option<f32>,    // World angle the turret should point, None keeps its current aim
+}
+
+impl PlayerInput {
+    // Whether anything is steered or held this frame; the one-off bomb and item presses still
+    // waiting for a fixed tick, and the aim, don't count
+    pub fn is_held(&self) -> bool {
+        self.rotate != 0.0 || self.thrust != 0.0 || self.strafe != 0.0 || self.fire || self.dock
+    }
+}
+
+// Resource holding this frame's intent of every player, indexed by player id
+#[derive(Reflect, Resource, Default, Deref, DerefMut)]
+#[reflect(Resource)]
+pub struct PlayerInputs(pub [PlayerInput; MAX_PLAYERS]);
+
+// Event asking for the game to be paused, or resumed if it already is
+#[derive(Event, Default)]
+pub struct PauseToggleEvent;
+
+// Event asking for slow motion to be switched on, or off if it already is
+#[derive(Event, Default)]
+pub struct SlowMotionToggleEvent;
+
+// Logical actions the player can trigger, independent of the physical keys
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    RotateLeft,
+    RotateRight,
+    Thrust,
+    Reverse,
+    StrafeLeft,
+    StrafeRight,
+    Fire,
+    Bomb,
+    Dock,
+    UseItem(Item),
+    Pause,
+    SlowMotion,
+}
+
+// Key bindings mapping each action to the keys that trigger it
+#[derive(Reflect, Serialize, Deserialize, Clone, Debug)]
+pub struct InputMap {
+    pub rotate_left: Vec<KeyCode>,   // Keys turning the ship counter-clockwise
+    pub rotate_right: Vec<KeyCode>,  // Keys turning the ship clockwise
+    pub thrust: Vec<KeyCode>,        // Keys firing the engine
+    pub reverse: Vec<KeyCode>,       // Keys firing the engine backwards
+    pub strafe_left: Vec<KeyCode>,   // Keys pushing the ship to its left
+    pub strafe_right: Vec<KeyCode>,  // Keys pushing the ship to its right
+    pub fire: Vec<KeyCode>,          // Keys firing the weapon
+    pub bomb: Vec<KeyCode>,          // Keys setting off a smart bomb
+    pub dock: Vec<KeyCode>,          // Keys docking with a station while held
+    pub repair_kit: Vec<KeyCode>,    // Keys using a repair kit
+    pub fuel_cell: Vec<KeyCode>,     // Keys using a fuel cell
+    pub shield_cell: Vec<KeyCode>,   // Keys using a shield cell
+    pub pause: Vec<KeyCode>,         // Keys pausing and resuming the game
+    pub slow_motion: Vec<KeyCode>,   // Keys switching slow motion on and off
+}
+
+impl InputMap {
+    // Arrow keys to fly, Space to shoot
+    pub fn arrows() -> Self {
+        Self {
+            rotate_left: vec![KeyCode::Left],
+            rotate_right: vec![KeyCode::Right],
+            thrust: vec![KeyCode::Up],
+            reverse: vec![KeyCode::Down],
+            strafe_left: vec![KeyCode::Q],
+            strafe_right: vec![KeyCode::E],
+            fire: vec![KeyCode::Space],
+            bomb: vec![KeyCode::B],
+            dock: vec![KeyCode::X],
+            repair_kit: vec![KeyCode::Key1],
+            fuel_cell: vec![KeyCode::Key2],
+            shield_cell: vec![KeyCode::Key3],
+            pause: vec![KeyCode::Escape, KeyCode::P],
+            slow_motion: vec![KeyCode::T],
+        }
+    }
+
+    // W/A/S/D to fly, Space to shoot
+    pub fn wasd() -> Self {
+        Self {
+            rotate_left: vec![KeyCode::A],
+            rotate_right: vec![KeyCode::D],
+            thrust: vec![KeyCode::W],
+            reverse: vec![KeyCode::S],
+            strafe_left: vec![KeyCode::Q],
+            strafe_right: vec![KeyCode::E],
+            fire: vec![KeyCode::Space],
+            bomb: vec![KeyCode::B],
+            dock: vec![KeyCode::X],
+            repair_kit: vec![KeyCode::Key1],
+            fuel_cell: vec![KeyCode::Key2],
+            shield_cell: vec![KeyCode::Key3],
+            pause: vec![KeyCode::Escape, KeyCode::P],
+            slow_motion: vec![KeyCode::T],
+        }
+    }
+
+    // Arrow keys and W/A/S/D both fly, Space shoots
+    pub fn arrows_and_wasd() -> Self {
+        let arrows = Self::arrows();
+        let wasd = Self::wasd();
+        Self {
+            rotate_left: [arrows.rotate_left, wasd.rotate_left].concat(),
+            rotate_right: [arrows.rotate_right, wasd.rotate_right].concat(),
+            thrust: [arrows.thrust, wasd.thrust].concat(),
+            reverse: [arrows.reverse, wasd.reverse].concat(),
+            strafe_left: vec![KeyCode::Q],
+            strafe_right: vec![KeyCode::E],
+            fire: vec![KeyCode::Space],
+            bomb: vec![KeyCode::B],
+            dock: vec![KeyCode::X],
+            repair_kit: vec![KeyCode::Key1],
+            fuel_cell: vec![KeyCode::Key2],
+            shield_cell: vec![KeyCode::Key3],
+            pause: vec![KeyCode::Escape, KeyCode::P],
+            slow_motion: vec![KeyCode::T],
+        }
+    }
+
+    // Left half of a shared keyboard: W/A/S/D to fly, F to shoot, G to bomb, 1/2/3 for items
+    pub fn split_left() -> Self {
+        Self {
+            fire: vec![KeyCode::F],
+            bomb: vec![KeyCode::G],
+            ..Self::wasd()
+        }
+    }
+
+    // Right half of a shared keyboard: arrow keys to fly, numpad 4/6 to strafe, right Ctrl to shoot,
+    // right Shift to bomb, numpad 0 to dock, numpad 1/2/3 for items
+    pub fn split_right() -> Self {
+        Self {
+            strafe_left: vec![KeyCode::Numpad4],
+            strafe_right: vec![KeyCode::Numpad6],
+            fire: vec![KeyCode::ControlRight],
+            bomb: vec![KeyCode::ShiftRight],
+            dock: vec![KeyCode::Numpad0],
+            repair_kit: vec![KeyCode::Numpad1],
+            fuel_cell: vec![KeyCode::Numpad2],
+            shield_cell: vec![KeyCode::Numpad3],
+            ..Self::arrows()
+        }
+    }
+
+    // Keys bound to an action
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        match action {
+            Action::RotateLeft => &self.rotate_left,
+            Action::RotateRight => &self.rotate_right,
+            Action::Thrust => &self.thrust,
+            Action::Reverse => &self.reverse,
+            Action::StrafeLeft => &self.strafe_left,
+            Action::StrafeRight => &self.strafe_right,
+            Action::Fire => &self.fire,
+            Action::Bomb => &self.bomb,
+            Action::Dock => &self.dock,
+            Action::UseItem(Item::RepairKit) => &self.repair_kit,
+            Action::UseItem(Item::FuelCell) => &self.fuel_cell,
+            Action::UseItem(Item::ShieldCell) => &self.shield_cell,
+            Action::Pause => &self.pause,
+            Action::SlowMotion => &self.slow_motion,
+        }
+    }
+
+    // Whether any key bound to the action is held
+    pub fn pressed(&self, action: Action, keyboard_input: &Input<KeyCode>) -> bool {
+        keyboard_input.any_pressed(self.keys(action).iter().copied())
+    }
+
+    // Whether any key bound to the action went down this frame
+    pub fn just_pressed(&self, action: Action, keyboard_input: &Input<KeyCode>) -> bool {
+        keyboard_input.any_just_pressed(self.keys(action).iter().copied())
+    }
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self::arrows_and_wasd()
+    }
+}
+
+// Resource holding the key bindings of every player taking part, indexed by player id
+#[derive(Reflect, Resource, Clone, Debug, Deref, DerefMut)]
+#[reflect(Resource)]
+pub struct KeyBindings(pub Vec<InputMap>);
+
+impl KeyBindings {
+    // Bindings for the given preset, or one half of the keyboard each when sharing it
+    fn new(control_scheme: ControlScheme, player_count: &PlayerCount) -> Self {
+        if player_count.ids().len() > 1 {
+            Self(vec![InputMap::split_right(), InputMap::split_left()])
+        } else {
+            Self(vec![control_scheme.input_map()])
+        }
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self::new(ControlScheme::default(), &PlayerCount::default())
+    }
+}
+
+// Resource selecting which preset a lone player's bindings are rebuilt from, written to
+// files by name so a settings file can be read field by field
+#[derive(Reflect, Resource, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[reflect(Resource)]
+#[serde(into = "String", try_from = "String")]
+pub enum ControlScheme {
+    #[default]
+    ArrowsAndWasd,  // Either set of keys, and Space
+    Arrows,         // Arrow keys and Space
+    Wasd,           // W/A/S/D and Space
+}
+
+impl ControlScheme {
+    // Every scheme, for looking one up by name
+    const ALL: [ControlScheme; 3] = [ControlScheme::ArrowsAndWasd, ControlScheme::Arrows, ControlScheme::Wasd];
+
+    // Build the key bindings for this scheme
+    fn input_map(self) -> InputMap {
+        match self {
+            ControlScheme::ArrowsAndWasd => InputMap::arrows_and_wasd(),
+            ControlScheme::Arrows => InputMap::arrows(),
+            ControlScheme::Wasd => InputMap::wasd(),
+        }
+    }
+}
+
+impl From<ControlScheme> for String {
+    fn from(control_scheme: ControlScheme) -> Self {
+        format!("{:?}", control_scheme)
+    }
+}
+
+impl TryFrom<String> for ControlScheme {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        ControlScheme::ALL
+            .into_iter()
+            .find(|control_scheme| String::from(*control_scheme) == name)
+            .ok_or_else(|| format!("unknown control scheme {:?}", name))
+    }
+}
+
+// Resource shaping raw stick values before they turn the ship
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct StickSettings {
+    pub inner_deadzone: f32,     // Deflection at or below this reads as zero, hiding drift
+    pub outer_saturation: f32,   // Deflection at or above this reads as full
+    pub response_exponent: f32,  // 1.0 is linear, higher values give finer control near center
+}
+
+impl Default for StickSettings {
+    fn default() -> Self {
+        Self {
+            inner_deadzone: 0.15,
+            outer_saturation: 0.95,
+            response_exponent: 1.5,
+        }
+    }
+}
+
+// Map a raw stick value in -1..1 through the deadzone, saturation and response curve
+pub fn apply_stick_response(value: f32, settings: &StickSettings) -> f32 {
+    let magnitude = value.abs();
+    if magnitude <= settings.inner_deadzone {
+        return 0.0;
+    }
+    // Rescale so the output starts at zero right after the deadzone
+    let range = (settings.outer_saturation - settings.inner_deadzone).max(f32::EPSILON);
+    let normalized = ((magnitude - settings.inner_deadzone) / range).min(1.0);
+    normalized.powf(settings.response_exponent).copysign(value)
+}
+
+// Resource configuring the on-screen touch controls
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+struct TouchSettings {
+    always_show: bool,       // Show the controls even before the screen is touched
+    joystick_radius: f32,    // Radius of the virtual joystick, in logical pixels
+    fire_button_size: f32,   // Width and height of the fire button, in logical pixels
+    margin: f32,             // Gap between the controls and the screen edges
+    steering_gain: f32,      // How sharply the ship turns towards the joystick direction
+}
+
+impl Default for TouchSettings {
+    fn default() -> Self {
+        Self {
+            always_show: false,
+            joystick_radius: 60.0,
+            fire_button_size: 80.0,
+            margin: 40.0,
+            steering_gain: 2.0,
+        }
+    }
+}
+
+impl TouchSettings {
+    // Center of the joystick in window coordinates (origin top-left)
+    fn joystick_center(&self, window_size: Vec2) -> Vec2 {
+        Vec2::new(
+            self.margin + self.joystick_radius,
+            window_size.y - self.margin - self.joystick_radius,
+        )
+    }
+
+    // Center of the fire button in window coordinates (origin top-left)
+    fn fire_button_center(&self, window_size: Vec2) -> Vec2 {
+        let half = self.fire_button_size / 2.0;
+        Vec2::new(window_size.x - self.margin - half, window_size.y - self.margin - half)
+    }
+}
+
+// Resource tracking what the fingers on the touch controls are doing
+#[derive(Resource, Default)]
+struct TouchState {
+    visible: bool,                   // Whether the controls have been revealed
+    joystick_touch: Option<u64>,     // The finger steering, if any
+    joystick: Vec2,                  // Joystick deflection, length up to 1, +Y up
+}
+
+// Marker for the root nodes of the touch controls
+#[derive(Component)]
+struct TouchControlsRoot;
+
+// Marker for the knob that follows the steering finger
+#[derive(Component)]
+struct JoystickKnob;
+
+// Resource remembering which gamepad controls the ship, if any
+#[derive(Resource, Default)]
+struct ActiveGamepad(Option<Gamepad>);
+
+// Components of a ship the keyboard input depends on
+type KeyboardShipData<'a> = (&'a Engine, &'a Player);
+
+// System to translate the keyboard into the input of every player
+fn keyboard_input_system(
+    keyboard_input: Res<Input<KeyCode>>,                      // For keyboard input
+    key_bindings: Res<KeyBindings>,                           // Which keys trigger which action
+    accessibility: Res<AccessibilitySettings>,                // Whether thrust is a toggle
+    mut thrust_latch: ResMut<ThrustLatch>,                    // Latched thrust state
+    engine_query: Query<KeyboardShipData, With<Starship>>,    // Get every ship's fuel
+    state: Res<State<GameState>>,                             // Taps while paused are ignored
+    mut player_inputs: ResMut<PlayerInputs>,                  // Where the intent is written
+) {
+    // Start every frame from no input so released keys stop the ship,
+    // but keep bomb and item presses until a fixed tick has seen them
+    for player_input in player_inputs.iter_mut() {
+        *player_input = PlayerInput {
+            bomb: player_input.bomb,
+            item: player_input.item,
+            ..default()
+        };
+    }
+
+    for (id, input_map) in key_bindings.iter().enumerate() {
+        let player_input = &mut player_inputs[id];
+        let latched = &mut thrust_latch.engaged[id];
+        let has_fuel = engine_query
+            .iter()
+            .any(|(engine, player)| player.id == id && engine.fuel > 0.0);
+        read_keyboard(&keyboard_input, input_map, &accessibility, *state.get(), has_fuel, latched, player_input);
+    }
+}
+
+// Turn the keys of one player's bindings into their input
+fn read_keyboard(
+    keyboard_input: &Input<KeyCode>,                          // For keyboard input
+    input_map: &InputMap,                                     // Which keys trigger which action
+    accessibility: &AccessibilitySettings,                    // Whether thrust is a toggle
+    state: GameState,                                         // Taps while paused are ignored
+    has_fuel: bool,                                           // Whether the player's ship can thrust
+    latched: &mut bool,                                       // Latched thrust state
+    player_input: &mut PlayerInput,                           // Where the intent is written
+) {
+    // Rotate left or right while a rotate key is held
+    if input_map.pressed(Action::RotateLeft, keyboard_input) {
+        player_input.rotate = 1.0;
+    } else if input_map.pressed(Action::RotateRight, keyboard_input) {
+        player_input.rotate = -1.0;
+    }
+
+    // With toggle-thrust, a tap latches the engine on or off instead of holding it
+    if accessibility.toggle_thrust {
+        if state == GameState::Playing && input_map.just_pressed(Action::Thrust, keyboard_input) {
+            *latched = !*latched;
+        }
+        // Cut the engine once the tank runs dry, or if the ship is gone
+        if !has_fuel {
+            *latched = false;
+        }
+    } else {
+        *latched = false;
+    }
+
+    // Thrust forwards or backwards while a thrust key is held; both cancel out
+    if *latched || (!accessibility.toggle_thrust && input_map.pressed(Action::Thrust, keyboard_input)) {
+        player_input.thrust += 1.0;
+    }
+    if input_map.pressed(Action::Reverse, keyboard_input) {
+        player_input.thrust -= 1.0;
+    }
+
+    // Strafe sideways while a strafe key is held, if strafing is switched on; both cancel out
+    if accessibility.strafing {
+        if input_map.pressed(Action::StrafeLeft, keyboard_input) {
+            player_input.strafe -= 1.0;
+        }
+        if input_map.pressed(Action::StrafeRight, keyboard_input) {
+            player_input.strafe += 1.0;
+        }
+    }
+
+    // Fire while a fire key is held, bomb when the bomb key goes down, dock while the dock key is held
+    player_input.fire = input_map.pressed(Action::Fire, keyboard_input);
+    player_input.bomb |= state == GameState::Playing && input_map.just_pressed(Action::Bomb, keyboard_input);
+    player_input.dock = input_map.pressed(Action::Dock, keyboard_input);
+
+    // Ask for an item when its key goes down; a later press this frame wins
+    if state == GameState::Playing {
+        for item in Item::ALL {
+            if input_map.just_pressed(Action::UseItem(item), keyboard_input) {
+                player_input.item = Some(item);
+            }
+        }
+    }
+}
+
+// System to turn the pause and slow-motion keys of any player into requests
+fn game_key_system(
+    keyboard_input: Res<Input<KeyCode>>,                      // For keyboard input
+    key_bindings: Res<KeyBindings>,                           // Which keys do what
+    mut pause_events: EventWriter<PauseToggleEvent>,          // Where pause requests go
+    mut slow_motion_events: EventWriter<SlowMotionToggleEvent>, // Where slow-motion requests go
+) {
+    // Either player can use them, but a key both bindings share only toggles once
+    let just_pressed = |action| {
+        key_bindings
+            .iter()
+            .any(|input_map| input_map.just_pressed(action, &keyboard_input))
+    };
+    if just_pressed(Action::Pause) {
+        pause_events.send_default();
+    }
+    if just_pressed(Action::SlowMotion) {
+        slow_motion_events.send_default();
+    }
+}
+
+// System to release latched thrust whenever play is interrupted
+fn release_thrust_latch_system(mut thrust_latch: ResMut<ThrustLatch>) {
+    thrust_latch.engaged = [false; MAX_PLAYERS];
+}
+
+// System to follow the control options in the settings
+fn input_settings_system(
+    settings: Res<Settings>,                                  // The player's preferences
+    mut control_scheme: ResMut<ControlScheme>,                // The preset to pick
+    mut accessibility: ResMut<AccessibilitySettings>,         // Whether thrust is a toggle and strafing is on
+) {
+    control_scheme.set_if_neq(settings.control_scheme);
+    accessibility.toggle_thrust = settings.toggle_thrust;
+    accessibility.strafing = settings.strafing;
+}
+
+// System to reset the key bindings when a different control scheme or number of players is picked
+fn control_scheme_system(
+    control_scheme: Res<ControlScheme>,                       // The selected preset
+    player_count: Res<PlayerCount>,                           // Whether the keyboard is shared
+    mut key_bindings: ResMut<KeyBindings>,                    // The bindings to rebuild
+) {
+    if control_scheme.is_changed() || player_count.is_changed() {
+        *key_bindings = KeyBindings::new(*control_scheme, &player_count);
+    }
+}
+
+// Setup system that builds the on-screen touch controls, hidden until needed
+fn setup_touch_controls(
+    mut commands: Commands,                                   // For spawning UI nodes
+    touch_settings: Res<TouchSettings>,                       // Layout of the controls
+) {
+    let diameter = touch_settings.joystick_radius * 2.0;
+    let knob_size = touch_settings.joystick_radius * 0.8;
+
+    // Spawn the joystick base in the lower-left corner
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(touch_settings.margin),
+                    bottom: Val::Px(touch_settings.margin),
+                    width: Val::Px(diameter),
+                    height: Val::Px(diameter),
+                    ..default()
+                },
+                background_color: Color::rgba(1.0, 1.0, 1.0, 0.15).into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            TouchControlsRoot,
+        ))
+        .with_children(|parent| {
+            // Spawn the knob that follows the steering finger
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px((diameter - knob_size) / 2.0),
+                        top: Val::Px((diameter - knob_size) / 2.0),
+                        width: Val::Px(knob_size),
+                        height: Val::Px(knob_size),
+                        ..default()
+                    },
+                    background_color: Color::rgba(1.0, 1.0, 1.0, 0.4).into(),
+                    ..default()
+                },
+                JoystickKnob,
+            ));
+        });
+
+    // Spawn the fire button in the lower-right corner
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                right: Val::Px(touch_settings.margin),
+                bottom: Val::Px(touch_settings.margin),
+                width: Val::Px(touch_settings.fire_button_size),
+                height: Val::Px(touch_settings.fire_button_size),
+                ..default()
+            },
+            background_color: Color::rgba(1.0, 0.3, 0.3, 0.3).into(),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        TouchControlsRoot,
+    ));
+}
+
+// System to show the touch controls and move the joystick knob
+fn touch_controls_ui_system(
+    touch_settings: Res<TouchSettings>,                       // Layout and visibility setting
+    touch_state: Res<TouchState>,                             // Fingers being tracked
+    mut root_query: Query<&mut Visibility, With<TouchControlsRoot>>, // Get the control nodes
+    mut knob_query: Query<&mut Style, With<JoystickKnob>>,    // Get the knob to move
+) {
+    let visibility = if touch_settings.always_show || touch_state.visible {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut root_visibility in root_query.iter_mut() {
+        *root_visibility = visibility;
+    }
+
+    // Offset the knob from the center of the base by the joystick deflection
+    let radius = touch_settings.joystick_radius;
+    let knob_size = radius * 0.8;
+    for mut style in knob_query.iter_mut() {
+        style.left = Val::Px(radius - knob_size / 2.0 + touch_state.joystick.x * radius);
+        style.top = Val::Px(radius - knob_size / 2.0 - touch_state.joystick.y * radius);
+    }
+}
+
+// System to track gamepads as they are plugged in and out
+fn gamepad_connection_system(
+    mut connection_events: EventReader<GamepadConnectionEvent>, // Connect/disconnect events
+    gamepads: Res<Gamepads>,                                  // Gamepads still connected
+    mut active_gamepad: ResMut<ActiveGamepad>,                // The gamepad driving the ship
+) {
+    for event in connection_events.read() {
+        match &event.connection {
+            GamepadConnection::Connected(info) => {
+                info!("Gamepad {} connected: {}", event.gamepad.id, info.name);
+                // The first gamepad plugged in takes control
+                if active_gamepad.0.is_none() {
+                    active_gamepad.0 = Some(event.gamepad);
+                }
+            }
+            GamepadConnection::Disconnected => {
+                info!("Gamepad {} disconnected", event.gamepad.id);
+                // Hand control to another connected gamepad, or back to the keyboard
+                if active_gamepad.0 == Some(event.gamepad) {
+                    active_gamepad.0 = gamepads.iter().find(|gamepad| *gamepad != event.gamepad);
+                }
+            }
+        }
+    }
+}
+
+// System to translate the active gamepad into the first player's input
+fn gamepad_input_system(
+    active_gamepad: Res<ActiveGamepad>,                       // The gamepad driving the ship
+    axes: Res<Axis<GamepadAxis>>,                             // Stick positions
+    button_axes: Res<Axis<GamepadButton>>,                    // Analog trigger positions
+    buttons: Res<Input<GamepadButton>>,                       // Button presses
+    stick_settings: Res<StickSettings>,                       // Deadzone and response curve
+    accessibility: Res<AccessibilitySettings>,                // Whether strafing is switched on
+    mut player_inputs: ResMut<PlayerInputs>,                  // Where the intent is written
+) {
+    let Some(gamepad) = active_gamepad.0 else {
+        return;
+    };
+    let player_input = &mut player_inputs[0];
+
+    // The keyboard already produced input this frame, so it wins
+    if player_input.is_held() {
+        return;
+    }
+
+    // Tilting the stick right turns the ship clockwise, proportionally to deflection
+    let stick_x = axes
+        .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+        .unwrap_or(0.0);
+    player_input.rotate = -apply_stick_response(stick_x.clamp(-1.0, 1.0), &stick_settings);
+
+    // The right trigger gives analog thrust, the A button full thrust
+    let trigger = button_axes
+        .get(GamepadButton::new(gamepad, GamepadButtonType::RightTrigger2))
+        .unwrap_or(0.0);
+    let forward = if buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::South)) {
+        1.0
+    } else {
+        trigger.clamp(0.0, 1.0)
+    };
+    // The left trigger gives analog reverse thrust
+    let reverse = button_axes
+        .get(GamepadButton::new(gamepad, GamepadButtonType::LeftTrigger2))
+        .unwrap_or(0.0)
+        .clamp(0.0, 1.0);
+    player_input.thrust = forward - reverse;
+
+    // The shoulder buttons strafe, if strafing is switched on
+    if accessibility.strafing {
+        if buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::LeftTrigger)) {
+            player_input.strafe -= 1.0;
+        }
+        if buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::RightTrigger)) {
+            player_input.strafe += 1.0;
+        }
+    }
+
+    // The X button fires, the Y button sets off a bomb and holding B docks
+    player_input.fire = buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::West));
+    player_input.bomb |= buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::North));
+    player_input.dock = buttons.pressed(GamepadButton::new(gamepad, GamepadButtonType::East));
+}
+
+// System to translate fingers on the touch controls into the first player's input
+fn touch_input_system(
+    touches: Res<Touches>,                                    // Fingers on the screen
+    window_query: Query<&Window, With<PrimaryWindow>>,        // Get window dimensions
+    touch_settings: Res<TouchSettings>,                       // Layout of the controls
+    mut touch_state: ResMut<TouchState>,                      // Fingers being tracked
+    ship_query: Query<(&Transform, &Player), With<Starship>>, // Get the ship's facing
+    mut player_inputs: ResMut<PlayerInputs>,                  // Where the intent is written
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let window_size = Vec2::new(window.width(), window.height());
+    let joystick_center = touch_settings.joystick_center(window_size);
+    let fire_center = touch_settings.fire_button_center(window_size);
+
+    // Reveal the controls the first time the screen is touched
+    if touches.any_just_pressed() {
+        touch_state.visible = true;
+    }
+
+    // Let go of the joystick when its finger lifts
+    if let Some(id) = touch_state.joystick_touch {
+        if touches.get_pressed(id).is_none() {
+            touch_state.joystick_touch = None;
+        }
+    }
+    // A new finger landing on the joystick takes it over
+    if touch_state.joystick_touch.is_none() {
+        touch_state.joystick_touch = touches
+            .iter_just_pressed()
+            .find(|touch| touch.position().distance(joystick_center) <= touch_settings.joystick_radius)
+            .map(|touch| touch.id());
+    }
+
+    // Deflection of the joystick, flipped so +Y points up like the world
+    touch_state.joystick = match touch_state
+        .joystick_touch
+        .and_then(|id| touches.get_pressed(id))
+    {
+        Some(touch) => {
+            let offset = (touch.position() - joystick_center) / touch_settings.joystick_radius;
+            Vec2::new(offset.x, -offset.y).clamp_length_max(1.0)
+        }
+        None => Vec2::ZERO,
+    };
+
+    // Any other finger on the fire button shoots, so steering and firing work together
+    let half_button = touch_settings.fire_button_size / 2.0;
+    let firing = touches.iter().any(|touch| {
+        let offset = (touch.position() - fire_center).abs();
+        offset.x <= half_button && offset.y <= half_button
+    });
+
+    // Keyboard and gamepad already produced input this frame, so they win
+    let player_input = &mut player_inputs[0];
+    if player_input.is_held() {
+        return;
+    }
+    player_input.fire = firing;
+
+    let joystick = touch_state.joystick;
+    if joystick == Vec2::ZERO {
+        return;
+    }
+    // Turn the ship towards the joystick direction and thrust by how far it is pushed
+    if let Some((transform, _)) = ship_query.iter().find(|(_, player)| player.id == 0) {
+        let facing = transform.up().truncate();
+        let turn = facing.angle_between(joystick);
+        player_input.rotate = (turn * touch_settings.steering_gain).clamp(-1.0, 1.0);
+    }
+    player_input.thrust = joystick.length();
+}
+
+// System to aim the first player's turret at the mouse cursor, independently of the hull
+fn mouse_aim_system(
+    window_query: Query<&Window, With<PrimaryWindow>>,        // Get the cursor position
+    camera_query: Query<(&Camera, &GlobalTransform)>,         // For converting to world space
+    ship_query: Query<(&Transform, &Player), With<Starship>>, // Get where the ship is
+    mut player_inputs: ResMut<PlayerInputs>,                  // Where the intent is written
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    // Keep the current aim while the cursor is outside the window
+    let Some(cursor) = cursor_world_position(window, camera_query.iter()) else {
+        return;
+    };
+    // Only the first player has the mouse; other turrets keep facing forward
+    let Some((transform, _)) = ship_query.iter().find(|(_, player)| player.id == 0) else {
+        return;
+    };
+    let direction = cursor - transform.translation.truncate();
+    if direction.length_squared() < f32::EPSILON {
+        return;
+    }
+    // Angle that makes the barrel (local +Y) point at the cursor in world space
+    player_inputs[0].aim = Some((-direction.x).atan2(direction.y));
+}
+
+// Set of the systems that fill in `PlayerInputs`, for anything that needs to run after them
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlayerInputSet;
+
+// Plugin for reading the player's controls
+pub struct InputPlugin;
+
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PlayerInputs>()
+            .register_type::<KeyBindings>()
+            .register_type::<ControlScheme>()
+            .register_type::<StickSettings>()
+            .register_type::<TouchSettings>()
+            .register_type::<AccessibilitySettings>()
+            .init_resource::<PlayerInputs>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<ControlScheme>()
+            .init_resource::<ActiveGamepad>()
+            .init_resource::<StickSettings>()
+            .init_resource::<TouchSettings>()
+            .init_resource::<TouchState>()
+            .init_resource::<AccessibilitySettings>()
+            .init_resource::<ThrustLatch>()
+            .add_event::<PauseToggleEvent>()
+            .add_event::<SlowMotionToggleEvent>()
+            .add_systems(Startup, setup_touch_controls)
+            // Translate keyboard and gamepad state into player input before gameplay runs
+            .add_systems(PreUpdate, (
+                gamepad_connection_system,
+                input_settings_system.run_if(resource_changed::<Settings>()),
+                control_scheme_system,
+                keyboard_input_system,
+                game_key_system,
+                gamepad_input_system,
+                touch_input_system,
+                mouse_aim_system,
+            ).chain().in_set(PlayerInputSet).after(InputSystem))
+            .add_systems(OnExit(GameState::Playing), release_thrust_latch_system)
+            .add_systems(Update, touch_controls_ui_system.run_if(any_with_component::<TouchControlsRoot>()));
+    }
+}
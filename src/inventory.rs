@@ -0,0 +1,241 @@
+// Consumable items the players carry and use when they choose to
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{Health, Shield};
+use crate::difficulty::{SurvivalSet, SurvivalTimer};
+use crate::input::{PlayerInputSet, PlayerInputs};
+use crate::player::{Engine, Player, Starship, player_exists};
+use crate::powerups::PowerUpSettings;
+use crate::session::{GameRng, GameState, NewGameEvent};
+use crate::GameSet;
+
+// The kinds of item the inventory holds
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Item {
+    RepairKit,   // Repairs part of the hull
+    FuelCell,    // Refills part of the fuel tank
+    ShieldCell,  // Recharges the shield completely
+}
+
+impl Item {
+    // Every item, in the order the counters are shown
+    pub const ALL: [Item; 3] = [Item::RepairKit, Item::FuelCell, Item::ShieldCell];
+
+    // What the HUD calls the item
+    pub fn label(self) -> &'static str {
+        match self {
+            Item::RepairKit => "Repair",
+            Item::FuelCell => "Fuel",
+            Item::ShieldCell => "Shield",
+        }
+    }
+
+    // Color of the ring shown when the item is used
+    pub fn color(self) -> Color {
+        match self {
+            Item::RepairKit => Color::rgb(0.2, 0.9, 0.3),
+            Item::FuelCell => Color::rgb(0.9, 0.9, 0.2),
+            Item::ShieldCell => Color::rgb(0.2, 0.8, 1.0),
+        }
+    }
+}
+
+// Resource holding the items the players share
+#[derive(Reflect, Resource, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[reflect(Resource)]
+pub struct Inventory {
+    pub repair_kits: u32,   // Repair kits carried
+    pub fuel_cells: u32,    // Fuel cells carried
+    pub shield_cells: u32,  // Shield cells carried
+}
+
+impl Inventory {
+    // How many of an item are carried
+    pub fn count(&self, item: Item) -> u32 {
+        match item {
+            Item::RepairKit => self.repair_kits,
+            Item::FuelCell => self.fuel_cells,
+            Item::ShieldCell => self.shield_cells,
+        }
+    }
+
+    // The counter of an item
+    fn count_mut(&mut self, item: Item) -> &mut u32 {
+        match item {
+            Item::RepairKit => &mut self.repair_kits,
+            Item::FuelCell => &mut self.fuel_cells,
+            Item::ShieldCell => &mut self.shield_cells,
+        }
+    }
+
+    // Put one more of an item in the inventory
+    pub fn add(&mut self, item: Item) {
+        let count = self.count_mut(item);
+        *count = count.saturating_add(1);
+    }
+
+    // Take one of an item out, returning whether there was one to take
+    pub fn take(&mut self, item: Item) -> bool {
+        let count = self.count_mut(item);
+        if *count == 0 {
+            return false;
+        }
+        *count -= 1;
+        true
+    }
+}
+
+// Resource configuring how items are earned
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct InventorySettings {
+    pub reward_interval: f32,  // Seconds survived between two free items, 0.0 for none
+}
+
+impl Default for InventorySettings {
+    fn default() -> Self {
+        Self {
+            reward_interval: 60.0,
+        }
+    }
+}
+
+// Define the ItemFlash component for the ring that closes in on a ship using an item,
+// spawned as a child of the ship so it follows it
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+struct ItemFlash {
+    color: Color,  // Color of the item used
+    timer: Timer,  // How long the ring takes to close
+}
+
+// Apply the effect of an item to the ship using it
+pub fn use_item(
+    item: Item,                                               // What is used
+    powerup_settings: &PowerUpSettings,                       // How strong the effects are
+    health: &mut Health,                                      // The ship's health
+    shield: Option<&mut Shield>,                              // The ship's shield, if any
+    engine: &mut Engine,                                      // The ship's engine
+) {
+    match item {
+        Item::RepairKit => {
+            health.current = (health.current + powerup_settings.health_restore).min(health.max);
+        }
+        Item::FuelCell => {
+            engine.fuel = (engine.fuel + powerup_settings.fuel_restore).min(engine.max_fuel);
+        }
+        Item::ShieldCell => {
+            if let Some(shield) = shield {
+                shield.current = shield.max;
+            }
+        }
+    }
+}
+
+// Components of a ship an item can affect
+type ItemTargetData<'a> = (Entity, &'a mut Health, Option<&'a mut Shield>, &'a mut Engine, &'a Player);
+
+// System to use the items the players asked for on their own ships
+fn use_item_system(
+    mut commands: Commands,                                   // For spawning the use animation
+    mut player_inputs: ResMut<PlayerInputs>,                  // Item requests to consume
+    mut inventory: ResMut<Inventory>,                         // Items left
+    powerup_settings: Res<PowerUpSettings>,                   // How strong the effects are
+    mut ship_query: Query<ItemTargetData, With<Starship>>,    // Get what items affect
+) {
+    // Each request is seen by one tick only, whether or not there is an item to use
+    let requested = player_inputs.0.map(|player_input| player_input.item);
+    for player_input in player_inputs.iter_mut() {
+        player_input.item = None;
+    }
+
+    for (entity, mut health, mut shield, mut engine, player) in ship_query.iter_mut() {
+        let Some(item) = requested[player.id] else {
+            continue;
+        };
+        if !inventory.take(item) {
+            continue;
+        }
+        use_item(item, &powerup_settings, &mut health, shield.as_deref_mut(), &mut engine);
+        commands.entity(entity).with_children(|parent| {
+            parent.spawn((
+                ItemFlash {
+                    color: item.color(),
+                    timer: Timer::from_seconds(0.4, TimerMode::Once),
+                },
+                TransformBundle::default(),
+            ));
+        });
+    }
+}
+
+// System to hand out a random item every time the run survives another interval
+fn item_reward_system(
+    time: Res<Time>,                                          // For spotting a crossed interval
+    survival: Res<SurvivalTimer>,                             // Time survived this run
+    inventory_settings: Res<InventorySettings>,               // How often items are given
+    mut rng: ResMut<GameRng>,                                 // For picking the item
+    mut inventory: ResMut<Inventory>,                         // Where the item goes
+) {
+    let interval = inventory_settings.reward_interval;
+    if survival.crossed(interval, time.delta_seconds()) {
+        let item = Item::ALL[rng.gen_range(0..Item::ALL.len())];
+        inventory.add(item);
+        info!("Survived another {:.0}s, earned one {} item", interval, item.label());
+    }
+}
+
+// System to close the use ring in on its ship, removing it when done
+fn item_flash_system(
+    mut commands: Commands,                                   // For despawning finished rings
+    time: Res<Time>,                                          // For animating the ring
+    mut gizmos: Gizmos,                                       // For drawing the ring
+    mut query: Query<(Entity, &GlobalTransform, &mut ItemFlash)>, // Get every ring
+) {
+    for (entity, transform, mut flash) in query.iter_mut() {
+        if flash.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let progress = flash.timer.percent();
+        gizmos.circle_2d(
+            transform.translation().truncate(),
+            40.0 * (1.0 - progress) + 10.0,
+            flash.color.with_a(1.0 - progress),
+        );
+    }
+}
+
+// System to empty the inventory when a new run starts
+fn reset_inventory_system(
+    mut new_game_events: EventReader<NewGameEvent>,           // Requests for a new run
+    mut inventory: ResMut<Inventory>,                         // Items to clear
+) {
+    if new_game_events.read().count() > 0 {
+        *inventory = Inventory::default();
+    }
+}
+
+// Plugin for the item inventory
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Inventory>()
+            .register_type::<InventorySettings>()
+            .register_type::<ItemFlash>()
+            .init_resource::<Inventory>()
+            .init_resource::<InventorySettings>()
+            .add_systems(Update, (
+                reset_inventory_system,
+                item_flash_system.run_if(in_state(GameState::Playing)),
+            ))
+            .add_systems(FixedUpdate, (
+                use_item_system.after(PlayerInputSet),
+                // Only counts survival time the same way the difficulty ramp does
+                item_reward_system.after(SurvivalSet).run_if(player_exists),
+            ).in_set(GameSet::Input).run_if(in_state(GameState::Playing)));
+    }
+}
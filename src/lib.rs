@@ -0,0 +1,181 @@
+// Import necessary modules from the Bevy game engine
+use bevy::prelude::*;
+
+// Each feature of the game lives in its own module with its own plugin
+pub mod camera;
+pub mod cli;
+pub mod combat;
+pub mod combo;
+pub mod config;
+pub mod death;
+pub mod debris;
+// The inspector doesn't run in the browser, so the web build goes without the developer tools
+#[cfg(all(feature = "debug-inspector", not(target_arch = "wasm32")))]
+pub mod console;
+#[cfg(all(feature = "debug-inspector", not(target_arch = "wasm32")))]
+pub mod debug;
+pub mod difficulty;
+pub mod effects;
+pub mod fallback;
+pub mod garage;
+pub mod graphics;
+pub mod headless;
+pub mod highscores;
+pub mod hostiles;
+pub mod hud;
+pub mod input;
+pub mod inventory;
+pub mod loading;
+pub mod palette;
+pub mod perf;
+pub mod physics;
+pub mod player;
+pub mod pool;
+pub mod powerups;
+pub mod practice;
+#[cfg(feature = "rapier")]
+pub mod rapier;
+pub mod replay;
+pub mod savegame;
+pub mod screenshot;
+pub mod session;
+pub mod settings;
+pub mod station;
+pub mod storage;
+pub mod trail;
+pub mod tutorial;
+pub mod weapons;
+pub mod wrap;
+pub mod zone;
+
+use camera::CameraPlugin;
+use cli::CliArgs;
+use combat::CombatPlugin;
+use combo::ComboPlugin;
+use config::ConfigPlugin;
+use death::DeathSequencePlugin;
+use debris::DebrisPlugin;
+use difficulty::DifficultyPlugin;
+use effects::EffectsPlugin;
+use garage::GaragePlugin;
+use graphics::GraphicsPlugin;
+use highscores::HighScoresPlugin;
+use hostiles::HostilesPlugin;
+use hud::HudPlugin;
+use input::InputPlugin;
+use inventory::InventoryPlugin;
+use loading::LoadingPlugin;
+use palette::PalettePlugin;
+use perf::PerfPlugin;
+use physics::{InterpolationSet, PhysicsPlugin};
+use player::PlayerPlugin;
+use pool::PoolPlugin;
+use powerups::PowerUpsPlugin;
+use practice::PracticePlugin;
+use replay::ReplayPlugin;
+use savegame::SaveGamePlugin;
+use screenshot::ScreenshotPlugin;
+use session::SessionPlugin;
+use settings::SettingsPlugin;
+use station::StationPlugin;
+use trail::TrailPlugin;
+use tutorial::TutorialPlugin;
+use weapons::WeaponsPlugin;
+use wrap::WrapPlugin;
+use zone::SafeZonePlugin;
+
+// The stages every fixed tick of the simulation runs through, in this order
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameSet {
+    Input,      // Intent becomes steering, thrust and shots
+    Physics,    // Everything moves
+    Collision,  // Overlaps deal damage and pick things up
+    Cleanup,    // The destroyed and expired go away, and new things spawn
+}
+
+// Whether this build adds the developer tools: every development build with the
+// `debug-inspector` feature, and release builds only when the `release-inspector` feature asks
+// for them. The inspector doesn't run in the browser, so the web build never has them
+pub const DEBUG_TOOLS_ENABLED: bool = cfg!(all(
+    feature = "debug-inspector",
+    not(target_arch = "wasm32"),
+    any(debug_assertions, feature = "release-inspector"),
+));
+
+// Add the developer tools to the app when this build has them
+pub fn add_debug_tools(app: &mut App) {
+    #[cfg(all(feature = "debug-inspector", not(target_arch = "wasm32")))]
+    if DEBUG_TOOLS_ENABLED {
+        app.add_plugins(debug::DebugToolsPlugin);
+    }
+    // Without the module there is nothing to add
+    #[cfg(not(all(feature = "debug-inspector", not(target_arch = "wasm32"))))]
+    let _ = app;
+}
+
+// Run every tick in the same order, so thrust applied in a tick moves the ship that tick; shared
+// by the game and the headless app so both step the simulation the same way
+pub fn configure_game_sets(app: &mut App) {
+    app.configure_sets(FixedUpdate, (
+        InterpolationSet,
+        GameSet::Input,
+        GameSet::Physics,
+        GameSet::Collision,
+        GameSet::Cleanup,
+    ).chain());
+}
+
+// Plugin bringing together every part of the game
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        configure_game_sets(app);
+        app
+            // Set background color to black
+            .insert_resource(ClearColor(Color::rgb(0.0, 0.0, 0.0)))
+            // Launched without options unless the binary parsed some first
+            .register_type::<CliArgs>()
+            .init_resource::<CliArgs>()
+            .add_plugins((
+                SettingsPlugin,
+                ConfigPlugin,
+                SessionPlugin,
+                LoadingPlugin,
+                InputPlugin,
+                ReplayPlugin,
+                SaveGamePlugin,
+                HighScoresPlugin,
+                PalettePlugin,
+                GraphicsPlugin,
+                ScreenshotPlugin,
+                DeathSequencePlugin,
+                PerfPlugin,
+                PoolPlugin,
+                WrapPlugin,
+            ))
+            .add_plugins((
+                PlayerPlugin,
+                PhysicsPlugin,
+                CombatPlugin,
+                WeaponsPlugin,
+                HostilesPlugin,
+                DifficultyPlugin,
+                EffectsPlugin,
+                PowerUpsPlugin,
+                InventoryPlugin,
+                GaragePlugin,
+                StationPlugin,
+                TrailPlugin,
+                CameraPlugin,
+                HudPlugin,
+                TutorialPlugin,
+            ))
+            .add_plugins((
+                ComboPlugin,
+                SafeZonePlugin,
+                DebrisPlugin,
+                PracticePlugin,
+            ));
+    }
+}
@@ -0,0 +1,135 @@
+// Holding the game back behind a loading screen until every asset it draws with is ready
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+
+use crate::fallback::{Placeholder, substitute};
+use crate::player::SHIP_PLACEHOLDER;
+use crate::session::GameState;
+
+// Every image the game loads, relative to the assets folder, with what to draw if it fails to load
+const ASSETS: [(&str, Option<Placeholder>); 1] = [("ship.png", Some(SHIP_PLACEHOLDER))];
+
+// An image being waited for
+struct PendingAsset {
+    path: &'static str,                 // Where it is, relative to the assets folder
+    handle: Handle<Image>,              // Keeps it loaded; loading the same path later gets it back
+    placeholder: Option<Placeholder>,   // What to draw in its place if it fails to load
+    substituted: bool,                  // Whether it failed and the placeholder took its place
+}
+
+// Resource holding the images being waited for
+#[derive(Resource, Default)]
+struct LoadingAssets(Vec<PendingAsset>);
+
+// Marker for the screen covering the game while it loads
+#[derive(Component)]
+struct LoadingScreen;
+
+// Setup system that starts every load and covers the game until they are done
+fn setup_loading(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let pending = ASSETS
+        .iter()
+        .map(|&(path, placeholder)| PendingAsset {
+            path,
+            handle: asset_server.load(path),
+            placeholder,
+            substituted: false,
+        })
+        .collect();
+    commands.insert_resource(LoadingAssets(pending));
+
+    // Opaque and drawn over everything, so nothing shows half loaded underneath
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::BLACK.into(),
+                z_index: ZIndex::Global(100),
+                ..default()
+            },
+            LoadingScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Loading…",
+                TextStyle {
+                    font_size: 32.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+}
+
+// System to start the game once every asset is loaded or stood in for, or name the ones that
+// failed with nothing to stand in for them
+fn loading_system(
+    asset_server: Res<AssetServer>,                           // For the load states
+    mut loading_assets: ResMut<LoadingAssets>,                // The assets waited for
+    mut images: ResMut<Assets<Image>>,                        // For putting placeholders in
+    screen_query: Query<&Children, With<LoadingScreen>>,      // Get the loading screen
+    mut text_query: Query<&mut Text>,                         // Get the words on the screen
+    mut next_state: ResMut<NextState<GameState>>,             // For leaving the loading screen
+) {
+    let mut failed = Vec::new();
+    for pending in loading_assets.0.iter_mut() {
+        if pending.substituted || asset_server.get_load_state(&pending.handle) != Some(LoadState::Failed) {
+            continue;
+        }
+        match pending.placeholder {
+            Some(placeholder) => {
+                substitute(&mut images, &pending.handle, pending.path, &placeholder);
+                pending.substituted = true;
+            }
+            None => {
+                error!("Failed to load the asset {}", pending.path);
+                failed.push(pending.path);
+            }
+        }
+    }
+    if !failed.is_empty() {
+        let message = format!("Failed to load {}\n\nCheck that the assets folder is next to the game", failed.join(", "));
+        for &child in screen_query.iter().flat_map(|children| children.iter()) {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                text.sections[0].value = message.clone();
+            }
+        }
+        next_state.set(GameState::LoadFailed);
+    } else if loading_assets
+        .0
+        .iter()
+        .all(|pending| pending.substituted || asset_server.get_load_state(&pending.handle) == Some(LoadState::Loaded))
+    {
+        next_state.set(GameState::Playing);
+    }
+}
+
+// System to uncover the game once loading is done
+fn close_loading(mut commands: Commands, screen_query: Query<Entity, With<LoadingScreen>>) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<LoadingAssets>();
+}
+
+// Plugin for the loading screen
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_loading)
+            .add_systems(Update, loading_system.run_if(in_state(GameState::Loading)))
+            // A failed load with no placeholder keeps the screen up with the error on it
+            .add_systems(OnTransition {
+                from: GameState::Loading,
+                to: GameState::Playing,
+            }, close_loading);
+    }
+}
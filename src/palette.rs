@@ -0,0 +1,177 @@
+// The colors everything in play is drawn in, with presets that stay apart for colorblind players
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::combat::DamageFlash;
+use crate::player::{Disabled, MAX_PLAYERS, Player};
+use crate::settings::Settings;
+
+// The sets of colors to choose from
+#[derive(Reflect, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorPreset {
+    #[default]
+    Default,       // The game's own colors
+    Deuteranopia,  // Blues, oranges and yellows in place of telling red from green
+    Protanopia,    // Like deuteranopia, brighter where reds would look dark
+}
+
+// Resource holding the colors of the ships, hostiles, shots and HUD bars
+#[derive(Reflect, Resource, Clone, PartialEq, Debug)]
+#[reflect(Resource)]
+pub struct ColorScheme {
+    pub ships: [Color; MAX_PLAYERS],  // Tint of every player's ship, so they can be told apart
+    pub asteroid: Color,              // Asteroids
+    pub enemy: Color,                 // Enemy drones
+    pub emp_mine: Color,              // EMP mines
+    pub projectile: Color,            // The ships' shots
+    pub debris: Color,                // Debris thrown off by explosions
+    pub damage_flash: Color,          // A ship just hit
+    pub disabled: Color,              // A ship with its engine knocked out
+    pub ammo_bar: Color,              // Fill of the ammo bar
+    pub fuel_bar: Color,              // Fill of the fuel bar
+    pub shield_bar: Color,            // Fill of the shield bar
+    pub heat_bar: Color,              // Fill of the heat gauge
+    pub heat_warning: Color,          // Fill of the heat gauge close to overheating
+}
+
+impl ColorScheme {
+    // The colors of a preset
+    pub fn preset(preset: ColorPreset) -> Self {
+        match preset {
+            ColorPreset::Default => Self {
+                ships: [Color::WHITE, Color::rgb(1.0, 0.6, 0.6)],
+                asteroid: Color::rgb(0.5, 0.45, 0.4),
+                enemy: Color::rgb(0.9, 0.2, 0.2),
+                emp_mine: Color::rgb(0.3, 0.5, 1.0),
+                projectile: Color::rgb(1.0, 0.9, 0.3),
+                debris: Color::rgb(1.0, 0.5, 0.2),
+                damage_flash: Color::rgb(1.0, 0.25, 0.2),
+                disabled: Color::rgb(0.3, 0.5, 1.0),
+                ammo_bar: Color::rgb(0.2, 0.6, 1.0),
+                fuel_bar: Color::rgb(0.3, 0.85, 0.3),
+                shield_bar: Color::rgb(0.2, 0.8, 1.0),
+                heat_bar: Color::rgb(1.0, 0.6, 0.2),
+                heat_warning: Color::rgb(1.0, 0.15, 0.1),
+            },
+            ColorPreset::Deuteranopia => Self {
+                ships: [Color::WHITE, Color::rgb(0.8, 0.47, 0.65)],
+                asteroid: Color::rgb(0.6, 0.6, 0.6),
+                enemy: Color::rgb(0.9, 0.62, 0.0),
+                emp_mine: Color::rgb(0.34, 0.71, 0.91),
+                projectile: Color::rgb(0.94, 0.89, 0.26),
+                debris: Color::rgb(0.84, 0.37, 0.0),
+                damage_flash: Color::rgb(0.84, 0.37, 0.0),
+                disabled: Color::rgb(0.0, 0.45, 0.7),
+                ammo_bar: Color::rgb(0.0, 0.45, 0.7),
+                fuel_bar: Color::rgb(0.34, 0.71, 0.91),
+                shield_bar: Color::rgb(0.8, 0.47, 0.65),
+                heat_bar: Color::rgb(0.9, 0.62, 0.0),
+                heat_warning: Color::rgb(0.94, 0.89, 0.26),
+            },
+            ColorPreset::Protanopia => Self {
+                ships: [Color::WHITE, Color::rgb(0.34, 0.71, 0.91)],
+                asteroid: Color::rgb(0.6, 0.6, 0.6),
+                enemy: Color::rgb(1.0, 0.75, 0.0),
+                emp_mine: Color::rgb(0.0, 0.45, 0.7),
+                projectile: Color::rgb(1.0, 1.0, 0.5),
+                debris: Color::rgb(1.0, 0.75, 0.0),
+                damage_flash: Color::rgb(1.0, 0.75, 0.0),
+                disabled: Color::rgb(0.0, 0.45, 0.7),
+                ammo_bar: Color::rgb(0.34, 0.71, 0.91),
+                fuel_bar: Color::rgb(0.0, 0.62, 0.45),
+                shield_bar: Color::rgb(0.8, 0.6, 0.7),
+                heat_bar: Color::rgb(1.0, 0.75, 0.0),
+                heat_warning: Color::WHITE,
+            },
+        }
+    }
+
+    // Tint of a player's ship
+    pub fn ship(&self, player: Player) -> Color {
+        self.ships[player.id]
+    }
+
+    // Color of something drawn with a tint
+    pub fn tint(&self, tint: Tint) -> Color {
+        match tint {
+            Tint::Ship(player) => self.ship(player),
+            Tint::Asteroid => self.asteroid,
+            Tint::Enemy => self.enemy,
+            Tint::EmpMine => self.emp_mine,
+            Tint::Projectile => self.projectile,
+            Tint::Debris => self.debris,
+        }
+    }
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        Self::preset(ColorPreset::default())
+    }
+}
+
+// Define the Tint component for sprites drawn in a color of the scheme, which follow it when it changes
+#[derive(Reflect, Component, Clone, Copy, PartialEq, Debug)]
+#[reflect(Component)]
+pub enum Tint {
+    Ship(Player),  // A player's ship
+    Asteroid,      // An asteroid
+    Enemy,         // An enemy drone
+    EmpMine,       // An EMP mine
+    Projectile,    // A ship's shot
+    Debris,        // A piece of debris from an explosion
+}
+
+impl Default for Tint {
+    fn default() -> Self {
+        Tint::Ship(Player::default())
+    }
+}
+
+// System to switch to the colors chosen in the settings
+fn color_scheme_settings_system(
+    settings: Res<Settings>,                                  // The player's preferences
+    mut color_scheme: ResMut<ColorScheme>,                    // The colors to switch
+) {
+    let chosen = ColorScheme::preset(settings.color_scheme);
+    if *color_scheme != chosen {
+        *color_scheme = chosen;
+    }
+}
+
+// Ships the scheme colors, leaving the ones a hit flash or knocked out engine is tinting alone
+type TintedShipFilter = (Without<DamageFlash>, Without<Disabled>);
+
+// System to color newly spawned sprites, and every sprite again when the scheme changes; a hit
+// flash or knocked out engine fades back to the scheme's color by itself
+fn tint_system(
+    color_scheme: Res<ColorScheme>,                           // The colors to draw in
+    mut sprite_query: Query<(Ref<Tint>, &mut Sprite)>,        // Get the plain sprites
+    mut atlas_query: Query<(Ref<Tint>, &mut TextureAtlasSprite), TintedShipFilter>, // Get the ships
+) {
+    let recolor = color_scheme.is_changed();
+    for (tint, mut sprite) in sprite_query.iter_mut() {
+        if recolor || tint.is_added() {
+            sprite.color = color_scheme.tint(*tint);
+        }
+    }
+    for (tint, mut sprite) in atlas_query.iter_mut() {
+        if recolor || tint.is_added() {
+            sprite.color = color_scheme.tint(*tint);
+        }
+    }
+}
+
+// Plugin for the color scheme
+pub struct PalettePlugin;
+
+impl Plugin for PalettePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ColorScheme>()
+            .register_type::<Tint>()
+            .init_resource::<ColorScheme>()
+            .add_systems(Update, color_scheme_settings_system.run_if(resource_changed::<Settings>()))
+            // After everything of the frame has spawned, so nothing is drawn before it is colored
+            .add_systems(PostUpdate, tint_system);
+    }
+}
@@ -0,0 +1,193 @@
+// An overlay showing the frame rate, how long the simulation takes and how many entities are
+// alive, for checking that nothing piles up over a long session
+use bevy::diagnostic::{Diagnostic, DiagnosticId, Diagnostics, DiagnosticsStore, FrameTimeDiagnosticsPlugin, RegisterDiagnostic};
+use bevy::ecs::entity::Entities;
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_real_timer;
+use bevy::utils::{Duration, Instant};
+
+use crate::effects::Particle;
+use crate::hostiles::{Asteroid, Enemy};
+use crate::physics::InterpolationSet;
+use crate::pool::Pooled;
+use crate::weapons::Projectile;
+use crate::GameSet;
+
+// Key showing and hiding the overlay
+const PERF_OVERLAY_KEY: KeyCode = KeyCode::F11;
+
+// Seconds between updates of the counts and the overlay, so it costs next to nothing itself
+const PERF_UPDATE_INTERVAL: f32 = 0.25;
+
+// Measurements kept of every custom diagnostic, for smoothing
+const PERF_HISTORY_LENGTH: usize = 20;
+
+// Time a fixed tick took, in milliseconds
+pub const FIXED_TICK_TIME: DiagnosticId = DiagnosticId::from_u128(0x7d1c_0a38_f1e4_4b2a_9a53_6c0e_21b4_0001);
+
+// Asteroids alive
+pub const ASTEROID_COUNT: DiagnosticId = DiagnosticId::from_u128(0x7d1c_0a38_f1e4_4b2a_9a53_6c0e_21b4_0002);
+
+// Enemy drones alive
+pub const ENEMY_COUNT: DiagnosticId = DiagnosticId::from_u128(0x7d1c_0a38_f1e4_4b2a_9a53_6c0e_21b4_0003);
+
+// Shots in flight
+pub const PROJECTILE_COUNT: DiagnosticId = DiagnosticId::from_u128(0x7d1c_0a38_f1e4_4b2a_9a53_6c0e_21b4_0004);
+
+// Particles of explosions, sparks and debris
+pub const PARTICLE_COUNT: DiagnosticId = DiagnosticId::from_u128(0x7d1c_0a38_f1e4_4b2a_9a53_6c0e_21b4_0005);
+
+// Shots and particles waiting in the pool to be reused
+pub const POOLED_COUNT: DiagnosticId = DiagnosticId::from_u128(0x7d1c_0a38_f1e4_4b2a_9a53_6c0e_21b4_0006);
+
+// Every entity in the world, including the HUD and cameras
+pub const ENTITY_COUNT: DiagnosticId = DiagnosticId::from_u128(0x7d1c_0a38_f1e4_4b2a_9a53_6c0e_21b4_0007);
+
+// Resource showing or hiding the overlay
+#[derive(Reflect, Resource, Default)]
+#[reflect(Resource)]
+pub struct PerfOverlay(pub bool);
+
+// Run condition for the measurements only the overlay uses
+fn perf_overlay_visible(overlay: Res<PerfOverlay>) -> bool {
+    overlay.0
+}
+
+// Resource holding when the fixed tick under way started
+#[derive(Resource, Default)]
+struct FixedTickStart(Option<Instant>);
+
+// Marker for the overlay text
+#[derive(Component)]
+struct PerfText;
+
+// Setup system that adds the overlay to the top right corner, below the developer tools' notes
+fn setup_perf_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 16.0,
+                color: Color::LIME_GREEN,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            top: Val::Px(58.0),
+            ..default()
+        }),
+        Visibility::Hidden,
+        PerfText,
+    ));
+}
+
+// System to show and hide the overlay
+fn perf_toggle_system(
+    keyboard_input: Res<Input<KeyCode>>,                      // For keyboard input
+    mut overlay: ResMut<PerfOverlay>,                         // Whether the overlay is shown
+    mut text_query: Query<&mut Visibility, With<PerfText>>,   // Get the overlay
+) {
+    if keyboard_input.just_pressed(PERF_OVERLAY_KEY) {
+        overlay.0 = !overlay.0;
+    }
+    if overlay.is_changed() {
+        for mut visibility in text_query.iter_mut() {
+            *visibility = if overlay.0 { Visibility::Inherited } else { Visibility::Hidden };
+        }
+    }
+}
+
+// System to note when a fixed tick starts
+fn fixed_tick_start_system(mut start: ResMut<FixedTickStart>) {
+    start.0 = Some(Instant::now());
+}
+
+// System to measure how long the fixed tick took, once everything in it has run
+fn fixed_tick_end_system(
+    mut start: ResMut<FixedTickStart>,                        // When the tick started
+    mut diagnostics: Diagnostics,                             // Where the measurement goes
+) {
+    if let Some(started) = start.0.take() {
+        diagnostics.add_measurement(FIXED_TICK_TIME, || started.elapsed().as_secs_f64() * 1000.0);
+    }
+}
+
+// System to count the entities alive in each category
+fn entity_count_system(
+    mut diagnostics: Diagnostics,                             // Where the counts go
+    entities: &Entities,                                      // Every entity in the world
+    asteroid_query: Query<(), With<Asteroid>>,                // Get the asteroids
+    enemy_query: Query<(), With<Enemy>>,                      // Get the enemy drones
+    projectile_query: Query<&Pooled, With<Projectile>>,       // Get the shots
+    particle_query: Query<&Pooled, With<Particle>>,           // Get the particles
+    pooled_query: Query<&Pooled>,                             // Get what waits in the pool
+) {
+    let in_use = |pooled: &&Pooled| pooled.in_use();
+    diagnostics.add_measurement(ASTEROID_COUNT, || asteroid_query.iter().count() as f64);
+    diagnostics.add_measurement(ENEMY_COUNT, || enemy_query.iter().count() as f64);
+    diagnostics.add_measurement(PROJECTILE_COUNT, || projectile_query.iter().filter(in_use).count() as f64);
+    diagnostics.add_measurement(PARTICLE_COUNT, || particle_query.iter().filter(in_use).count() as f64);
+    diagnostics.add_measurement(POOLED_COUNT, || pooled_query.iter().filter(|pooled| !in_use(pooled)).count() as f64);
+    diagnostics.add_measurement(ENTITY_COUNT, || entities.len() as f64);
+}
+
+// System to write the latest measurements into the overlay
+fn perf_text_system(
+    store: Res<DiagnosticsStore>,                             // Every diagnostic measured
+    mut text_query: Query<&mut Text, With<PerfText>>,         // Get the overlay
+) {
+    let smoothed = |id| store.get(id).and_then(Diagnostic::smoothed).unwrap_or(0.0);
+    let latest = |id| store.get(id).and_then(Diagnostic::value).unwrap_or(0.0);
+    let value = format!(
+        "FPS {:.0} ({:.1} ms)\nFixed tick {:.2} ms\nAsteroids {}\nEnemies {}\nShots {}\nParticles {}\nPooled {}\nEntities {}",
+        smoothed(FrameTimeDiagnosticsPlugin::FPS),
+        smoothed(FrameTimeDiagnosticsPlugin::FRAME_TIME),
+        smoothed(FIXED_TICK_TIME),
+        latest(ASTEROID_COUNT),
+        latest(ENEMY_COUNT),
+        latest(PROJECTILE_COUNT),
+        latest(PARTICLE_COUNT),
+        latest(POOLED_COUNT),
+        latest(ENTITY_COUNT)
+    );
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = value.clone();
+    }
+}
+
+// Plugin for the performance overlay
+pub struct PerfPlugin;
+
+impl Plugin for PerfPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        let interval = Duration::from_secs_f32(PERF_UPDATE_INTERVAL);
+        app.register_type::<PerfOverlay>()
+            .init_resource::<PerfOverlay>()
+            .init_resource::<FixedTickStart>()
+            .register_diagnostic(Diagnostic::new(FIXED_TICK_TIME, "fixed_tick_time", PERF_HISTORY_LENGTH).with_suffix("ms"))
+            .register_diagnostic(Diagnostic::new(ASTEROID_COUNT, "asteroid_count", PERF_HISTORY_LENGTH))
+            .register_diagnostic(Diagnostic::new(ENEMY_COUNT, "enemy_count", PERF_HISTORY_LENGTH))
+            .register_diagnostic(Diagnostic::new(PROJECTILE_COUNT, "projectile_count", PERF_HISTORY_LENGTH))
+            .register_diagnostic(Diagnostic::new(PARTICLE_COUNT, "particle_count", PERF_HISTORY_LENGTH))
+            .register_diagnostic(Diagnostic::new(POOLED_COUNT, "pooled_count", PERF_HISTORY_LENGTH))
+            .register_diagnostic(Diagnostic::new(ENTITY_COUNT, "entity_count", PERF_HISTORY_LENGTH))
+            .add_systems(Startup, setup_perf_text)
+            // Around everything else in the tick, in every state
+            .add_systems(FixedUpdate, (
+                fixed_tick_start_system.before(InterpolationSet),
+                fixed_tick_end_system.after(GameSet::Cleanup),
+            ).run_if(perf_overlay_visible))
+            .add_systems(Update, (
+                perf_toggle_system,
+                (entity_count_system, perf_text_system)
+                    .chain()
+                    .run_if(perf_overlay_visible)
+                    .run_if(on_real_timer(interval)),
+            ).chain());
+    }
+}
@@ -0,0 +1,483 @@
+// Movement, screen wrapping and the shared collision shape
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy::window::{PrimaryWindow, WindowResized};
+
+use crate::config::GameConfig;
+use crate::session::GameState;
+use crate::settings::Settings;
+use crate::GameSet;
+
+// Simulation ticks per second, unless Time<Fixed> is given another timestep
+pub const FIXED_TICK_RATE: f64 = 60.0;
+
+// Define the Velocity component to track ship's movement
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Velocity {
+    pub x: f32,  // Horizontal velocity
+    pub y: f32,  // Vertical velocity
+}
+
+impl Velocity {
+    // Get the velocity as a vector for vector math
+    pub fn as_vec2(&self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+}
+
+// Define the Collider component giving an entity a circular hit area
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Collider {
+    pub radius: f32,  // Radius of the hit circle in pixels
+}
+
+// Define the Mass component; thrust accelerates a heavier body less, and it pushes harder in a collision
+#[derive(Reflect, Component)]
+#[reflect(Component)]
+pub struct Mass(pub f32);
+
+impl Default for Mass {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+// Define the Lifetime component for entities that disappear on their own
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Lifetime(pub Timer);
+
+// Marker for entities drawn part of the way between the fixed ticks, for what moves fast enough
+// to stutter on a display refreshing faster than the simulation; the rest are drawn where the
+// last tick left them
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Interpolated;
+
+// Resource switching the drawing between the fixed ticks on or off, following the settings
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct InterpolationSettings {
+    pub enabled: bool,  // Whether Interpolated entities are drawn between the ticks
+}
+
+impl Default for InterpolationSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+// Define the PreviousTransform component for drawing moving entities between fixed ticks
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct PreviousTransform {
+    pub previous: Transform,   // Where the entity was before the last fixed tick
+    pub simulated: Transform,  // Where the last fixed tick left it
+    pub blended: bool,         // Whether Transform currently holds a drawn in-between position
+}
+
+// Label for the systems saving and blending transforms around the fixed ticks;
+// it comes first in every tick, and anything following entities on screen runs after it
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InterpolationSet;
+
+// Resource holding the size of the play area that entities wrap around, centered on the origin;
+// as big as the window unless the config makes it bigger, with the cameras following the ships
+#[derive(Reflect, Resource, Default)]
+#[reflect(Resource)]
+pub struct WorldBounds {
+    pub width: f32,   // Width of the play area in pixels
+    pub height: f32,  // Height of the play area in pixels
+}
+
+impl WorldBounds {
+    // Half the width and height, how far the play area reaches from the origin each way
+    pub fn half_extents(&self) -> Vec2 {
+        Vec2::new(self.width, self.height) / 2.0
+    }
+}
+
+// Smallest cell the grid uses, however small the circles in it are
+const MIN_GRID_CELL_SIZE: f32 = 16.0;
+
+// Resource bucketing hit circles into square cells, so a collision check only looks at the cells
+// near the circle it tests instead of at every entity; refilled every tick before collisions,
+// with cells sized after the largest circle so a check never searches more than a few of them
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct SpatialGrid {
+    pub cell_size: f32,                                  // Width and height of a cell in pixels
+    #[reflect(ignore)]
+    cells: HashMap<IVec2, Vec<(Entity, Vec2, f32)>>,     // Entity, position and radius of every circle, by cell
+    max_radius: f32,                                     // Largest radius of the circles in the grid
+    half_extents: Vec2,                                  // Size of the arena the circles wrap around
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        Self {
+            cell_size: 128.0,
+            cells: HashMap::default(),
+            max_radius: 0.0,
+            half_extents: Vec2::ZERO,
+        }
+    }
+}
+
+impl SpatialGrid {
+    // The cell a point falls in
+    fn cell(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size.max(1.0)).floor().as_ivec2()
+    }
+
+    // Empty the grid for circles up to the given radius in an arena of the given size, keeping
+    // the cells' storage for the next tick unless the cells change size. A cell is as wide as two
+    // of the largest circles, so checking one against another spans two cells each way at most;
+    // smaller cells were measured to spend longer looking cells up than they saved in checks
+    pub fn clear(&mut self, max_radius: f32, half_extents: Vec2) {
+        let cell_size = (4.0 * max_radius).max(MIN_GRID_CELL_SIZE);
+        if cell_size == self.cell_size {
+            for entries in self.cells.values_mut() {
+                entries.clear();
+            }
+        } else {
+            self.cells.clear();
+            self.cell_size = cell_size;
+        }
+        self.max_radius = 0.0;
+        self.half_extents = half_extents;
+    }
+
+    // Put a circle in the cell holding its center
+    pub fn insert(&mut self, entity: Entity, position: Vec2, radius: f32) {
+        let cell = self.cell(position);
+        self.cells.entry(cell).or_default().push((entity, position, radius));
+        self.max_radius = self.max_radius.max(radius);
+    }
+
+    // Every circle that may overlap a circle of the given radius, with its position and radius;
+    // a circle is only kept in one cell, so the cells searched reach out by the largest radius
+    // to catch circles centered across a cell boundary. Near an edge the cells across the
+    // opposite edge are searched too, and what is found there comes back at the position it
+    // wraps around to, next to the circle tested; an entity can then come back more than once,
+    // but only one of its positions can be close enough to overlap
+    pub fn query(&self, position: Vec2, radius: f32) -> impl Iterator<Item = (Entity, Vec2, f32)> + '_ {
+        let reach = radius + self.max_radius;
+        let wraps = |value: f32, half_extent: f32| {
+            // An arena without size, or smaller than the reach, has no seams worth searching over
+            let seams = half_extent > reach;
+            [
+                Some(0.0),
+                (seams && value - reach < -half_extent).then_some(2.0 * half_extent),
+                (seams && value + reach > half_extent).then_some(-2.0 * half_extent),
+            ]
+        };
+        let offsets_y = wraps(position.y, self.half_extents.y);
+        // The cells to search are gathered first, so going through their circles is a plain walk
+        let mut searched = Vec::new();
+        for offset_x in wraps(position.x, self.half_extents.x).into_iter().flatten() {
+            for offset_y in offsets_y.into_iter().flatten() {
+                // Searched around the circle's copy across the seam, then brought back beside it
+                let offset = Vec2::new(offset_x, offset_y);
+                let min = self.cell(position + offset - Vec2::splat(reach));
+                let max = self.cell(position + offset + Vec2::splat(reach));
+                for y in min.y..=max.y {
+                    for x in min.x..=max.x {
+                        if let Some(entries) = self.cells.get(&IVec2::new(x, y)) {
+                            searched.push((entries.as_slice(), offset));
+                        }
+                    }
+                }
+            }
+        }
+        searched.into_iter().flat_map(|(entries, offset)| {
+            entries.iter().map(move |&(entity, found, radius)| (entity, found - offset, radius))
+        })
+    }
+}
+
+// Where a position in the wrapping arena is closest to another, on this side of the edges or
+// across them; for comparing positions of things touching over a seam
+pub fn nearest_wrapped(position: Vec2, near: Vec2, half_extents: Vec2) -> Vec2 {
+    let nearest = |value: f32, near: f32, half_extent: f32| {
+        // An arena without size (no window yet) has no edges to wrap over
+        if half_extent <= 0.0 {
+            return value;
+        }
+        let size = 2.0 * half_extent;
+        value - ((value - near) / size).round() * size
+    };
+    Vec2::new(
+        nearest(position.x, near.x, half_extents.x),
+        nearest(position.y, near.y, half_extents.y),
+    )
+}
+
+// System to hand the simulation back its own transforms before a new frame starts
+fn restore_transform_system(
+    mut query: Query<(&mut Transform, &mut PreviousTransform)>, // Get every interpolated entity
+) {
+    for (mut transform, mut interpolation) in query.iter_mut() {
+        if interpolation.blended {
+            *transform = interpolation.simulated;
+            interpolation.blended = false;
+        }
+    }
+}
+
+// Filter for interpolated entities that don't have a PreviousTransform yet
+type NewMoverFilter = (With<Interpolated>, Without<PreviousTransform>);
+
+// System to remember where interpolated entities are at the start of every fixed tick
+fn save_previous_transform_system(
+    mut commands: Commands,                                   // For tracking new entities
+    mut query: Query<(&Transform, &mut PreviousTransform)>,   // Get every interpolated entity
+    new_query: Query<(Entity, &Transform), NewMoverFilter>,   // Get movers not tracked yet
+) {
+    for (transform, mut interpolation) in query.iter_mut() {
+        interpolation.previous = *transform;
+    }
+    for (entity, transform) in new_query.iter() {
+        commands.entity(entity).insert(PreviousTransform {
+            previous: *transform,
+            simulated: *transform,
+            blended: false,
+        });
+    }
+}
+
+// System to draw interpolated entities part of the way between the last two fixed ticks
+fn interpolate_transform_system(
+    fixed_time: Res<Time<Fixed>>,                             // How far into the next tick we are
+    interpolation_settings: Res<InterpolationSettings>,       // Whether to draw between the ticks
+    arena: Res<WorldBounds>,                                  // Size of the play area
+    mut query: Query<(&mut Transform, &mut PreviousTransform)>, // Get every interpolated entity
+) {
+    if !interpolation_settings.enabled {
+        return;
+    }
+    let blend = fixed_time.overstep_percentage();
+    for (mut transform, mut interpolation) in query.iter_mut() {
+        if !interpolation.blended {
+            interpolation.simulated = *transform;
+            interpolation.blended = true;
+        }
+        let from = interpolation.previous;
+        let to = interpolation.simulated;
+        // Moving over half the arena in one tick means wrapping, so snap instead of sweeping across
+        if (to.translation - from.translation).truncate().abs().cmpgt(arena.half_extents()).any() {
+            *transform = to;
+            continue;
+        }
+        transform.translation = from.translation.lerp(to.translation, blend);
+        transform.rotation = from.rotation.slerp(to.rotation, blend);
+    }
+}
+
+// System to switch the drawing between the ticks on or off as the settings ask
+fn interpolation_settings_system(
+    settings: Res<Settings>,                                  // The player's preferences
+    mut interpolation_settings: ResMut<InterpolationSettings>, // The drawing to switch
+) {
+    interpolation_settings.enabled = settings.smooth_motion;
+}
+
+// Filter for what velocity_system moves: everything in the default build, and what isn't a rigid
+// body with the rapier backend, which moves those itself
+#[cfg(not(feature = "rapier"))]
+type IntegratedFilter = ();
+#[cfg(feature = "rapier")]
+type IntegratedFilter = Without<bevy_rapier2d::prelude::RigidBody>;
+
+// System to handle ship movement
+fn velocity_system(
+    time: Res<Time>,                                          // For frame-independent movement
+    mut query: Query<(&mut Transform, &Velocity), IntegratedFilter>, // Get position and velocity
+    arena: Res<WorldBounds>,                                  // Size of the play area
+) {
+    let half_extents = arena.half_extents();
+    // Update position for each entity with Transform and Velocity
+    for (mut transform, velocity) in query.iter_mut() {
+        // Apply velocity to position
+        transform.translation.x += velocity.x * time.delta_seconds();
+        transform.translation.y += velocity.y * time.delta_seconds();
+
+        // Wrap around the arena horizontally and vertically
+        transform.translation.x = wrap_coordinate(transform.translation.x, half_extents.x);
+        transform.translation.y = wrap_coordinate(transform.translation.y, half_extents.y);
+    }
+}
+
+// Bring a coordinate back into -half_extent..half_extent, however far outside it is
+pub fn wrap_coordinate(value: f32, half_extent: f32) -> f32 {
+    // An arena without size (no window yet) leaves positions alone
+    if half_extent <= 0.0 {
+        return value;
+    }
+    if value < -half_extent || value > half_extent {
+        (value + half_extent).rem_euclid(2.0 * half_extent) - half_extent
+    } else {
+        value
+    }
+}
+
+// Where copies of something close to the arena's edges would show on the opposite edges, as the
+// offset from it: across the width when it nears the left or right edge, across the height when
+// it nears the top or bottom, and both at a corner
+pub fn wrap_ghost_offsets(position: Vec2, half_extents: Vec2, margin: f32) -> [Option<Vec2>; 2] {
+    let across = |value: f32, half_extent: f32| {
+        // An arena without size (no window yet) has no edges to wrap over
+        if half_extent <= 0.0 {
+            None
+        } else if value > half_extent - margin {
+            Some(-2.0 * half_extent)
+        } else if value < margin - half_extent {
+            Some(2.0 * half_extent)
+        } else {
+            None
+        }
+    };
+    [
+        across(position.x, half_extents.x).map(|x| Vec2::new(x, 0.0)),
+        across(position.y, half_extents.y).map(|y| Vec2::new(0.0, y)),
+    ]
+}
+
+// System to size the play area from the config, taking the window's size on any side the
+// config leaves at zero; runs again whenever the window is resized or the config changes
+fn world_bounds_system(
+    config: Res<GameConfig>,                                  // World size, if set
+    window_query: Query<&Window, With<PrimaryWindow>>,        // Get window dimensions
+    mut world: ResMut<WorldBounds>,                           // The play area to size
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let size = config.world.size(Vec2::new(window.width(), window.height()));
+    if world.width != size.x || world.height != size.y {
+        world.width = size.x;
+        world.height = size.y;
+    }
+}
+
+// Speed at which two bodies approach each other along the line between them
+pub fn closing_speed(position_a: Vec2, velocity_a: Vec2, position_b: Vec2, velocity_b: Vec2) -> f32 {
+    // Unit vector pointing from b to a
+    let normal = (position_a - position_b).normalize_or_zero();
+    // Positive when a moves towards b faster than b gets away
+    (-(velocity_a - velocity_b).dot(normal)).max(0.0)
+}
+
+// Change in velocity of each of two bodies bouncing off each other, given the restitution
+// (1.0 perfectly elastic, 0.0 not bouncing at all); momentum is shared out by mass, so the
+// lighter body is thrown harder, and bodies already moving apart are left alone
+pub fn collision_impulse(
+    position_a: Vec2,                                         // Where a is
+    velocity_a: Vec2,                                         // How a moves
+    mass_a: f32,                                              // How heavy a is
+    position_b: Vec2,                                         // Where b is
+    velocity_b: Vec2,                                         // How b moves
+    mass_b: f32,                                              // How heavy b is
+    restitution: f32,                                         // How bouncy the collision is
+) -> (Vec2, Vec2) {
+    let speed = closing_speed(position_a, velocity_a, position_b, velocity_b);
+    if speed <= 0.0 {
+        return (Vec2::ZERO, Vec2::ZERO);
+    }
+    let inverse_masses = 1.0 / mass_a.max(f32::EPSILON) + 1.0 / mass_b.max(f32::EPSILON);
+    // Unit vector pointing from b to a, the way a is pushed
+    let normal = (position_a - position_b).normalize_or_zero();
+    let impulse = normal * (1.0 + restitution) * speed / inverse_masses;
+    (impulse / mass_a.max(f32::EPSILON), -impulse / mass_b.max(f32::EPSILON))
+}
+
+// Distance along a ray to where it first meets a circle, or None if it misses;
+// a ray starting inside the circle meets it straight away
+pub fn ray_circle_distance(origin: Vec2, direction: Vec2, center: Vec2, radius: f32) -> Option<f32> {
+    let offset = origin - center;
+    let b = offset.dot(direction);
+    let c = offset.length_squared() - radius * radius;
+    // Starting outside and pointing away never meets it
+    if c > 0.0 && b > 0.0 {
+        return None;
+    }
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    Some((-b - discriminant.sqrt()).max(0.0))
+}
+
+// Time until a shot fired now at the given speed meets a target, or None if it can never catch up;
+// the offset and velocity are the target's relative to the shooter
+pub fn intercept_time(offset: Vec2, relative_velocity: Vec2, projectile_speed: f32) -> Option<f32> {
+    // Solve |offset + relative_velocity * t| = projectile_speed * t for the earliest t > 0
+    let a = relative_velocity.length_squared() - projectile_speed * projectile_speed;
+    let b = 2.0 * offset.dot(relative_velocity);
+    let c = offset.length_squared();
+    if a.abs() < f32::EPSILON {
+        // The target moves exactly as fast as the shot, so only one meeting is possible
+        let t = -c / b;
+        return (b < 0.0).then_some(t);
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    let (t1, t2) = ((-b - root) / (2.0 * a), (-b + root) / (2.0 * a));
+    [t1.min(t2), t1.max(t2)].into_iter().find(|t| *t > 0.0)
+}
+
+// System to despawn entities whose lifetime has run out
+fn lifetime_system(
+    mut commands: Commands,                                   // For despawning entities
+    time: Res<Time>,                                          // For ticking lifetimes
+    mut query: Query<(Entity, &mut Lifetime)>,                // Get every entity with a lifetime
+) {
+    for (entity, mut lifetime) in query.iter_mut() {
+        if lifetime.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+// Plugin for movement and the arena everything wraps around
+pub struct PhysicsPlugin;
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Velocity>()
+            .register_type::<Collider>()
+            .register_type::<Mass>()
+            .register_type::<Lifetime>()
+            .register_type::<WorldBounds>()
+            .register_type::<Interpolated>()
+            .register_type::<InterpolationSettings>()
+            .register_type::<PreviousTransform>()
+            .register_type::<SpatialGrid>()
+            .init_resource::<WorldBounds>()
+            .init_resource::<SpatialGrid>()
+            .init_resource::<InterpolationSettings>()
+            .insert_resource(Time::<Fixed>::from_hz(FIXED_TICK_RATE))
+            .add_systems(Startup, world_bounds_system)
+            .add_systems(Update, world_bounds_system
+                .run_if(resource_changed::<GameConfig>().or_else(on_event::<WindowResized>())))
+            // Move on a fixed timestep so the result doesn't depend on the frame rate
+            .add_systems(FixedUpdate, (
+                velocity_system.in_set(GameSet::Physics),
+                lifetime_system.in_set(GameSet::Cleanup),
+            ).run_if(in_state(GameState::Playing)))
+            // Draw movement smoothly between the ticks, whatever the frame rate
+            .add_systems(First, restore_transform_system)
+            .add_systems(FixedUpdate, save_previous_transform_system.in_set(InterpolationSet))
+            .add_systems(Update, (
+                interpolation_settings_system.run_if(resource_changed::<Settings>()),
+                interpolate_transform_system.in_set(InterpolationSet),
+            ).chain());
+        #[cfg(feature = "rapier")]
+        app.add_plugins(crate::rapier::RapierBackendPlugin);
+    }
+}
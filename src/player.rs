@@ -0,0 +1,894 @@
+// The player's ship: spawning, steering, thrust and coming back after being destroyed
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::god_mode;
+use crate::combat::{DamageFlash, Health, Invulnerable, Shield, ShieldHitEvent};
+use crate::config::GameConfig;
+use crate::effects::spawn_explosion;
+use crate::fallback::{Placeholder, PlaceholderShape};
+use crate::input::{PlayerInput, PlayerInputSet, PlayerInputs};
+use crate::palette::{ColorScheme, Tint};
+use crate::physics::{Collider, Interpolated, InterpolationSet, Mass, Velocity};
+use crate::session::{GameRng, GameState, GameplayEntity, Lives};
+use crate::trail::{Trail, TrailSettings};
+use crate::weapons::{AmmoPool, Beam, Heat, Turret, Weapon, WeaponType};
+use crate::GameSet;
+
+// Define the Starship component with reflection capabilities for the inspector
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Starship {
+    pub rotation_speed: f32,  // How fast the ship can rotate
+    pub max_speed: f32,       // Fastest the ship can fly
+}
+
+// Define the Disabled component for ships knocked out by an EMP: the engine gives no thrust and
+// steering is sluggish until the timer runs out, while the ship keeps drifting and colliding
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Disabled {
+    pub timer: Timer,  // Time until the engine comes back
+}
+
+// Share of the usual rotation speed left to a disabled ship
+const DISABLED_ROTATION: f32 = 0.3;
+
+// What the ship is drawn as if ship.png fails to load, laid out like its 2x2 sheet of 32x32 frames
+pub const SHIP_PLACEHOLDER: Placeholder = Placeholder {
+    cell: UVec2::new(32, 32),
+    frames: UVec2::new(2, 2),
+    shape: PlaceholderShape::Triangle,
+    color: Color::WHITE,
+};
+
+// The shield bubble, a plain disc drawn in code and tinted by the sprite
+const SHIELD_BUBBLE: Placeholder = Placeholder {
+    cell: UVec2::new(64, 64),
+    frames: UVec2::new(1, 1),
+    shape: PlaceholderShape::Circle,
+    color: Color::WHITE,
+};
+
+// Size of the shield bubble next to the ship's 32 pixel sprite, before scaling
+const SHIELD_BUBBLE_SIZE: f32 = 40.0;
+
+// Color of the shield bubble, and its opacity while the shield just sits there
+const SHIELD_BUBBLE_COLOR: Color = Color::rgb(0.3, 0.7, 1.0);
+const SHIELD_BUBBLE_OPACITY: f32 = 0.2;
+
+// Opacity added at the start of a flash after the shield soaks up a hit
+const SHIELD_FLASH_OPACITY: f32 = 0.4;
+
+// Seconds the flash after a hit takes to die down, and the bubble of a broken shield to fade out
+const SHIELD_FLASH_TIME: f32 = 0.1;
+const SHIELD_FADE_TIME: f32 = 0.5;
+
+// Define the ShieldBubble component for the disc drawn around a shielded ship, as its child so it
+// moves with the ship
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct ShieldBubble {
+    pub flash: f32,  // Seconds left of the flash after a hit
+    pub fade: f32,   // Seconds left of fading out after the shield broke
+}
+
+// Most players that can share one screen
+pub const MAX_PLAYERS: usize = 2;
+
+// Define the Player component telling the ships of different players apart
+#[derive(Reflect, Component, Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[reflect(Component)]
+pub struct Player {
+    pub id: usize,  // 0 for the first player, 1 for the second
+}
+
+// Resource holding how many players a new game starts with
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct PlayerCount(pub usize);
+
+impl Default for PlayerCount {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+impl PlayerCount {
+    // Ids of every player taking part, never more than the screen can be split for
+    pub fn ids(&self) -> std::ops::Range<usize> {
+        0..self.0.clamp(1, MAX_PLAYERS)
+    }
+}
+
+// Where a player's ship starts: the center alone, side by side with two players
+pub fn spawn_point(id: usize, player_count: &PlayerCount) -> Vec2 {
+    let count = player_count.ids().len();
+    if count == 1 {
+        return Vec2::ZERO;
+    }
+    Vec2::new((id as f32 - (count - 1) as f32 / 2.0) * 240.0, 0.0)
+}
+
+// Run condition for systems that have nothing to do without a ship in play
+pub fn player_exists(query: Query<(), With<Starship>>) -> bool {
+    !query.is_empty()
+}
+
+// Define the Engine component to handle ship's propulsion
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Engine {
+    pub fuel: f32,           // Amount of fuel remaining
+    pub max_fuel: f32,       // Most fuel the tank can hold
+    pub thrust: f32,         // Power of the engine
+    pub strafe_thrust: f32,  // Power of the side thrusters
+}
+
+// Define the EngineThrottle component easing a ship's engine towards the requested thrust, so full
+// thrust builds up and dies down over a moment instead of snapping on and off
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct EngineThrottle {
+    pub current: f32,  // Thrust the engine gives right now, from -1.0 (full reverse) to 1.0 (full thrust)
+}
+
+impl EngineThrottle {
+    // Move the throttle one step towards the requested thrust and return where it ends up;
+    // opening up follows the attack time, easing off or reversing the release time
+    pub fn follow(&mut self, target: f32, response: &ThrottleResponse, delta_seconds: f32) -> f32 {
+        let opening = target.abs() > self.current.abs() && target * self.current >= 0.0;
+        let time = if opening { response.attack } else { response.release };
+        // No time at all means no easing
+        let step = if time > 0.0 { delta_seconds / time } else { f32::INFINITY };
+        self.current += (target - self.current).clamp(-step, step);
+        self.current
+    }
+}
+
+// Resource holding how quickly engines answer the throttle
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct ThrottleResponse {
+    pub attack: f32,   // Seconds from idle to full thrust
+    pub release: f32,  // Seconds from full thrust back to idle
+}
+
+impl Default for ThrottleResponse {
+    fn default() -> Self {
+        Self {
+            attack: 0.25,
+            release: 0.15,
+        }
+    }
+}
+
+// Define the RotationCharge component counting how long a ship has been turning, so a tap turns it
+// a little and a long hold spins it up to full speed
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct RotationCharge {
+    pub held: f32,  // Seconds the ship has been turning without letting go
+}
+
+impl RotationCharge {
+    // Scale the requested turn by how far the ramp has got and count the step towards it; letting
+    // go starts the ramp over
+    pub fn follow(&mut self, rotate: f32, ramp: &RotationRamp, delta_seconds: f32) -> f32 {
+        if rotate == 0.0 {
+            self.held = 0.0;
+            return 0.0;
+        }
+        let share = (ramp.base + ramp.rate * self.held).clamp(0.0, 1.0);
+        self.held += delta_seconds;
+        rotate * share
+    }
+}
+
+// Resource holding how quickly a held turn spins up to the ship's full rotation speed
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct RotationRamp {
+    pub base: f32,  // Share of the full rotation speed a turn starts at
+    pub rate: f32,  // Share of the full rotation speed added per second held
+}
+
+impl Default for RotationRamp {
+    fn default() -> Self {
+        Self {
+            base: 0.4,
+            rate: 1.2,
+        }
+    }
+}
+
+// Length of the plume behind a ship at full thrust, in pixels
+const THRUST_PLUME_LENGTH: f32 = 28.0;
+
+// Distance of the thruster nozzles from the middle of the 32 pixel ship sprite, before scaling
+const NOZZLE_OFFSET: f32 = 14.0;
+
+// Width and length of a nozzle's flame at full power, before scaling
+const NOZZLE_FLAME_SIZE: Vec2 = Vec2::new(4.0, 7.0);
+
+// Color of a nozzle's flame
+const NOZZLE_FLAME_COLOR: Color = Color::rgb(1.0, 0.7, 0.3);
+
+// Define the Nozzle component for the thruster flames drawn around a ship, one for each way it
+// can be pushed; each sits on the side opposite the way it pushes and shows while it fires
+#[derive(Reflect, Component, Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[reflect(Component)]
+pub enum Nozzle {
+    #[default]
+    Forward,      // The main engine at the back
+    Reverse,      // At the nose, for braking and reversing
+    StrafeLeft,   // On the right flank, pushing the ship to its left
+    StrafeRight,  // On the left flank, pushing the ship to its right
+}
+
+impl Nozzle {
+    // Every nozzle a ship has
+    pub const ALL: [Nozzle; 4] = [Nozzle::Forward, Nozzle::Reverse, Nozzle::StrafeLeft, Nozzle::StrafeRight];
+
+    // Where the nozzle sits on the ship, turned so its flame points away from the hull
+    fn transform(self) -> Transform {
+        let (position, angle) = match self {
+            Nozzle::Forward => (Vec2::new(0.0, -NOZZLE_OFFSET), std::f32::consts::PI),
+            Nozzle::Reverse => (Vec2::new(0.0, NOZZLE_OFFSET), 0.0),
+            Nozzle::StrafeLeft => (Vec2::new(NOZZLE_OFFSET, 0.0), -std::f32::consts::FRAC_PI_2),
+            Nozzle::StrafeRight => (Vec2::new(-NOZZLE_OFFSET, 0.0), std::f32::consts::FRAC_PI_2),
+        };
+        // Just under the hull
+        Transform::from_translation(position.extend(-0.5)).with_rotation(Quat::from_rotation_z(angle))
+    }
+
+    // How hard the nozzle fires for the given thrust and strafe, from 0.0 (off) to 1.0
+    pub fn power(self, thrust: f32, strafe: f32) -> f32 {
+        let power = match self {
+            Nozzle::Forward => thrust,
+            Nozzle::Reverse => -thrust,
+            Nozzle::StrafeLeft => -strafe,
+            Nozzle::StrafeRight => strafe,
+        };
+        power.clamp(0.0, 1.0)
+    }
+}
+
+// Resource holding the starting values of every new ship
+#[derive(Reflect, Resource, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[reflect(Resource)]
+pub struct Loadout {
+    pub rotation_speed: f32,  // How fast the ship can rotate
+    pub thrust: f32,          // Power of the engine
+    #[serde(default = "default_strafe_thrust")]
+    pub strafe_thrust: f32,   // Power of the side thrusters
+    pub fuel: f32,            // Fuel in the tank at launch
+    pub max_fuel: f32,        // Most fuel the tank can hold
+    pub max_speed: f32,       // Fastest the ship can fly
+    pub fire_cooldown: f32,   // Seconds between two shots of the cannon
+}
+
+// Side thrusters of loadouts written before ships could strafe
+fn default_strafe_thrust() -> f32 {
+    60.0
+}
+
+impl FromWorld for Loadout {
+    // The selected class's values, as the game config gives them
+    fn from_world(world: &mut World) -> Self {
+        let ship_class = world.get_resource::<ShipClass>().copied().unwrap_or_default();
+        world
+            .get_resource::<GameConfig>()
+            .map_or_else(|| ship_class.loadout(), |config| config.ships.loadout(ship_class))
+    }
+}
+
+// The ship classes a player can pick from
+#[derive(Reflect, Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[reflect(Resource)]
+pub enum ShipClass {
+    #[default]
+    Scout,  // All-rounder
+    Tank,   // Slow to turn and accelerate, but carries lots of fuel
+    Racer,  // Quick and nimble on a small tank
+}
+
+impl ShipClass {
+    // Starting values for ships of this class, unless the game config tunes them
+    pub fn loadout(self) -> Loadout {
+        match self {
+            ShipClass::Scout => Loadout {
+                rotation_speed: 1.0,
+                thrust: 100.0,
+                strafe_thrust: 60.0,
+                fuel: 1000.0,
+                max_fuel: 1000.0,
+                max_speed: 500.0,
+                fire_cooldown: 0.2,
+            },
+            ShipClass::Tank => Loadout {
+                rotation_speed: 0.7,
+                thrust: 70.0,
+                strafe_thrust: 40.0,
+                fuel: 2000.0,
+                max_fuel: 2000.0,
+                max_speed: 350.0,
+                fire_cooldown: 0.2,
+            },
+            ShipClass::Racer => Loadout {
+                rotation_speed: 1.6,
+                thrust: 160.0,
+                strafe_thrust: 100.0,
+                fuel: 600.0,
+                max_fuel: 600.0,
+                max_speed: 750.0,
+                fire_cooldown: 0.2,
+            },
+        }
+    }
+}
+
+// Resource shaping how fuel burn grows with throttle
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct EfficiencyCurve {
+    exponent: f32,  // 1.0 burns fuel linearly, 2.0 makes half throttle burn a quarter
+    scale: f32,     // Fuel burned per second per unit of thrust at full throttle
+}
+
+impl Default for EfficiencyCurve {
+    fn default() -> Self {
+        Self {
+            exponent: 2.0,
+            scale: 1.0,
+        }
+    }
+}
+
+impl EfficiencyCurve {
+    // Fuel burned per second by an engine of the given power at the given throttle
+    fn fuel_rate(&self, engine_thrust: f32, throttle: f32) -> f32 {
+        engine_thrust * self.scale * throttle.abs().powf(self.exponent)
+    }
+}
+
+// Event sent when a ship is destroyed
+#[derive(Event)]
+pub struct ShipDestroyed {
+    pub player: Player,   // Whose ship it was
+    pub position: Vec2,   // Where it blew up
+}
+
+// Resource counting down until each player's destroyed ship comes back
+#[derive(Resource)]
+pub struct Respawn {
+    pub timers: [Timer; MAX_PLAYERS],  // Delay between destruction and the new ship
+    pub pending: [bool; MAX_PLAYERS],  // Whether a new ship is on its way
+}
+
+impl Default for Respawn {
+    fn default() -> Self {
+        Self {
+            timers: std::array::from_fn(|_| Timer::from_seconds(2.0, TimerMode::Once)),
+            pending: [false; MAX_PLAYERS],
+        }
+    }
+}
+
+// Scale the ship sprite is drawn at
+pub const SHIP_SCALE: f32 = 2.0;
+
+// Resource holding the ship's sprite sheet so respawns can reuse it
+#[derive(Resource)]
+pub struct ShipAssets {
+    pub texture_atlas: Handle<TextureAtlas>,  // Sprite sheet of the ship
+    pub shield_bubble: Handle<Image>,         // Disc drawn around a shielded ship
+}
+
+// Setup system that spawns the ship when the game starts
+fn setup_ship(
+    mut commands: Commands,                                   // For spawning entities
+    asset_server: Res<AssetServer>,                           // For loading assets
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,        // For handling sprite sheets
+    mut images: ResMut<Assets<Image>>,                        // For the shield bubble drawn in code
+    trail_settings: Res<TrailSettings>,                       // For the ship's trail length
+    loadout: Res<Loadout>,                                    // Starting values of the ships
+    player_count: Res<PlayerCount>,                           // How many ships to spawn
+) {
+    // Load the ship sprite sheet
+    let texture_handle = asset_server.load("ship.png");
+    // Create a texture atlas from the sprite sheet (2x2 grid of 32x32 pixel sprites)
+    let texture_atlas = TextureAtlas::from_grid(texture_handle, Vec2::new(32.0, 32.0), 2, 2, None, None);
+    // Add the texture atlas to the game's assets, keeping it around for respawns
+    let ship_assets = ShipAssets {
+        texture_atlas: texture_atlases.add(texture_atlas),
+        shield_bubble: images.add(SHIELD_BUBBLE.image()),
+    };
+
+    // Spawn a ship for every player
+    for id in player_count.ids() {
+        let position = spawn_point(id, &player_count);
+        spawn_ship(&mut commands, &ship_assets, &trail_settings, &loadout, Player { id }, position);
+    }
+    commands.insert_resource(ship_assets);
+}
+
+// Spawn a player's ship
+pub fn spawn_ship(
+    commands: &mut Commands,                                  // For spawning entities
+    ship_assets: &ShipAssets,                                 // The ship's sprite sheet
+    trail_settings: &TrailSettings,                           // For the ship's trail length
+    loadout: &Loadout,                                        // Starting values of the ship
+    player: Player,                                           // Who flies the ship
+    position: Vec2,                                           // Where the ship starts
+) -> Entity {
+    commands
+        .spawn((
+            // Visual components for the ship
+            SpriteSheetBundle {
+                texture_atlas: ship_assets.texture_atlas.clone(),
+                transform: Transform {
+                    scale: Vec3::new(SHIP_SCALE, SHIP_SCALE, 1.0), // Ship size
+                    translation: position.extend(0.0),        // Starting position
+                    ..default()
+                },
+                ..default()
+            },
+            // Add ship components with initial values
+            GameplayEntity,
+            // Tinted by player so the two ships can be told apart
+            (player, Tint::Ship(player)),
+            Starship {
+                rotation_speed: loadout.rotation_speed,
+                max_speed: loadout.max_speed,
+            },
+            (Velocity { x: 0.0, y: 0.0 }, Interpolated),
+            Mass::default(),
+            // The throttle starts at idle
+            (
+                Engine {
+                    fuel: loadout.fuel.min(loadout.max_fuel),
+                    max_fuel: loadout.max_fuel,
+                    thrust: loadout.thrust,
+                    strafe_thrust: loadout.strafe_thrust,
+                },
+                EngineThrottle::default(),
+                RotationCharge::default(),
+            ),
+            Weapon {
+                weapon_type: WeaponType::Standard,
+                cooldown: Timer::from_seconds(loadout.fire_cooldown, TimerMode::Once),
+                trigger_held: false,
+                damage_bonus: 0.0,
+            },
+            AmmoPool {
+                energy: 100.0,
+                max: 100.0,
+                cost_per_shot: 10.0,
+                recharge: 20.0,
+            },
+            Heat {
+                current: 0.0,
+                max: 100.0,
+                cool_rate: 30.0,
+                per_shot: 12.0,
+                resume_below: 40.0,
+                overheated: false,
+            },
+            Beam::default(),
+            Trail::new(trail_settings.ship_length),
+            Health {
+                current: 100.0,
+                max: 100.0,
+            },
+            Shield {
+                current: 50.0,
+                max: 50.0,
+                since_hit: 0.0,
+            },
+            Collider { radius: 24.0 },
+        ))
+        .with_children(|parent| {
+            // Spawn the turret on top of the hull so it can aim on its own
+            parent.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgb(0.7, 0.7, 0.8),
+                        custom_size: Some(Vec2::new(3.0, 10.0)),
+                        anchor: Anchor::BottomCenter,
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(0.0, 0.0, 1.0),
+                    ..default()
+                },
+                Turret { barrel_length: 10.0 },
+            ));
+            // Spawn the shield bubble around the hull, under it so the ship shows through
+            parent.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: SHIELD_BUBBLE_COLOR.with_a(SHIELD_BUBBLE_OPACITY),
+                        custom_size: Some(Vec2::splat(SHIELD_BUBBLE_SIZE)),
+                        ..default()
+                    },
+                    texture: ship_assets.shield_bubble.clone(),
+                    transform: Transform::from_xyz(0.0, 0.0, -0.05),
+                    ..default()
+                },
+                ShieldBubble::default(),
+            ));
+            // Spawn the thruster flames, hidden until their nozzle fires
+            for nozzle in Nozzle::ALL {
+                parent.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: NOZZLE_FLAME_COLOR,
+                            custom_size: Some(NOZZLE_FLAME_SIZE),
+                            anchor: Anchor::BottomCenter,
+                            ..default()
+                        },
+                        transform: nozzle.transform(),
+                        visibility: Visibility::Hidden,
+                        ..default()
+                    },
+                    nozzle,
+                ));
+            }
+        })
+        .id()
+}
+
+// Turn a ship by one step of a player's intent
+pub fn rotate_ship(transform: &mut Transform, starship: &Starship, player_input: &PlayerInput, delta_seconds: f32) {
+    // Rotate at up to rotation_speed, in the direction and amount requested
+    transform.rotation *= Quat::from_rotation_z(delta_seconds * starship.rotation_speed * player_input.rotate);
+}
+
+// Push a ship along by one step of a player's intent, burning fuel as it goes
+pub fn thrust_ship(
+    velocity: &mut Velocity,                                  // The ship's velocity
+    transform: &Transform,                                    // Which way the ship faces
+    engine: &mut Engine,                                      // The ship's engine and fuel
+    mass: f32,                                                // How heavy the ship is
+    player_input: &PlayerInput,                               // How hard thrust is requested
+    efficiency: &EfficiencyCurve,                             // How fuel burn grows with throttle
+    delta_seconds: f32,                                       // Length of the step
+) {
+    // Apply thrust when thrust is requested and there's fuel
+    if player_input.thrust != 0.0 && engine.fuel > 0.0 {
+        // Scale the engine's power by how hard thrust is requested, negative for reverse,
+        // and accelerate a heavier ship less
+        let thrust = engine.thrust * player_input.thrust / mass.max(f32::EPSILON);
+        // Get ship's rotation angle
+        let (_, _, z) = transform.rotation.to_euler(EulerRot::YXZ);
+        // Apply thrust in the direction the ship is facing
+        velocity.x -= thrust * delta_seconds * z.sin();
+        velocity.y += thrust * delta_seconds * z.cos();
+        // Consume fuel along the efficiency curve, whichever way the engine pushes
+        engine.fuel -= efficiency.fuel_rate(engine.thrust, player_input.thrust) * delta_seconds;
+        // Ensure fuel stays within valid range
+        engine.fuel = engine.fuel.clamp(0.0, engine.max_fuel);
+    }
+
+    // The side thrusters push square to the facing, leaving the heading alone
+    if player_input.strafe != 0.0 && engine.fuel > 0.0 {
+        let thrust = engine.strafe_thrust * player_input.strafe / mass.max(f32::EPSILON);
+        let facing = transform.up().truncate();
+        let right = Vec2::new(facing.y, -facing.x);
+        velocity.x += right.x * thrust * delta_seconds;
+        velocity.y += right.y * thrust * delta_seconds;
+        engine.fuel -= efficiency.fuel_rate(engine.strafe_thrust, player_input.strafe) * delta_seconds;
+        engine.fuel = engine.fuel.clamp(0.0, engine.max_fuel);
+    }
+}
+
+// Keep a ship from flying faster than its top speed
+pub fn limit_speed(velocity: &mut Velocity, starship: &Starship) {
+    let limited = velocity.as_vec2().clamp_length_max(starship.max_speed);
+    velocity.x = limited.x;
+    velocity.y = limited.y;
+}
+
+// Components of a ship that turns
+type RotationData<'a> = (&'a mut Transform, &'a mut RotationCharge, &'a Starship, &'a Player, Has<Disabled>);
+
+// System to handle ship rotation, spinning up the longer a turn is held
+fn rotate_ship_system(
+    time: Res<Time>,                                          // For frame-independent movement
+    mut query: Query<RotationData>,                           // Get ship position and properties
+    player_inputs: Res<PlayerInputs>,                         // This frame's intent of every player
+    rotation_ramp: Res<RotationRamp>,                         // How quickly a held turn spins up
+) {
+    for (mut transform, mut charge, starship, player, disabled) in query.iter_mut() {
+        let mut player_input = player_inputs[player.id];
+        player_input.rotate = charge.follow(player_input.rotate, &rotation_ramp, time.delta_seconds());
+        if disabled {
+            player_input.rotate *= DISABLED_ROTATION;
+        }
+        rotate_ship(&mut transform, starship, &player_input, time.delta_seconds());
+    }
+}
+
+// Components of a ship the engine moves
+type EngineData<'a> = (
+    &'a mut Velocity,
+    &'a Transform,
+    &'a mut Engine,
+    &'a mut EngineThrottle,
+    &'a Starship,
+    Option<&'a Mass>,
+    &'a Player,
+    Has<Disabled>,
+);
+
+// System to handle ship engine and thrust
+fn engine_system(
+    time: Res<Time>,                                          // For frame-independent movement
+    mut query: Query<EngineData>,                             // Get velocity, position, and engine
+    player_inputs: Res<PlayerInputs>,                         // This frame's intent of every player
+    efficiency: Res<EfficiencyCurve>,                         // How fuel burn grows with throttle
+    throttle_response: Res<ThrottleResponse>,                 // How quickly the engines answer
+) {
+    // Update each entity with Velocity, Transform, and Engine
+    for (mut velocity, transform, mut engine, mut throttle, starship, mass, player, disabled) in query.iter_mut() {
+        // A disabled engine ignores the throttle, but the top speed still holds
+        let mut player_input = player_inputs[player.id];
+        if disabled {
+            player_input.thrust = 0.0;
+            player_input.strafe = 0.0;
+        }
+        player_input.thrust = throttle.follow(player_input.thrust, &throttle_response, time.delta_seconds());
+        thrust_ship(
+            &mut velocity,
+            transform,
+            &mut engine,
+            mass.map_or(Mass::default().0, |mass| mass.0),
+            &player_input,
+            &efficiency,
+            time.delta_seconds(),
+        );
+        limit_speed(&mut velocity, starship);
+    }
+}
+
+// System to draw a plume out of each ship's engine, as long as what the engine is giving
+fn thrust_plume_system(
+    mut gizmos: Gizmos,                                       // For drawing the plumes
+    query: Query<(&Transform, &Engine, &EngineThrottle), With<Starship>>, // Get every ship's engine
+) {
+    for (transform, engine, throttle) in query.iter() {
+        if throttle.current == 0.0 || engine.fuel <= 0.0 {
+            continue;
+        }
+        // Out the back when thrusting forwards, out the nose in reverse, from the edge of the
+        // 32 pixel sprite
+        let facing = transform.up().truncate();
+        let direction = -facing * throttle.current.signum();
+        let start = transform.translation.truncate() + direction * 16.0 * SHIP_SCALE;
+        let end = start + direction * throttle.current.abs() * THRUST_PLUME_LENGTH;
+        gizmos.line_2d(start, end, Color::rgba(1.0, 0.6, 0.2, throttle.current.abs()));
+    }
+}
+
+// System to show each ship's shield as a bubble that flashes brighter when it soaks up a hit, and
+// fades out over a moment when the shield breaks instead of vanishing at once
+fn shield_bubble_system(
+    time: Res<Time>,                                          // For the flash and fade
+    mut shield_events: EventReader<ShieldHitEvent>,           // Hits the shields soaked up
+    shield_query: Query<&Shield>,                             // Get the ships' shields
+    mut bubble_query: Query<(&mut ShieldBubble, &Parent, &mut Sprite, &mut Visibility)>, // Get the bubbles
+) {
+    for event in shield_events.read() {
+        for (mut bubble, parent, _, _) in bubble_query.iter_mut() {
+            if parent.get() != event.target {
+                continue;
+            }
+            bubble.flash = SHIELD_FLASH_TIME;
+            if event.broken {
+                bubble.fade = SHIELD_FADE_TIME;
+            }
+        }
+    }
+
+    let delta_seconds = time.delta_seconds();
+    for (mut bubble, parent, mut sprite, mut visibility) in bubble_query.iter_mut() {
+        bubble.flash = (bubble.flash - delta_seconds).max(0.0);
+        bubble.fade = (bubble.fade - delta_seconds).max(0.0);
+        let charged = shield_query.get(parent.get()).is_ok_and(|shield| shield.current > 0.0);
+        let flash = SHIELD_FLASH_OPACITY * bubble.flash / SHIELD_FLASH_TIME;
+        let opacity = if charged {
+            SHIELD_BUBBLE_OPACITY + flash
+        } else {
+            (SHIELD_BUBBLE_OPACITY + flash) * bubble.fade / SHIELD_FADE_TIME
+        };
+        *visibility = if opacity > 0.0 { Visibility::Inherited } else { Visibility::Hidden };
+        sprite.color.set_a(opacity);
+    }
+}
+
+// Components of a ship whose nozzles fire
+type NozzleShipData<'a> = (&'a Engine, &'a EngineThrottle, &'a Player, Has<Disabled>);
+
+// System to show the flame of every nozzle firing, longer the harder it fires: the main engine
+// and the nose follow the throttle, the flanks the strafe asked for
+fn thruster_nozzle_system(
+    player_inputs: Res<PlayerInputs>,                         // This frame's intent of every player
+    ship_query: Query<NozzleShipData, With<Starship>>,        // Get the ships' engines
+    mut nozzle_query: Query<(&Nozzle, &Parent, &mut Visibility, &mut Sprite)>, // Get the nozzles
+) {
+    for (nozzle, parent, mut visibility, mut sprite) in nozzle_query.iter_mut() {
+        // A disabled or empty engine fires nothing, as in engine_system
+        let power = match ship_query.get(parent.get()) {
+            Ok((engine, throttle, player, false)) if engine.fuel > 0.0 => {
+                nozzle.power(throttle.current, player_inputs[player.id].strafe)
+            }
+            _ => 0.0,
+        };
+        visibility.set_if_neq(if power > 0.0 { Visibility::Inherited } else { Visibility::Hidden });
+        if power > 0.0 {
+            sprite.custom_size = Some(Vec2::new(NOZZLE_FLAME_SIZE.x, NOZZLE_FLAME_SIZE.y * power));
+        }
+    }
+}
+
+// System to destroy ships once their health runs out
+fn ship_destroy_system(
+    mut commands: Commands,                                   // For despawning ships
+    query: Query<(Entity, &Transform, &Collider, &Health, &Player), With<Starship>>, // Get every ship's health
+    mut lives: ResMut<Lives>,                                 // Lives left of every player
+    mut respawn: ResMut<Respawn>,                             // For bringing ships back
+    mut rng: ResMut<GameRng>,                                 // For the explosion debris
+    mut destroyed_events: EventWriter<ShipDestroyed>,         // For telling others about it
+) {
+    for (entity, transform, collider, health, player) in query.iter() {
+        if health.current <= 0.0 {
+            let position = transform.translation.truncate();
+            spawn_explosion(&mut commands, &mut rng, position, collider.radius);
+            destroyed_events.send(ShipDestroyed {
+                player: *player,
+                position,
+            });
+            // Take the turret and any other children down with the hull
+            commands.entity(entity).despawn_recursive();
+            let lives_left = &mut lives.0[player.id];
+            *lives_left = lives_left.saturating_sub(1);
+            info!("Player {} ship destroyed, {} lives left", player.id + 1, lives_left);
+            // A player out of lives sits out while the other carries on
+            if *lives_left > 0 {
+                respawn.pending[player.id] = true;
+                respawn.timers[player.id].reset();
+            }
+        }
+    }
+}
+
+// System to bring ships back after they were destroyed
+fn respawn_system(
+    mut commands: Commands,                                   // For spawning ships
+    time: Res<Time>,                                          // For the respawn delay
+    mut respawn: ResMut<Respawn>,                             // Respawn countdowns
+    ship_assets: Res<ShipAssets>,                             // The ship's sprite sheet
+    trail_settings: Res<TrailSettings>,                       // For the ship's trail length
+    loadout: Res<Loadout>,                                    // Starting values of the ships
+    player_count: Res<PlayerCount>,                           // For where ships come back
+) {
+    let respawn = &mut *respawn;
+    for id in player_count.ids() {
+        if respawn.pending[id] && respawn.timers[id].tick(time.delta()).finished() {
+            respawn.pending[id] = false;
+            let position = spawn_point(id, &player_count);
+            spawn_ship(&mut commands, &ship_assets, &trail_settings, &loadout, Player { id }, position);
+        }
+    }
+}
+
+// System to bring the engine of disabled ships back once their timer runs out
+fn disabled_system(
+    mut commands: Commands,                                   // For removing the effect
+    time: Res<Time>,                                          // For the timer
+    mut query: Query<(Entity, &mut Disabled)>,                // Get the disabled ships
+) {
+    for (entity, mut disabled) in query.iter_mut() {
+        if disabled.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Disabled>();
+        }
+    }
+}
+
+// System to tint disabled ships blue, fading as the engine comes back, and give recovered
+// ships their own color again; a hit flash takes over the tint while it lasts
+fn disabled_tint_system(
+    color_scheme: Res<ColorScheme>,                           // Colors of the ships
+    mut removed: RemovedComponents<Disabled>,                 // Ships whose engine came back
+    mut query: Query<(&mut TextureAtlasSprite, &Player, Option<&Disabled>), Without<DamageFlash>>, // Get the ships to tint
+) {
+    for entity in removed.read() {
+        if let Ok((mut sprite, player, None)) = query.get_mut(entity) {
+            sprite.color = color_scheme.ship(*player);
+        }
+    }
+    for (mut sprite, player, disabled) in query.iter_mut() {
+        let Some(disabled) = disabled else {
+            continue;
+        };
+        let strength = disabled.timer.percent_left();
+        let from = color_scheme.disabled.as_rgba_f32();
+        let to = color_scheme.ship(*player).as_rgba_f32();
+        sprite.color = Color::rgba(
+            to[0] + (from[0] - to[0]) * strength,
+            to[1] + (from[1] - to[1]) * strength,
+            to[2] + (from[2] - to[2]) * strength,
+            to[3] + (from[3] - to[3]) * strength,
+        );
+    }
+}
+
+// System keeping every ship fuelled and out of harm's way, for testing with `--god-mode`
+fn god_mode_system(
+    mut commands: Commands,                                   // For making ships invulnerable
+    mut ship_query: Query<(Entity, &mut Engine, Has<Invulnerable>), With<Starship>>, // Get the ships
+) {
+    for (entity, mut engine, invulnerable) in ship_query.iter_mut() {
+        engine.fuel = engine.max_fuel;
+        if !invulnerable {
+            commands.entity(entity).insert(Invulnerable);
+        }
+    }
+}
+
+// System to pick up the loadout of a newly selected ship class for the next ships spawned
+fn ship_class_system(
+    ship_class: Res<ShipClass>,                               // The selected class
+    config: Res<GameConfig>,                                  // Starting values of every class
+    mut loadout: ResMut<Loadout>,                             // The loadout to replace
+) {
+    if ship_class.is_changed() && !ship_class.is_added() {
+        *loadout = config.ships.loadout(*ship_class);
+    }
+}
+
+// Plugin for the player's ship
+pub struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Starship>()
+            .register_type::<Player>()
+            .register_type::<PlayerCount>()
+            .register_type::<Engine>()
+            .register_type::<Disabled>()
+            .register_type::<Nozzle>()
+            .register_type::<EfficiencyCurve>()
+            .register_type::<EngineThrottle>()
+            .register_type::<RotationCharge>()
+            .register_type::<ShieldBubble>()
+            .register_type::<RotationRamp>()
+            .register_type::<ThrottleResponse>()
+            .register_type::<Loadout>()
+            .register_type::<ShipClass>()
+            .init_resource::<PlayerCount>()
+            .init_resource::<ShipClass>()
+            .init_resource::<Loadout>()
+            .init_resource::<Respawn>()
+            .init_resource::<EfficiencyCurve>()
+            .init_resource::<ThrottleResponse>()
+            .init_resource::<RotationRamp>()
+            .add_event::<ShipDestroyed>()
+            .add_systems(Startup, setup_ship)
+            .add_systems(Update, (
+                ship_class_system,
+                disabled_tint_system.run_if(in_state(GameState::Playing)),
+                thrust_plume_system.after(InterpolationSet).run_if(in_state(GameState::Playing)),
+                thruster_nozzle_system.run_if(in_state(GameState::Playing)),
+                shield_bubble_system.run_if(in_state(GameState::Playing)),
+            ))
+            // Steering, fuel burn and respawn countdowns advance with the simulation
+            .add_systems(FixedUpdate, (
+                (rotate_ship_system, engine_system).after(PlayerInputSet).in_set(GameSet::Input),
+                god_mode_system.after(engine_system).in_set(GameSet::Input).run_if(god_mode),
+                disabled_system.after(engine_system).in_set(GameSet::Input),
+                (ship_destroy_system, respawn_system).in_set(GameSet::Cleanup),
+            ).run_if(in_state(GameState::Playing)));
+    }
+}
@@ -0,0 +1,174 @@
+// Reusing the entities of shots and particles instead of spawning and despawning hundreds a second
+use bevy::ecs::system::Command;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::combat::{DamageFlash, Invulnerable, LastHitBy};
+use crate::physics::{Lifetime, PreviousTransform, Velocity};
+use crate::session::NewGameEvent;
+use crate::trail::Trail;
+
+// Most entities of one kind kept waiting in the pool; past this they are despawned as usual
+const MAX_POOLED: usize = 4096;
+
+// The kinds of entity kept in the pool, each reused only for its own kind
+#[derive(Reflect, Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PoolKind {
+    #[default]
+    Projectile,  // A ship's shot
+    Particle,    // A piece of debris or a spark
+}
+
+// Define the Pooled component for shots and particles that go back in the pool once they are done
+// instead of being despawned. A free one keeps its components, hidden and at rest, so taking it out
+// of the pool and putting it back never moves it between archetypes; everything acting on shots or
+// particles skips the free ones
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Pooled {
+    pub kind: PoolKind,  // Which part of the pool it goes back in
+    pub free: bool,      // Whether it is waiting in the pool
+}
+
+impl Pooled {
+    // Whether the entity is out of the pool and in play
+    pub fn in_use(&self) -> bool {
+        !self.free
+    }
+}
+
+// What gameplay may add to a shot or particle besides what it is spawned with, taken off as it goes
+// back in the pool so none of it carries over to the next use
+type PoolStripped = (Invulnerable, DamageFlash, LastHitBy, Lifetime);
+
+// Resource holding the entities waiting to be reused, by kind
+#[derive(Resource, Default)]
+pub struct Pool {
+    free: HashMap<PoolKind, Vec<Entity>>,  // Entities in the pool, the most recently used last
+    pub reused: u64,                       // Spawns served from the pool so far
+    pub spawned: u64,                      // Spawns that had to build a new entity so far
+}
+
+impl Pool {
+    // How many entities of a kind are waiting
+    pub fn len(&self, kind: PoolKind) -> usize {
+        self.free.get(&kind).map_or(0, Vec::len)
+    }
+}
+
+// Command spawning an entity from its bundle, in an entity taken from the pool when there is one
+struct SpawnPooled<B: Bundle> {
+    kind: PoolKind,  // Which part of the pool to take from
+    bundle: B,       // Everything the entity is spawned with
+}
+
+impl<B: Bundle> Command for SpawnPooled<B> {
+    fn apply(self, world: &mut World) {
+        let pooled = Pooled {
+            kind: self.kind,
+            free: false,
+        };
+        if !world.contains_resource::<Pool>() {
+            world.spawn((self.bundle, pooled));
+            return;
+        }
+        // Entities cleared away with a run while they waited are skipped over
+        let mut reused = None;
+        while let Some(entity) = world.resource_mut::<Pool>().free.get_mut(&self.kind).and_then(Vec::pop) {
+            let free = world.get::<Pooled>(entity).is_some_and(|pooled| pooled.free);
+            if free {
+                reused = Some(entity);
+                break;
+            }
+        }
+        let Some(entity) = reused else {
+            world.spawn((self.bundle, pooled));
+            world.resource_mut::<Pool>().spawned += 1;
+            return;
+        };
+        // The bundle overwrites the transform, sprite, visibility, velocity and timers of the last
+        // use; the drawn position starts over from the new transform instead of sweeping across
+        let mut entity = world.entity_mut(entity);
+        entity.insert((self.bundle, pooled));
+        let transform = entity.get::<Transform>().copied();
+        if let (Some(transform), Some(mut interpolation)) = (transform, entity.get_mut::<PreviousTransform>()) {
+            *interpolation = PreviousTransform {
+                previous: transform,
+                simulated: transform,
+                blended: false,
+            };
+        }
+        world.resource_mut::<Pool>().reused += 1;
+    }
+}
+
+// Command hiding an entity that is done and putting it in the pool, or despawning it when full
+struct ReleaseToPool {
+    entity: Entity,  // The entity that is done
+}
+
+impl Command for ReleaseToPool {
+    fn apply(self, world: &mut World) {
+        // Something already despawned or pooled it, like a shot used up on the tick it expired
+        let Some(entity) = world.get_entity(self.entity) else {
+            return;
+        };
+        let Some(&Pooled { kind, free }) = entity.get::<Pooled>() else {
+            // Not spawned through the pool, so it goes the usual way
+            world.entity_mut(self.entity).despawn();
+            return;
+        };
+        if free {
+            return;
+        }
+        let full = world.get_resource::<Pool>().is_none_or(|pool| pool.len(kind) >= MAX_POOLED);
+        if full {
+            world.entity_mut(self.entity).despawn();
+            return;
+        }
+        let mut entity = world.entity_mut(self.entity);
+        entity.remove::<PoolStripped>().insert(Visibility::Hidden);
+        if let Some(mut velocity) = entity.get_mut::<Velocity>() {
+            *velocity = Velocity::default();
+        }
+        if let Some(mut trail) = entity.get_mut::<Trail>() {
+            trail.points.clear();
+        }
+        if let Some(mut pooled) = entity.get_mut::<Pooled>() {
+            pooled.free = true;
+        }
+        world.resource_mut::<Pool>().free.entry(kind).or_default().push(self.entity);
+    }
+}
+
+// Spawn a shot or particle, reusing one from the pool when there is one
+pub fn spawn_pooled(commands: &mut Commands, kind: PoolKind, bundle: impl Bundle) {
+    commands.add(SpawnPooled { kind, bundle });
+}
+
+// Put a shot or particle that is done back in the pool, in place of despawning it
+pub fn release_pooled(commands: &mut Commands, entity: Entity) {
+    commands.add(ReleaseToPool { entity });
+}
+
+// System to let go of the pool with the run it belonged to; the new run clears its entities away
+fn pool_reset_system(
+    mut new_game_events: EventReader<NewGameEvent>,           // Requests for a new run
+    mut pool: ResMut<Pool>,                                   // The pool to empty
+) {
+    if new_game_events.read().count() > 0 {
+        pool.free.clear();
+    }
+}
+
+// Plugin for reusing shots and particles
+pub struct PoolPlugin;
+
+impl Plugin for PoolPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Pooled>()
+            .register_type::<PoolKind>()
+            .init_resource::<Pool>()
+            .add_systems(Update, pool_reset_system);
+    }
+}
@@ -0,0 +1,342 @@
+// Pickups dropped by destroyed hostiles and their effects on the ship
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{Health, Shield};
+use crate::hostiles::{HostileDestroySet, HostileDestroyed};
+use crate::inventory::{Inventory, Item};
+use crate::physics::{Collider, Lifetime, Velocity};
+use crate::player::{Engine, Player, Starship};
+use crate::session::{GameRng, GameState, GameplayEntity, Lives};
+use crate::weapons::{Weapon, WeaponType};
+use crate::GameSet;
+
+// The effects a power-up can have when collected
+#[derive(Reflect, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PowerUpKind {
+    #[default]
+    Health,         // Repairs part of the hull
+    WeaponUpgrade,  // Swaps the weapon for the boosted one
+    BeamWeapon,     // Swaps the weapon for the beam
+    Shield,         // Recharges the shield completely
+    ExtraLife,      // Grants one more life
+    Fuel,           // Refills part of the fuel tank
+    RepairKit,      // Adds a repair kit to the inventory
+    FuelCell,       // Adds a fuel cell to the inventory
+    ShieldCell,     // Adds a shield cell to the inventory
+    Magnet,         // Pulls nearby pickups in for a while
+}
+
+impl PowerUpKind {
+    // Every kind, for picking one at random
+    pub const ALL: [PowerUpKind; 10] = [
+        PowerUpKind::Health,
+        PowerUpKind::WeaponUpgrade,
+        PowerUpKind::BeamWeapon,
+        PowerUpKind::Shield,
+        PowerUpKind::ExtraLife,
+        PowerUpKind::Fuel,
+        PowerUpKind::RepairKit,
+        PowerUpKind::FuelCell,
+        PowerUpKind::ShieldCell,
+        PowerUpKind::Magnet,
+    ];
+
+    // How often the power-up drops compared to the others; hull repairs come in at half the rate
+    // of fuel, and extra lives rarest of all
+    pub fn drop_weight(self) -> u32 {
+        match self {
+            PowerUpKind::Fuel => 4,
+            PowerUpKind::WeaponUpgrade | PowerUpKind::Shield => 3,
+            PowerUpKind::ExtraLife => 1,
+            _ => 2,
+        }
+    }
+
+    // The kind a roll in 0..the sum of every drop weight lands on
+    pub fn from_weighted_roll(mut roll: u32) -> PowerUpKind {
+        for kind in PowerUpKind::ALL {
+            if roll < kind.drop_weight() {
+                return kind;
+            }
+            roll -= kind.drop_weight();
+        }
+        PowerUpKind::default()
+    }
+
+    // Color the power-up is drawn with
+    pub fn color(self) -> Color {
+        match self {
+            PowerUpKind::Health => Color::rgb(0.2, 0.9, 0.3),
+            PowerUpKind::WeaponUpgrade => Color::rgb(1.0, 0.5, 0.1),
+            PowerUpKind::BeamWeapon => Color::rgb(0.6, 0.4, 1.0),
+            PowerUpKind::Shield => Color::rgb(0.2, 0.8, 1.0),
+            PowerUpKind::ExtraLife => Color::rgb(1.0, 0.3, 0.9),
+            PowerUpKind::Fuel => Color::rgb(0.9, 0.9, 0.2),
+            PowerUpKind::RepairKit => Item::RepairKit.color(),
+            PowerUpKind::FuelCell => Item::FuelCell.color(),
+            PowerUpKind::ShieldCell => Item::ShieldCell.color(),
+            PowerUpKind::Magnet => Color::rgb(0.8, 0.8, 0.85),
+        }
+    }
+
+    // The item collecting the power-up stores in the inventory, if it isn't used straight away
+    pub fn item(self) -> Option<Item> {
+        match self {
+            PowerUpKind::RepairKit => Some(Item::RepairKit),
+            PowerUpKind::FuelCell => Some(Item::FuelCell),
+            PowerUpKind::ShieldCell => Some(Item::ShieldCell),
+            _ => None,
+        }
+    }
+}
+
+// Define the PowerUp component for pickups dropped by destroyed hostiles
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct PowerUp {
+    pub kind: PowerUpKind,  // What collecting it does
+}
+
+// Define the Magnet component for ships pulling nearby pickups towards them
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Magnet {
+    pub radius: f32,    // Distance from the ship within which pickups are pulled
+    pub strength: f32,  // Acceleration of a pulled pickup towards the ship
+    pub timer: Timer,   // Time until the magnet wears off
+}
+
+// Resource configuring power-up drops
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct PowerUpSettings {
+    pub drop_chance: f32,     // Chance in 0..1 that a destroyed hostile drops one
+    pub lifetime: f32,        // Seconds before an uncollected power-up disappears
+    pub drift_speed: f32,     // Top speed of the slow drift
+    pub health_restore: f32,  // Health repaired by a Health power-up
+    pub fuel_restore: f32,    // Fuel added by a Fuel power-up
+    pub magnet_radius: f32,   // Reach of a Magnet power-up
+    pub magnet_strength: f32, // Pull of a Magnet power-up
+    pub magnet_duration: f32, // Seconds a Magnet power-up lasts
+}
+
+impl Default for PowerUpSettings {
+    fn default() -> Self {
+        Self {
+            drop_chance: 0.2,
+            lifetime: 10.0,
+            drift_speed: 20.0,
+            health_restore: 25.0,
+            fuel_restore: 250.0,
+            magnet_radius: 200.0,
+            magnet_strength: 600.0,
+            magnet_duration: 8.0,
+        }
+    }
+}
+
+// System to sometimes leave a power-up where a hostile was destroyed, every kind picked by its
+// drop weight
+fn powerup_drop_system(
+    mut commands: Commands,                                   // For spawning power-ups
+    mut destroyed_events: EventReader<HostileDestroyed>,      // Destroyed hostiles
+    mut rng: ResMut<GameRng>,                                 // For the drop roll and kind
+    powerup_settings: Res<PowerUpSettings>,                   // Drop chance and drift
+) {
+    let total_weight: u32 = PowerUpKind::ALL.iter().map(|kind| kind.drop_weight()).sum();
+    for event in destroyed_events.read() {
+        if rng.gen::<f32>() >= powerup_settings.drop_chance {
+            continue;
+        }
+        let kind = PowerUpKind::from_weighted_roll(rng.gen_range(0..total_weight));
+        let drift = Vec2::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0))
+            .clamp_length_max(1.0)
+            * powerup_settings.drift_speed;
+        spawn_power_up(&mut commands, kind, event.position, drift, powerup_settings.lifetime);
+    }
+}
+
+// Spawn a power-up that drifts slowly until collected or expired
+pub fn spawn_power_up(
+    commands: &mut Commands,                                  // For spawning the power-up
+    kind: PowerUpKind,                                        // What collecting it does
+    position: Vec2,                                           // Where it appears
+    drift: Vec2,                                              // Its slow drift velocity
+    lifetime: f32,                                            // Seconds before it disappears
+) -> Entity {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: kind.color(),
+                    custom_size: Some(Vec2::splat(12.0)),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(0.0)),
+                ..default()
+            },
+            GameplayEntity,
+            PowerUp { kind },
+            Velocity {
+                x: drift.x,
+                y: drift.y,
+            },
+            Collider { radius: 8.0 },
+            Lifetime(Timer::from_seconds(lifetime, TimerMode::Once)),
+        ))
+        .id()
+}
+
+// Apply the effect of a collected power-up to the ship
+fn apply_power_up(
+    kind: PowerUpKind,                                        // What was collected
+    powerup_settings: &PowerUpSettings,                       // How strong the effects are
+    health: &mut Health,                                      // The ship's health
+    shield: Option<&mut Shield>,                              // The ship's shield, if any
+    weapon: Option<&mut Weapon>,                              // The ship's weapon, if any
+    engine: Option<&mut Engine>,                              // The ship's engine, if any
+    lives: &mut u32,                                          // Lives left of the ship's player
+) {
+    match kind {
+        PowerUpKind::Health => {
+            health.current = (health.current + powerup_settings.health_restore).min(health.max);
+        }
+        PowerUpKind::WeaponUpgrade => {
+            if let Some(weapon) = weapon {
+                weapon.weapon_type = WeaponType::Boosted;
+            }
+        }
+        PowerUpKind::BeamWeapon => {
+            if let Some(weapon) = weapon {
+                weapon.weapon_type = WeaponType::Beam;
+            }
+        }
+        PowerUpKind::Shield => {
+            if let Some(shield) = shield {
+                shield.current = shield.max;
+            }
+        }
+        PowerUpKind::ExtraLife => *lives += 1,
+        PowerUpKind::Fuel => {
+            if let Some(engine) = engine {
+                engine.fuel = (engine.fuel + powerup_settings.fuel_restore).min(engine.max_fuel);
+            }
+        }
+        // Items are stored and magnets fitted by the collect system instead
+        PowerUpKind::RepairKit | PowerUpKind::FuelCell | PowerUpKind::ShieldCell | PowerUpKind::Magnet => {}
+    }
+}
+
+// Components of the ship a power-up can affect
+type PowerUpTargetData<'a> = (
+    Entity,
+    &'a Transform,
+    &'a Collider,
+    &'a mut Health,
+    Option<&'a mut Shield>,
+    Option<&'a mut Weapon>,
+    Option<&'a mut Engine>,
+    &'a Player,
+);
+
+// System to collect power-ups the ship flies into
+fn powerup_collect_system(
+    mut commands: Commands,                                   // For despawning power-ups
+    mut ship_query: Query<PowerUpTargetData, With<Starship>>, // Get what power-ups affect
+    powerup_query: Query<(Entity, &Transform, &Collider, &PowerUp)>, // Get every power-up
+    powerup_settings: Res<PowerUpSettings>,                   // How strong the effects are
+    mut lives: ResMut<Lives>,                                 // Lives left of every player
+    mut inventory: ResMut<Inventory>,                         // Where collected items go
+) {
+    for (ship, ship_transform, ship_collider, mut health, mut shield, mut weapon, mut engine, player) in ship_query.iter_mut() {
+        let ship_position = ship_transform.translation.truncate();
+        for (entity, transform, collider, powerup) in powerup_query.iter() {
+            if ship_position.distance(transform.translation.truncate()) > ship_collider.radius + collider.radius {
+                continue;
+            }
+            // A hull repair is left floating for later rather than wasted on a ship that needs none
+            if powerup.kind == PowerUpKind::Health && health.current >= health.max {
+                continue;
+            }
+            commands.entity(entity).despawn();
+            if let Some(item) = powerup.kind.item() {
+                inventory.add(item);
+                continue;
+            }
+            // Another magnet starts the time over rather than adding to it
+            if powerup.kind == PowerUpKind::Magnet {
+                commands.entity(ship).insert(Magnet {
+                    radius: powerup_settings.magnet_radius,
+                    strength: powerup_settings.magnet_strength,
+                    timer: Timer::from_seconds(powerup_settings.magnet_duration, TimerMode::Once),
+                });
+                continue;
+            }
+            apply_power_up(
+                powerup.kind,
+                &powerup_settings,
+                &mut health,
+                shield.as_deref_mut(),
+                weapon.as_deref_mut(),
+                engine.as_deref_mut(),
+                &mut lives.0[player.id],
+            );
+        }
+    }
+}
+
+// System to pull the pickups within reach of a ship's magnet towards it, and take the magnet away
+// once it wears off. A pickup never closes in faster than it would cover the distance left in one
+// tick, so it lands on the ship instead of being flung through and out the other side
+fn magnet_system(
+    mut commands: Commands,                                   // For removing worn off magnets
+    time: Res<Time>,                                          // For the pull and the timer
+    mut ship_query: Query<(Entity, &Transform, &Velocity, &mut Magnet), Without<PowerUp>>, // Get the ships with a magnet
+    mut powerup_query: Query<(&Transform, &mut Velocity), With<PowerUp>>, // Get every pickup
+) {
+    let delta_seconds = time.delta_seconds();
+    for (ship, ship_transform, ship_velocity, mut magnet) in ship_query.iter_mut() {
+        if magnet.timer.tick(time.delta()).finished() {
+            commands.entity(ship).remove::<Magnet>();
+            continue;
+        }
+        let ship_position = ship_transform.translation.truncate();
+        for (transform, mut velocity) in powerup_query.iter_mut() {
+            let offset = ship_position - transform.translation.truncate();
+            let distance = offset.length();
+            if distance > magnet.radius || distance <= f32::EPSILON {
+                continue;
+            }
+            let direction = offset / distance;
+            let mut pulled = velocity.as_vec2() + direction * magnet.strength * delta_seconds;
+            // Measured against the ship, which may be flying away from or into the pickup
+            let closing = (pulled - ship_velocity.as_vec2()).dot(direction);
+            let max_closing = distance / delta_seconds.max(f32::EPSILON);
+            if closing > max_closing {
+                pulled -= direction * (closing - max_closing);
+            }
+            velocity.x = pulled.x;
+            velocity.y = pulled.y;
+        }
+    }
+}
+
+// Plugin for power-up drops and pickups
+pub struct PowerUpsPlugin;
+
+impl Plugin for PowerUpsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PowerUp>()
+            .register_type::<Magnet>()
+            .register_type::<PowerUpSettings>()
+            .init_resource::<PowerUpSettings>()
+            .add_systems(FixedUpdate, (
+                // Pulled in before everything moves, like a steered ship
+                magnet_system.in_set(GameSet::Input),
+                powerup_collect_system.in_set(GameSet::Collision),
+                powerup_drop_system.after(HostileDestroySet).in_set(GameSet::Cleanup),
+            ).run_if(in_state(GameState::Playing)));
+    }
+}
@@ -0,0 +1,166 @@
+// The practice range: a run with no asteroids or closing zone, just a row of target dummies that
+// never die and show the damage every hit deals, for trying out weapons and upgrades
+use bevy::prelude::*;
+
+use crate::combat::{DamageTakenEvent, Health};
+use crate::hostiles::{Hostile, HostileDestroySet};
+use crate::palette::Tint;
+use crate::physics::Collider;
+use crate::session::{GameMode, GameState, GameplayEntity};
+use crate::GameSet;
+
+// Health of a target dummy, refilled whenever it is shot down to nothing
+const DUMMY_HEALTH: f32 = 1000.0;
+
+// Radius of a target dummy
+const DUMMY_RADIUS: f32 = 20.0;
+
+// Where the dummies stand, in a row ahead of the ships
+const DUMMY_POSITIONS: [Vec2; 3] = [Vec2::new(-160.0, 200.0), Vec2::new(0.0, 200.0), Vec2::new(160.0, 200.0)];
+
+// Seconds a damage number floats before it is gone
+const DAMAGE_TEXT_LIFETIME: f32 = 0.8;
+
+// How fast a damage number rises, in pixels/second
+const DAMAGE_TEXT_RISE_SPEED: f32 = 40.0;
+
+// Define the TargetDummy component for the stationary targets of the practice range
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct TargetDummy;
+
+// Define the DamageText component for a number rising and fading above a dummy that was hit
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct DamageText {
+    pub amount: f32,   // Damage the hit dealt
+    pub timer: Timer,  // Time until the number is gone
+}
+
+// Spawn a target dummy: a hostile that stays put, never runs out of health and scores nothing
+pub fn spawn_target_dummy(commands: &mut Commands, position: Vec2) -> Entity {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(DUMMY_RADIUS * 2.0)),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(0.0)),
+                ..default()
+            },
+            Tint::Enemy,
+            GameplayEntity,
+            TargetDummy,
+            Hostile,
+            Collider { radius: DUMMY_RADIUS },
+            Health {
+                current: DUMMY_HEALTH,
+                max: DUMMY_HEALTH,
+            },
+        ))
+        .id()
+}
+
+// Spawn a damage number above where a hit landed
+pub fn spawn_damage_text(commands: &mut Commands, position: Vec2, amount: f32) -> Entity {
+    commands
+        .spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    format!("{:.0}", amount),
+                    TextStyle {
+                        font_size: 18.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_translation(position.extend(5.0)),
+                ..default()
+            },
+            GameplayEntity,
+            DamageText {
+                amount,
+                timer: Timer::from_seconds(DAMAGE_TEXT_LIFETIME, TimerMode::Once),
+            },
+        ))
+        .id()
+}
+
+// System to set up the dummies whenever the range has none, on arriving and after every new run
+// cleared them away; they never die, so they are only ever missing all together
+fn dummy_spawn_system(
+    mut commands: Commands,                                   // For spawning the dummies
+    dummy_query: Query<(), With<TargetDummy>>,                // Get the dummies already up
+) {
+    if !dummy_query.is_empty() {
+        return;
+    }
+    for position in DUMMY_POSITIONS {
+        spawn_target_dummy(&mut commands, position);
+    }
+}
+
+// System to fill a dummy shot down to nothing back up before it would be destroyed
+fn dummy_refill_system(mut query: Query<&mut Health, With<TargetDummy>>) {
+    for mut health in query.iter_mut() {
+        if health.current <= 0.0 {
+            health.current = health.max;
+        }
+    }
+}
+
+// System to float a number above a dummy for every hit it takes, showing the health the hit took
+// off rather than what it asked for, so a bomb doesn't show an absurd number
+fn damage_text_spawn_system(
+    mut commands: Commands,                                   // For spawning the numbers
+    mut taken_events: EventReader<DamageTakenEvent>,          // Health lost this frame
+    dummy_query: Query<(&Transform, &Collider), With<TargetDummy>>, // Get the dummies that were hit
+) {
+    for event in taken_events.read() {
+        let Ok((transform, collider)) = dummy_query.get(event.target) else {
+            continue;
+        };
+        let position = transform.translation.truncate() + Vec2::Y * collider.radius;
+        spawn_damage_text(&mut commands, position, event.amount);
+    }
+}
+
+// System to rise and fade the damage numbers, removing them once they are gone
+fn damage_text_system(
+    mut commands: Commands,                                   // For removing faded numbers
+    time: Res<Time>,                                          // For frame-independent movement
+    mut query: Query<(Entity, &mut DamageText, &mut Transform, &mut Text)>, // Get every number
+) {
+    for (entity, mut damage_text, mut transform, mut text) in query.iter_mut() {
+        if damage_text.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation.y += DAMAGE_TEXT_RISE_SPEED * time.delta_seconds();
+        let opacity = 1.0 - damage_text.timer.percent();
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(opacity);
+        }
+    }
+}
+
+// Plugin for the practice range
+pub struct PracticePlugin;
+
+impl Plugin for PracticePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TargetDummy>()
+            .register_type::<DamageText>()
+            .add_systems(Update, (
+                dummy_spawn_system,
+                damage_text_spawn_system,
+                damage_text_system,
+            ).run_if(in_state(GameMode::Practice)).run_if(in_state(GameState::Playing)))
+            .add_systems(FixedUpdate, dummy_refill_system
+                .before(HostileDestroySet)
+                .in_set(GameSet::Cleanup)
+                .run_if(in_state(GameMode::Practice))
+                .run_if(in_state(GameState::Playing)));
+    }
+}
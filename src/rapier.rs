@@ -0,0 +1,137 @@
+// The rapier backend, built with the `rapier` feature: ships and hostiles become rigid bodies that
+// bevy_rapier2d moves, so hostiles knock each other about, spin and bounce off one another. The
+// gameplay keeps working on Velocity, Collider and Mass as in the default build; they are handed
+// to the bodies before every step and read back after it
+use bevy::prelude::*;
+use bevy_rapier2d::plugin::systems::sync_removals;
+use bevy_rapier2d::prelude as rapier;
+use bevy_rapier2d::prelude::{NoUserData, PhysicsSet, RapierConfiguration, RapierPhysicsPlugin, TimestepMode};
+
+use crate::hostiles::Hostile;
+use crate::physics::{Collider, Mass, Velocity, WorldBounds, wrap_coordinate};
+use crate::player::Starship;
+use crate::session::GameState;
+use crate::GameSet;
+
+// Pixels to a meter of rapier's world, keeping its tolerances sensible at the game's scale
+const PIXELS_PER_METER: f32 = 100.0;
+
+// How bouncy hostiles are against one another, from 0.0 (no bounce) to 1.0 (perfectly elastic)
+const HOSTILE_RESTITUTION: f32 = 0.8;
+
+// How much hostiles grip one another as they scrape past, setting each other spinning
+const HOSTILE_FRICTION: f32 = 0.4;
+
+// Group of the ships' bodies, which rapier collides with nothing; running into hostiles stays
+// with the collision systems, which deal the damage and the bounce
+const SHIP_GROUP: rapier::Group = rapier::Group::GROUP_1;
+
+// Group of the hostiles' bodies, colliding with one another
+const HOSTILE_GROUP: rapier::Group = rapier::Group::GROUP_2;
+
+// Components of a new ship or hostile its body is made from
+type NewBodyData<'a> = (Entity, &'a Collider, &'a Velocity, Option<&'a Mass>, Has<Starship>);
+
+// Filter for new ships and hostiles without a body yet
+type NewBodyFilter = (Or<(With<Starship>, With<Hostile>)>, Without<rapier::RigidBody>);
+
+// System to give every new ship and hostile a rigid body matching its hit circle and mass
+fn attach_body_system(
+    mut commands: Commands,                                   // For adding the bodies
+    query: Query<NewBodyData, NewBodyFilter>,                 // Get the new bodies
+) {
+    for (entity, collider, velocity, mass, ship) in query.iter() {
+        let mass = mass.map_or(Mass::default().0, |mass| mass.0);
+        let mut body = commands.entity(entity);
+        body.insert((
+            rapier::RigidBody::Dynamic,
+            rapier::Collider::ball(collider.radius),
+            rapier::ColliderMassProperties::Mass(mass),
+            rapier::Velocity::linear(velocity.as_vec2()),
+            rapier::GravityScale(0.0),
+        ));
+        if ship {
+            // Steering turns the ship, not whatever it scrapes against
+            body.insert((
+                rapier::LockedAxes::ROTATION_LOCKED,
+                rapier::CollisionGroups::new(SHIP_GROUP, rapier::Group::NONE),
+            ));
+        } else {
+            body.insert((
+                rapier::Restitution::coefficient(HOSTILE_RESTITUTION),
+                rapier::Friction::coefficient(HOSTILE_FRICTION),
+                rapier::CollisionGroups::new(HOSTILE_GROUP, HOSTILE_GROUP),
+            ));
+        }
+    }
+}
+
+// System to hand the bodies the velocities the gameplay set since the last step: thrust, the
+// speed limit, bounces off ships and everything else acting on Velocity
+fn push_velocity_system(mut query: Query<(&Velocity, &mut rapier::Velocity)>) {
+    for (velocity, mut body_velocity) in query.iter_mut() {
+        if body_velocity.linvel != velocity.as_vec2() {
+            body_velocity.linvel = velocity.as_vec2();
+        }
+    }
+}
+
+// System to read back where the step moved the bodies, wrapping them around the arena; a body
+// moved over an edge is teleported there on the next step
+fn pull_velocity_system(
+    arena: Res<WorldBounds>,                                  // Size of the play area
+    mut query: Query<(&mut Transform, &mut Velocity, &rapier::Velocity)>, // Get every body
+) {
+    let half_extents = arena.half_extents();
+    for (mut transform, mut velocity, body_velocity) in query.iter_mut() {
+        velocity.x = body_velocity.linvel.x;
+        velocity.y = body_velocity.linvel.y;
+        let x = wrap_coordinate(transform.translation.x, half_extents.x);
+        let y = wrap_coordinate(transform.translation.y, half_extents.y);
+        if x != transform.translation.x || y != transform.translation.y {
+            transform.translation.x = x;
+            transform.translation.y = y;
+        }
+    }
+}
+
+// Plugin for the rapier backend, added by PhysicsPlugin in place of its own movement
+pub struct RapierBackendPlugin;
+
+impl Plugin for RapierBackendPlugin {
+    fn build(&self, app: &mut App) {
+        let plugin = RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(PIXELS_PER_METER)
+            .in_fixed_schedule()
+            .with_default_system_setup(false);
+        // Space has no gravity, and every step lasts one fixed tick of the game clock
+        app.insert_resource(RapierConfiguration {
+                gravity: Vec2::ZERO,
+                timestep_mode: TimestepMode::Variable {
+                    max_dt: f32::MAX,
+                    time_scale: 1.0,
+                    substeps: 1,
+                },
+                ..default()
+            })
+            .add_plugins(plugin)
+            // Stepped where the default build moves things, so the collision checks see the result
+            .configure_sets(FixedUpdate, (
+                PhysicsSet::SyncBackend,
+                PhysicsSet::StepSimulation,
+                PhysicsSet::Writeback,
+            ).chain().in_set(GameSet::Physics).run_if(in_state(GameState::Playing)))
+            .add_systems(FixedUpdate, (
+                RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::SyncBackend).in_set(PhysicsSet::SyncBackend),
+                RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::StepSimulation).in_set(PhysicsSet::StepSimulation),
+                RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::Writeback).in_set(PhysicsSet::Writeback),
+            ))
+            .add_systems(FixedUpdate, (
+                (attach_body_system, apply_deferred, push_velocity_system)
+                    .chain()
+                    .before(PhysicsSet::SyncBackend),
+                pull_velocity_system.after(PhysicsSet::Writeback),
+            ).in_set(GameSet::Physics).run_if(in_state(GameState::Playing)))
+            // Bodies of despawned entities are let go of whatever the state
+            .add_systems(PostUpdate, sync_removals);
+    }
+}
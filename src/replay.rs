@@ -0,0 +1,511 @@
+// Recording player input to a file and playing it back, live or as a ghost ship
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::Path;
+
+use crate::cli::CliArgs;
+use crate::hostiles::Hostile;
+use crate::input::{PlayerInput, PlayerInputSet, PlayerInputs};
+use crate::physics::{FIXED_TICK_RATE, Interpolated, Mass, Velocity};
+use crate::player::{
+    EfficiencyCurve, Engine, EngineThrottle, Loadout, MAX_PLAYERS, Player, PlayerCount, RotationCharge, RotationRamp,
+    SHIP_SCALE, ShipAssets, Starship, ThrottleResponse, limit_speed, rotate_ship, spawn_point, thrust_ship,
+};
+use crate::session::{GameSettings, GameState, GameplayEntity, Lives, NewGameEvent, Score};
+use crate::storage;
+use crate::weapons::Turret;
+use crate::GameSet;
+
+// File replays are saved to and loaded from unless the recorder is pointed elsewhere
+const REPLAY_PATH: &str = "replay.ron";
+
+// Format of the replays written by this build; older files are turned away rather than misplayed
+const REPLAY_VERSION: u32 = 2;
+
+// Ticks of input room set aside up front, so recording doesn't reallocate for the first ten minutes
+const RECORDING_CAPACITY: usize = FIXED_TICK_RATE as usize * 60 * 10;
+
+// Keys held with F8 to race the replay as a ghost instead of watching it
+const GHOST_MODIFIERS: [KeyCode; 2] = [KeyCode::ShiftLeft, KeyCode::ShiftRight];
+
+// Ticks between two checksums of the simulated state
+const CHECKSUM_INTERVAL: usize = FIXED_TICK_RATE as usize;
+
+// A recorded run: the input of every player for every fixed tick, in order
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+struct Recording {
+    #[serde(default)]
+    version: u32,                               // Format the file was written in
+    seed: u64,                                  // Seed the recorded run started from
+    #[serde(default = "single_player")]
+    players: usize,                             // How many players the run had
+    inputs: Vec<[PlayerInput; MAX_PLAYERS]>,    // One entry per fixed tick
+    #[serde(default)]
+    checksums: Vec<u64>,                        // State every CHECKSUM_INTERVAL ticks, for spotting desyncs
+    #[serde(default)]
+    final_score: Option<[u32; MAX_PLAYERS]>,    // Score when recording stopped
+}
+
+// Player count of recordings made before there was more than one player
+fn single_player() -> usize {
+    1
+}
+
+impl Recording {
+    // Write the recording to a RON file
+    fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        storage::write(Path::new(path), &ron::to_string(self)?)?;
+        Ok(())
+    }
+
+    // Read a recording back from a RON file
+    fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let recording: Self = ron::from_str(&storage::read(Path::new(path))?)?;
+        if recording.version != REPLAY_VERSION {
+            return Err(format!(
+                "replay version {} is not supported, expected {}",
+                recording.version, REPLAY_VERSION
+            )
+            .into());
+        }
+        Ok(recording)
+    }
+}
+
+// Start and step of the 64-bit FNV-1a hash, which unlike the standard library's hasher gives
+// the same checksum on every build and platform, so recordings can be checked anywhere
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+// Fold the bytes of a value into an FNV-1a hash
+fn fnv_fold(hash: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(hash, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME))
+}
+
+// The simulated state a replay is checked against
+#[derive(SystemParam)]
+struct SimulationState<'w, 's> {
+    score: Res<'w, Score>,                                    // Points so far
+    ship_query: Query<'w, 's, (Entity, &'static Transform, &'static Velocity, &'static Player), With<Starship>>, // Get every ship
+    turret_query: Query<'w, 's, (&'static Parent, &'static Transform), With<Turret>>, // Get where every ship aims
+    hostile_query: Query<'w, 's, (), With<Hostile>>,          // Get the hostiles alive
+}
+
+impl SimulationState<'_, '_> {
+    // Fingerprint of the simulated state, equal between runs only while they stay in step
+    fn checksum(&self) -> u64 {
+        let mut hash = FNV_OFFSET;
+        for points in self.score.0 {
+            hash = fnv_fold(hash, &points.to_le_bytes());
+        }
+        hash = fnv_fold(hash, &(self.hostile_query.iter().count() as u64).to_le_bytes());
+        // Query order isn't guaranteed, so hash the ships in player order
+        let mut ships: Vec<_> = self.ship_query.iter().collect();
+        ships.sort_by_key(|(_, _, _, player)| player.id);
+        for (entity, transform, velocity, player) in ships {
+            hash = fnv_fold(hash, &(player.id as u64).to_le_bytes());
+            let aim = self
+                .turret_query
+                .iter()
+                .find(|(parent, _)| parent.get() == entity)
+                .map_or(0.0, |(_, turret)| turret.rotation.z);
+            for value in [transform.translation.x, transform.translation.y, transform.rotation.z, velocity.x, velocity.y, aim] {
+                hash = fnv_fold(hash, &value.to_bits().to_le_bytes());
+            }
+        }
+        hash
+    }
+}
+
+// What the replay recorder is currently doing
+#[derive(Reflect, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReplayMode {
+    #[default]
+    Off,        // Live input, nothing recorded
+    Recording,  // Live input, every tick stored
+    Playback,   // Stored input replaces live input
+}
+
+// Resource recording player input and feeding it back for playback
+#[derive(Resource)]
+pub struct ReplayRecorder {
+    pub path: String,             // File the replay is saved to and loaded from
+    mode: ReplayMode,             // Recording, playing back, or idle
+    pending: Option<ReplayMode>,  // Mode to switch to once the requested new run has started
+    recording: Recording,         // The inputs recorded or being played back
+    cursor: usize,                // Next tick to play back
+    desynced: bool,               // Whether playback has already drifted from the recording
+}
+
+impl Default for ReplayRecorder {
+    fn default() -> Self {
+        Self {
+            path: REPLAY_PATH.to_string(),
+            mode: ReplayMode::Off,
+            pending: None,
+            recording: Recording::default(),
+            cursor: 0,
+            desynced: false,
+        }
+    }
+}
+
+impl ReplayRecorder {
+    // Whether the recorder is recording, playing back or idle
+    pub fn mode(&self) -> ReplayMode {
+        self.mode
+    }
+
+    // Whether the playback has drifted from the recorded run
+    pub fn desynced(&self) -> bool {
+        self.desynced
+    }
+
+    // Stop recording and keep the run on disk
+    fn finish_recording(&mut self, score: &Score) {
+        self.mode = ReplayMode::Off;
+        self.recording.final_score = Some(score.0);
+        match self.recording.save(&self.path) {
+            Ok(()) => info!("Saved {} ticks to {}", self.recording.inputs.len(), self.path),
+            Err(err) => error!("Failed to save replay to {}: {}", self.path, err),
+        }
+    }
+
+    // Load the replay file and play it back from a fresh run
+    fn start_playback(&mut self, player_count: &mut PlayerCount, new_game_events: &mut EventWriter<NewGameEvent>) {
+        match Recording::load(&self.path) {
+            Ok(recording) => {
+                info!("Playing back {} ticks from {}", recording.inputs.len(), self.path);
+                // Replay with the recorded seed and players so the run starts exactly as it did
+                player_count.0 = recording.players;
+                new_game_events.send(NewGameEvent {
+                    seed: Some(recording.seed),
+                });
+                *self = ReplayRecorder {
+                    path: std::mem::take(&mut self.path),
+                    pending: Some(ReplayMode::Playback),
+                    recording,
+                    ..default()
+                };
+            }
+            Err(err) => error!("Failed to load replay from {}: {}", self.path, err),
+        }
+    }
+}
+
+// System to start and stop recording (F7) and playback (F8)
+fn replay_control_system(
+    keyboard_input: Res<Input<KeyCode>>,                      // For keyboard input
+    mut recorder: ResMut<ReplayRecorder>,                     // The recorder to drive
+    game_settings: Res<GameSettings>,                         // Seed for recorded runs
+    score: Res<Score>,                                        // Score to store with the recording
+    mut player_count: ResMut<PlayerCount>,                    // Players in the recorded run
+    mut new_game_events: EventWriter<NewGameEvent>,           // For restarting the run
+) {
+    if keyboard_input.just_pressed(KeyCode::F7) {
+        if recorder.mode == ReplayMode::Recording {
+            recorder.finish_recording(&score);
+        } else {
+            // Record from a fresh run so playback can start from the same state
+            *recorder = ReplayRecorder {
+                path: std::mem::take(&mut recorder.path),
+                pending: Some(ReplayMode::Recording),
+                recording: Recording {
+                    version: REPLAY_VERSION,
+                    seed: game_settings.seed,
+                    players: player_count.0,
+                    inputs: Vec::with_capacity(RECORDING_CAPACITY),
+                    ..default()
+                },
+                ..default()
+            };
+            new_game_events.send(NewGameEvent {
+                seed: Some(game_settings.seed),
+            });
+            info!("Recording replay");
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::F8) && !keyboard_input.any_pressed(GHOST_MODIFIERS) {
+        recorder.start_playback(&mut player_count, &mut new_game_events);
+    }
+}
+
+// Startup system playing the replay file back straight away when started with `--replay`
+fn replay_args_system(
+    cli_args: Res<CliArgs>,                                   // Options the game was launched with
+    mut recorder: ResMut<ReplayRecorder>,                     // The recorder to drive
+    mut player_count: ResMut<PlayerCount>,                    // Players in the recorded run
+    mut new_game_events: EventWriter<NewGameEvent>,           // For restarting the run
+) {
+    if cli_args.replay {
+        recorder.start_playback(&mut player_count, &mut new_game_events);
+    }
+}
+
+// System to start recording or playing back once the requested run has been set up, so the
+// first recorded tick always sees the fresh run however many ticks the restart took
+fn replay_start_system(
+    mut new_game_events: EventReader<NewGameEvent>,           // Runs being started
+    mut recorder: ResMut<ReplayRecorder>,                     // The recorder to arm
+) {
+    if new_game_events.read().count() == 0 {
+        return;
+    }
+    if let Some(mode) = recorder.pending.take() {
+        recorder.mode = mode;
+        recorder.cursor = 0;
+    }
+}
+
+// System to save the recording once every player has run out of lives
+fn replay_game_over_system(
+    mut recorder: ResMut<ReplayRecorder>,                     // The recording to save
+    lives: Res<Lives>,                                        // Lives left of every player
+    score: Res<Score>,                                        // Score to store with the recording
+    player_count: Res<PlayerCount>,                           // Who is taking part
+) {
+    if recorder.mode == ReplayMode::Recording && player_count.ids().all(|id| lives.0[id] == 0) {
+        recorder.finish_recording(&score);
+    }
+}
+
+// Run condition for whether a recording is being played back instead of live input
+pub fn replay_playing(recorder: Res<ReplayRecorder>) -> bool {
+    recorder.mode == ReplayMode::Playback
+}
+
+// System to store this tick's input while recording
+fn replay_record_system(
+    player_inputs: Res<PlayerInputs>,                         // This tick's intent of every player
+    mut recorder: ResMut<ReplayRecorder>,                     // Where the input is stored
+    state: SimulationState,                                   // Part of the checksum
+) {
+    if recorder.mode != ReplayMode::Recording {
+        return;
+    }
+    if recorder.recording.inputs.len().is_multiple_of(CHECKSUM_INTERVAL) {
+        let checksum = state.checksum();
+        recorder.recording.checksums.push(checksum);
+    }
+    recorder.recording.inputs.push(player_inputs.0);
+}
+
+// System to replace live input with the recorded input while playing back,
+// reporting where the simulation drifts away from the recorded one
+fn replay_playback_system(
+    mut player_inputs: ResMut<PlayerInputs>,                  // Where the intent is written
+    mut recorder: ResMut<ReplayRecorder>,                     // Where the input comes from
+    state: SimulationState,                                   // Compared against the recording
+) {
+    if recorder.mode != ReplayMode::Playback {
+        return;
+    }
+    let tick = recorder.cursor;
+
+    // Check the state every so often; one report is enough once it has drifted
+    if tick.is_multiple_of(CHECKSUM_INTERVAL) && !recorder.desynced {
+        let expected = recorder.recording.checksums.get(tick / CHECKSUM_INTERVAL).copied();
+        let checksum = state.checksum();
+        if expected.is_some_and(|expected| expected != checksum) {
+            error!("Replay desynced at tick {}: the simulation no longer matches the recording", tick);
+            recorder.desynced = true;
+        }
+    }
+
+    match recorder.recording.inputs.get(tick).copied() {
+        Some(inputs) => {
+            player_inputs.0 = inputs;
+            recorder.cursor += 1;
+        }
+        None => {
+            // Hand control back to the player once the recording runs out
+            match recorder.recording.final_score {
+                Some(expected) if expected == state.score.0 => info!("Replay finished, score {:?} matches", state.score.0),
+                Some(expected) => error!("Replay finished with score {:?}, recorded {:?}", state.score.0, expected),
+                None => info!("Replay finished"),
+            }
+            recorder.mode = ReplayMode::Off;
+        }
+    }
+}
+
+// Define the Ghost component for a see-through ship flying a recorded run alongside live play
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+struct Ghost {
+    starship: Starship,  // Handling of the recorded ship
+}
+
+// Resource holding the recording a ghost ship flies
+#[derive(Resource, Default)]
+struct GhostReplay {
+    recording: Option<Recording>,  // The run the ghost flies, if any
+    pending: bool,                 // Whether the ghost joins the next run started
+    cursor: usize,                 // Next tick the ghost flies
+}
+
+// System to race the first player of the replay file as a ghost (Shift+F8)
+fn ghost_control_system(
+    keyboard_input: Res<Input<KeyCode>>,                      // For keyboard input
+    recorder: Res<ReplayRecorder>,                            // Where the replay file is
+    mut ghost: ResMut<GhostReplay>,                           // The ghost to set up
+    mut new_game_events: EventWriter<NewGameEvent>,           // For restarting the run
+) {
+    if !keyboard_input.just_pressed(KeyCode::F8) || !keyboard_input.any_pressed(GHOST_MODIFIERS) {
+        return;
+    }
+    match Recording::load(&recorder.path) {
+        Ok(recording) => {
+            info!("Racing a ghost of {} ticks from {}", recording.inputs.len(), recorder.path);
+            // The same seed gives the live run the same hostiles the ghost faced
+            new_game_events.send(NewGameEvent {
+                seed: Some(recording.seed),
+            });
+            *ghost = GhostReplay {
+                recording: Some(recording),
+                pending: true,
+                cursor: 0,
+            };
+        }
+        Err(err) => error!("Failed to load replay from {}: {}", recorder.path, err),
+    }
+}
+
+// System to put the ghost on the starting line of a new run it was asked into
+fn ghost_spawn_system(
+    mut commands: Commands,                                   // For spawning the ghost
+    mut new_game_events: EventReader<NewGameEvent>,           // Runs being started
+    mut ghost: ResMut<GhostReplay>,                           // The ghost to start
+    ship_assets: Res<ShipAssets>,                             // The ship's sprite sheet
+    loadout: Res<Loadout>,                                    // Starting values of the ship
+    player_count: Res<PlayerCount>,                           // For where the first ship starts
+) {
+    if new_game_events.read().count() == 0 {
+        return;
+    }
+    // Any other new run leaves the ghost behind with the old one
+    if !ghost.pending {
+        ghost.recording = None;
+        return;
+    }
+    ghost.pending = false;
+    ghost.cursor = 0;
+    commands.spawn((
+        SpriteSheetBundle {
+            texture_atlas: ship_assets.texture_atlas.clone(),
+            sprite: TextureAtlasSprite {
+                color: Color::rgba(0.6, 0.8, 1.0, 0.35),
+                ..default()
+            },
+            transform: Transform {
+                scale: Vec3::new(SHIP_SCALE, SHIP_SCALE, 1.0),
+                // Drawn under the live ship where the two overlap
+                translation: spawn_point(0, &player_count).extend(-0.1),
+                ..default()
+            },
+            ..default()
+        },
+        GameplayEntity,
+        Ghost {
+            starship: Starship {
+                rotation_speed: loadout.rotation_speed,
+                max_speed: loadout.max_speed,
+            },
+        },
+        Velocity { x: 0.0, y: 0.0 },
+        Interpolated,
+        Engine {
+            fuel: loadout.fuel.min(loadout.max_fuel),
+            max_fuel: loadout.max_fuel,
+            thrust: loadout.thrust,
+            strafe_thrust: loadout.strafe_thrust,
+        },
+        EngineThrottle::default(),
+        RotationCharge::default(),
+    ));
+}
+
+// Components of the ghost flying the recorded input
+type GhostData<'a> = (
+    Entity,
+    &'a mut Transform,
+    &'a mut Velocity,
+    &'a mut Engine,
+    &'a mut EngineThrottle,
+    &'a mut RotationCharge,
+    &'a Ghost,
+);
+
+// System to fly the ghost with the recorded input, the same way a live ship flies;
+// without a collider it passes through everything
+fn ghost_system(
+    mut commands: Commands,                                   // For removing the ghost at the end
+    time: Res<Time>,                                          // For frame-independent movement
+    mut ghost: ResMut<GhostReplay>,                           // The run the ghost flies
+    mut query: Query<GhostData>,                              // Get the ghost
+    efficiency: Res<EfficiencyCurve>,                         // How fuel burn grows with throttle
+    throttle_response: Res<ThrottleResponse>,                 // How quickly the engine answers
+    rotation_ramp: Res<RotationRamp>,                         // How quickly a held turn spins up
+) {
+    let Ok((entity, mut transform, mut velocity, mut engine, mut throttle, mut charge, ghost_ship)) = query.get_single_mut() else {
+        return;
+    };
+    let input = ghost
+        .recording
+        .as_ref()
+        .and_then(|recording| recording.inputs.get(ghost.cursor))
+        .map(|inputs| inputs[0]);
+    let Some(mut input) = input else {
+        info!("Ghost finished");
+        commands.entity(entity).despawn();
+        ghost.recording = None;
+        return;
+    };
+    ghost.cursor += 1;
+    input.thrust = throttle.follow(input.thrust, &throttle_response, time.delta_seconds());
+    input.rotate = charge.follow(input.rotate, &rotation_ramp, time.delta_seconds());
+    rotate_ship(&mut transform, &ghost_ship.starship, &input, time.delta_seconds());
+    thrust_ship(
+        &mut velocity,
+        &transform,
+        &mut engine,
+        // Ghosts fly like a ship fresh off the line
+        Mass::default().0,
+        &input,
+        &efficiency,
+        time.delta_seconds(),
+    );
+    limit_speed(&mut velocity, &ghost_ship.starship);
+}
+
+// Plugin for recording and replaying runs
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Ghost>()
+            .init_resource::<ReplayRecorder>()
+            .init_resource::<GhostReplay>()
+            .add_systems(Startup, replay_args_system)
+            .add_systems(PreUpdate, (
+                replay_control_system,
+                ghost_control_system,
+            ).after(PlayerInputSet).run_if(in_state(GameState::Playing)))
+            .add_systems(Update, (
+                replay_start_system,
+                ghost_spawn_system,
+                replay_game_over_system.run_if(in_state(GameState::Playing)),
+            ))
+            // Record or overwrite the player input once per fixed tick, before the simulation reads it
+            .add_systems(FixedUpdate, (
+                replay_record_system,
+                replay_playback_system,
+            ).in_set(PlayerInputSet).in_set(GameSet::Input).run_if(in_state(GameState::Playing)))
+            .add_systems(FixedUpdate, ghost_system.in_set(GameSet::Input).run_if(in_state(GameState::Playing)));
+    }
+}
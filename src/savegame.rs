@@ -0,0 +1,359 @@
+// Saving a run mid-game (F5) and picking it up again later (F9)
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use crate::combat::{Health, Shield};
+use crate::difficulty::SurvivalTimer;
+use crate::garage::{Credits, Upgrades};
+use crate::hostiles::{Asteroid, Enemy, spawn_asteroid, spawn_enemy};
+use crate::inventory::Inventory;
+use crate::physics::{Collider, Lifetime, Velocity};
+use crate::player::{
+    Engine, Loadout, MAX_PLAYERS, Player, PlayerCount, Respawn, SHIP_SCALE, ShipAssets, Starship, spawn_ship,
+};
+use crate::powerups::{PowerUp, PowerUpKind, spawn_power_up};
+use crate::session::{GameState, GameplayEntity, Lives, Score};
+use crate::storage::{self, data_path};
+use crate::trail::TrailSettings;
+use crate::weapons::{Bombs, Weapon, WeaponType};
+
+// Key saving the run
+const SAVE_KEY: KeyCode = KeyCode::F5;
+
+// Key loading the saved run
+const LOAD_KEY: KeyCode = KeyCode::F9;
+
+// Format of the saves written by this build; older files are turned away rather than misread
+const SAVE_VERSION: u32 = 3;
+
+// Where the save lives
+fn save_path() -> PathBuf {
+    data_path("save.ron")
+}
+
+// A ship as it was when the run was saved
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct SavedShip {
+    player: usize,        // Who flies it
+    position: Vec2,       // Where it was
+    rotation: f32,        // Which way it faced, in radians
+    velocity: Vec2,       // How it was moving
+    fuel: f32,            // Fuel left in the tank
+    health: f32,          // Hull left
+    shield: f32,          // Shield left
+    weapon: WeaponType,   // Gun it carried
+}
+
+// An asteroid as it was when the run was saved
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct SavedAsteroid {
+    position: Vec2,  // Where it was
+    velocity: Vec2,  // How it was moving
+    radius: f32,     // How big it is
+    health: f32,     // Health left
+}
+
+// An enemy drone as it was when the run was saved
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct SavedEnemy {
+    position: Vec2,  // Where it was
+    velocity: Vec2,  // How it was moving
+    health: f32,     // Health left
+}
+
+// A pickup as it was when the run was saved
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct SavedPowerUp {
+    kind: PowerUpKind,  // What collecting it does
+    position: Vec2,     // Where it was
+    velocity: Vec2,     // Its drift
+    lifetime: f32,      // Seconds left before it disappears
+}
+
+// Everything needed to pick a run up where it was left
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct SaveGame {
+    version: u32,                   // Format the file was written in
+    players: usize,                 // How many players the run had
+    score: [u32; MAX_PLAYERS],      // Points of every player
+    lives: [u32; MAX_PLAYERS],      // Lives left of every player
+    bombs: u32,                     // Smart bombs left
+    inventory: Inventory,           // Items carried
+    credits: Credits,               // Credits left to spend in the garage
+    upgrades: Upgrades,             // Upgrades bought this run
+    survival: f32,                  // Seconds survived, which sets the difficulty
+    ships: Vec<SavedShip>,          // Ships in play
+    asteroids: Vec<SavedAsteroid>,  // Asteroids in play
+    enemies: Vec<SavedEnemy>,       // Enemy drones in play
+    power_ups: Vec<SavedPowerUp>,   // Pickups waiting to be collected
+}
+
+impl SaveGame {
+    // Write the save to a RON file
+    fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        storage::write(path, &ron::to_string(self)?)?;
+        Ok(())
+    }
+
+    // Read a save back from a RON file, refusing anything that can't be restored as it is
+    fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let save: Self = ron::from_str(&storage::read(path)?)?;
+        save.validate()?;
+        Ok(save)
+    }
+
+    // Check the save describes a run the game can actually be in
+    fn validate(&self) -> Result<(), String> {
+        if self.version != SAVE_VERSION {
+            return Err(format!("save version {} is not supported, expected {}", self.version, SAVE_VERSION));
+        }
+        if !(1..=MAX_PLAYERS).contains(&self.players) {
+            return Err(format!("save has {} players", self.players));
+        }
+        let mut seen = [false; MAX_PLAYERS];
+        for ship in &self.ships {
+            if ship.player >= self.players || std::mem::replace(&mut seen[ship.player], true) {
+                return Err(format!("save has an unexpected ship for player {}", ship.player + 1));
+            }
+        }
+        let finite = self.survival.is_finite()
+            && self.ships.iter().all(|ship| {
+                ship.position.is_finite()
+                    && ship.velocity.is_finite()
+                    && [ship.rotation, ship.fuel, ship.health, ship.shield].iter().all(|value| value.is_finite())
+            })
+            && self.asteroids.iter().all(|asteroid| {
+                asteroid.position.is_finite() && asteroid.velocity.is_finite() && asteroid.health.is_finite()
+            })
+            && self.enemies.iter().all(|enemy| {
+                enemy.position.is_finite() && enemy.velocity.is_finite() && enemy.health.is_finite()
+            })
+            && self.power_ups.iter().all(|power_up| {
+                power_up.position.is_finite() && power_up.velocity.is_finite() && power_up.lifetime.is_finite()
+            });
+        if !finite {
+            return Err("save holds a position, speed or amount that isn't a number".to_string());
+        }
+        if self.asteroids.iter().any(|asteroid| asteroid.radius <= 0.0 || !asteroid.radius.is_finite()) {
+            return Err("save holds an asteroid without a size".to_string());
+        }
+        Ok(())
+    }
+}
+
+// The run-wide state a save captures and restores
+#[derive(SystemParam)]
+struct RunState<'w> {
+    score: ResMut<'w, Score>,                                 // Points of every player
+    lives: ResMut<'w, Lives>,                                 // Lives left of every player
+    bombs: ResMut<'w, Bombs>,                                 // Smart bombs left
+    inventory: ResMut<'w, Inventory>,                         // Items carried
+    credits: ResMut<'w, Credits>,                             // Credits left to spend
+    upgrades: ResMut<'w, Upgrades>,                           // Upgrades bought
+    survival: ResMut<'w, SurvivalTimer>,                      // Seconds survived
+    player_count: ResMut<'w, PlayerCount>,                    // How many players take part
+    respawn: ResMut<'w, Respawn>,                             // Ships on their way back
+}
+
+// Components of a ship captured by a save
+type SavedShipData<'a> = (
+    &'a Transform,
+    &'a Velocity,
+    &'a Engine,
+    &'a Health,
+    Option<&'a Shield>,
+    &'a Weapon,
+    &'a Player,
+);
+
+// System to write the run to the save file
+fn save_game_system(
+    keyboard_input: Res<Input<KeyCode>>,                      // For keyboard input
+    run_state: RunState,                                      // Score, lives and the like
+    ship_query: Query<SavedShipData, With<Starship>>,         // Get the ships
+    asteroid_query: Query<(&Transform, &Velocity, &Collider, &Health), With<Asteroid>>, // Get the asteroids
+    enemy_query: Query<(&Transform, &Velocity, &Health), With<Enemy>>, // Get the drones
+    power_up_query: Query<(&Transform, &Velocity, &Lifetime, &PowerUp)>, // Get the pickups
+) {
+    if !keyboard_input.just_pressed(SAVE_KEY) {
+        return;
+    }
+    let save = SaveGame {
+        version: SAVE_VERSION,
+        players: run_state.player_count.ids().len(),
+        score: run_state.score.0,
+        lives: run_state.lives.0,
+        bombs: run_state.bombs.0,
+        inventory: *run_state.inventory,
+        credits: *run_state.credits,
+        upgrades: *run_state.upgrades,
+        survival: run_state.survival.elapsed,
+        ships: ship_query
+            .iter()
+            .map(|(transform, velocity, engine, health, shield, weapon, player)| SavedShip {
+                player: player.id,
+                position: transform.translation.truncate(),
+                rotation: transform.rotation.to_euler(EulerRot::ZYX).0,
+                velocity: velocity.as_vec2(),
+                fuel: engine.fuel,
+                health: health.current,
+                shield: shield.map_or(0.0, |shield| shield.current),
+                weapon: weapon.weapon_type,
+            })
+            .collect(),
+        asteroids: asteroid_query
+            .iter()
+            .map(|(transform, velocity, collider, health)| SavedAsteroid {
+                position: transform.translation.truncate(),
+                velocity: velocity.as_vec2(),
+                radius: collider.radius,
+                health: health.current,
+            })
+            .collect(),
+        enemies: enemy_query
+            .iter()
+            .map(|(transform, velocity, health)| SavedEnemy {
+                position: transform.translation.truncate(),
+                velocity: velocity.as_vec2(),
+                health: health.current,
+            })
+            .collect(),
+        power_ups: power_up_query
+            .iter()
+            .map(|(transform, velocity, lifetime, power_up)| SavedPowerUp {
+                kind: power_up.kind,
+                position: transform.translation.truncate(),
+                velocity: velocity.as_vec2(),
+                lifetime: lifetime.0.remaining_secs(),
+            })
+            .collect(),
+    };
+    let path = save_path();
+    match save.save(&path) {
+        Ok(()) => info!("Saved the run to {}", path.display()),
+        Err(err) => error!("Failed to save the run to {}: {}", path.display(), err),
+    }
+}
+
+// System to swap the current run for the one in the save file
+fn load_game_system(
+    mut commands: Commands,                                   // For despawning and respawning
+    keyboard_input: Res<Input<KeyCode>>,                      // For keyboard input
+    mut run_state: RunState,                                  // Score, lives and the like
+    gameplay_query: Query<Entity, With<GameplayEntity>>,      // Get the current run
+    ship_assets: Res<ShipAssets>,                             // The ship's sprite sheet
+    trail_settings: Res<TrailSettings>,                       // For the ship's trail length
+    loadout: Res<Loadout>,                                    // Starting values of the ships
+) {
+    if !keyboard_input.just_pressed(LOAD_KEY) {
+        return;
+    }
+    // A bad file leaves the current run alone
+    let path = save_path();
+    let save = match SaveGame::load(&path) {
+        Ok(save) => save,
+        Err(err) => {
+            error!("Failed to load the run from {}: {}", path.display(), err);
+            return;
+        }
+    };
+
+    for entity in gameplay_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    run_state.player_count.0 = save.players;
+    run_state.score.0 = save.score;
+    run_state.lives.0 = save.lives;
+    run_state.bombs.0 = save.bombs;
+    *run_state.inventory = save.inventory;
+    *run_state.credits = save.credits;
+    *run_state.upgrades = save.upgrades;
+    run_state.survival.elapsed = save.survival;
+
+    // Players caught between losing a ship and getting the next one get it shortly
+    let mut has_ship = [false; MAX_PLAYERS];
+    for ship in save.ships.iter().cloned() {
+        has_ship[ship.player] = true;
+        let entity = spawn_ship(
+            &mut commands,
+            &ship_assets,
+            &trail_settings,
+            &loadout,
+            Player { id: ship.player },
+            ship.position,
+        );
+        commands.entity(entity).add(move |entity: EntityWorldMut| restore_ship(&ship, entity));
+    }
+    for id in run_state.player_count.ids() {
+        run_state.respawn.pending[id] = !has_ship[id] && run_state.lives.0[id] > 0;
+        run_state.respawn.timers[id].reset();
+    }
+
+    for asteroid in &save.asteroids {
+        let entity = spawn_asteroid(&mut commands, asteroid.position, asteroid.velocity, asteroid.radius);
+        commands.entity(entity).insert(Health {
+            current: asteroid.health,
+            max: asteroid.radius,
+        });
+    }
+    for enemy in &save.enemies {
+        let entity = spawn_enemy(&mut commands, enemy.position);
+        commands.entity(entity).insert(Velocity {
+            x: enemy.velocity.x,
+            y: enemy.velocity.y,
+        });
+        // Never more health than a fresh drone has
+        let saved_health = enemy.health;
+        commands.entity(entity).add(move |mut entity: EntityWorldMut| {
+            if let Some(mut health) = entity.get_mut::<Health>() {
+                health.current = saved_health.clamp(0.0, health.max);
+            }
+        });
+    }
+    for power_up in &save.power_ups {
+        spawn_power_up(&mut commands, power_up.kind, power_up.position, power_up.velocity, power_up.lifetime);
+    }
+    info!("Loaded the run from {}", path.display());
+}
+
+// Put the saved state back onto a freshly spawned ship, within what its loadout allows
+fn restore_ship(ship: &SavedShip, mut entity: EntityWorldMut) {
+    if let Some(mut transform) = entity.get_mut::<Transform>() {
+        *transform = Transform {
+            translation: ship.position.extend(0.0),
+            rotation: Quat::from_rotation_z(ship.rotation),
+            scale: Vec3::new(SHIP_SCALE, SHIP_SCALE, 1.0),
+        };
+    }
+    if let Some(mut velocity) = entity.get_mut::<Velocity>() {
+        velocity.x = ship.velocity.x;
+        velocity.y = ship.velocity.y;
+    }
+    if let Some(mut engine) = entity.get_mut::<Engine>() {
+        engine.fuel = ship.fuel.clamp(0.0, engine.max_fuel);
+    }
+    if let Some(mut health) = entity.get_mut::<Health>() {
+        health.current = ship.health.clamp(0.0, health.max);
+    }
+    if let Some(mut shield) = entity.get_mut::<Shield>() {
+        shield.current = ship.shield.clamp(0.0, shield.max);
+    }
+    if let Some(mut weapon) = entity.get_mut::<Weapon>() {
+        weapon.weapon_type = ship.weapon;
+    }
+}
+
+// Plugin for saving and loading runs
+pub struct SaveGamePlugin;
+
+impl Plugin for SaveGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (
+            save_game_system,
+            load_game_system,
+        ).chain().run_if(in_state(GameState::Playing)));
+    }
+}
@@ -0,0 +1,185 @@
+// Saving the current frame to a PNG, HUD and all
+use bevy::prelude::*;
+use bevy::render::view::screenshot::ScreenshotManager;
+use bevy::window::PrimaryWindow;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::sync::Mutex;
+
+use crate::storage::unix_time;
+
+// Key taking a screenshot
+const SCREENSHOT_KEY: KeyCode = KeyCode::F10;
+
+// Folder the screenshots go in, next to the game
+const SCREENSHOT_DIR: &str = "screenshots";
+
+// Seconds the confirmation stays on screen while fading out
+const CONFIRMATION_TIME: f32 = 2.0;
+
+// Where a screenshot was written, or why it couldn't be
+type SaveResult = Result<PathBuf, String>;
+
+// Resource carrying the outcome of every screenshot back from the thread that writes it
+#[derive(Resource)]
+struct ScreenshotResults {
+    sender: Sender<SaveResult>,              // Handed to every capture
+    receiver: Mutex<Receiver<SaveResult>>,   // Read once a frame
+}
+
+impl Default for ScreenshotResults {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            sender,
+            receiver: Mutex::new(receiver),
+        }
+    }
+}
+
+// Define the ScreenshotText component for the confirmation shown after a screenshot
+#[derive(Component)]
+struct ScreenshotText {
+    timer: Timer,  // Time left on screen
+}
+
+// The date and time in UTC for a file name, like 2024-01-01_12-00-00
+fn timestamp(unix_time: u64) -> String {
+    let (days, seconds) = ((unix_time / 86_400) as i64, unix_time % 86_400);
+    // Days since the epoch to a date, counting in 400-year eras that start on the 1st of March
+    let day_of_era_start = days + 719_468;
+    let era = day_of_era_start.div_euclid(146_097);
+    let day_of_era = day_of_era_start - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 { month_from_march + 3 } else { month_from_march - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}_{:02}-{:02}-{:02}",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+// System to capture the window when the screenshot key is pressed. The capture is everything the
+// window shows, so the HUD is in it, and so is the inspector when it is open
+fn screenshot_system(
+    keyboard_input: Res<Input<KeyCode>>,                      // For keyboard input
+    window_query: Query<Entity, With<PrimaryWindow>>,         // Get the window to capture
+    mut screenshot_manager: ResMut<ScreenshotManager>,        // For capturing the window
+    results: Res<ScreenshotResults>,                          // Where the outcome goes
+) {
+    if !keyboard_input.just_pressed(SCREENSHOT_KEY) {
+        return;
+    }
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+    let path = PathBuf::from(SCREENSHOT_DIR).join(format!("screenshot_{}.png", timestamp(unix_time())));
+    let sender = results.sender.clone();
+
+    // Written out on a worker thread once the frame is rendered, reporting back either way
+    #[cfg(not(target_arch = "wasm32"))]
+    let requested = screenshot_manager.take_screenshot(window, move |image| {
+        let saved = std::fs::create_dir_all(SCREENSHOT_DIR)
+            .map_err(|err| err.to_string())
+            .and_then(|()| image.try_into_dynamic().map_err(|err| err.to_string()))
+            // The alpha channel holds brightness with HDR on, so leave it out
+            .and_then(|image| image.to_rgb8().save(&path).map_err(|err| err.to_string()))
+            .map(|()| path);
+        // Nobody is left to tell if the game closed in the meantime
+        let _ = sender.send(saved);
+    });
+    // The browser offers the PNG as a download instead, with no way of telling whether it was kept
+    #[cfg(target_arch = "wasm32")]
+    let requested = screenshot_manager.save_screenshot_to_disk(window, &path).map(|()| {
+        let _ = sender.send(Ok(path));
+    });
+
+    if let Err(err) = requested {
+        warn!("Not taking a screenshot: {}", err);
+    }
+}
+
+// System to confirm every screenshot written, or say why it wasn't, in a note that fades away
+fn screenshot_result_system(
+    mut commands: Commands,                                   // For showing the note
+    results: Res<ScreenshotResults>,                          // Outcomes of the screenshots
+    text_query: Query<Entity, With<ScreenshotText>>,          // Get the note already shown
+) {
+    let receiver = results.receiver.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let Some(saved) = receiver.try_iter().last() else {
+        return;
+    };
+    let message = match saved {
+        Ok(path) => {
+            info!("Saved a screenshot to {}", path.display());
+            format!("Saved {}", path.file_name().unwrap_or_default().to_string_lossy())
+        }
+        Err(err) => {
+            error!("Failed to save a screenshot: {}", err);
+            format!("Failed to save screenshot: {}", err)
+        }
+    };
+    // A newer note replaces the one showing
+    for entity in text_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.spawn((
+        TextBundle::from_section(
+            message,
+            TextStyle {
+                font_size: 18.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            bottom: Val::Px(10.0),
+            ..default()
+        }),
+        ScreenshotText {
+            timer: Timer::from_seconds(CONFIRMATION_TIME, TimerMode::Once),
+        },
+    ));
+}
+
+// System to fade the note out and take it away once it has faded
+fn screenshot_text_system(
+    mut commands: Commands,                                   // For removing the note
+    time: Res<Time<Real>>,                                    // For fading, even while paused
+    mut text_query: Query<(Entity, &mut ScreenshotText, &mut Text)>, // Get the note
+) {
+    for (entity, mut screenshot_text, mut text) in text_query.iter_mut() {
+        if screenshot_text.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let alpha = screenshot_text.timer.percent_left();
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(alpha);
+        }
+    }
+}
+
+// Plugin for taking screenshots
+pub struct ScreenshotPlugin;
+
+impl Plugin for ScreenshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScreenshotResults>()
+            .add_systems(Update, (
+                screenshot_system,
+                screenshot_result_system,
+                screenshot_text_system,
+            ).chain());
+    }
+}
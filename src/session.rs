@@ -0,0 +1,370 @@
+// Game state shared by a whole run: pausing, starting over, score, lives and the seeded RNG
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use bevy::window::WindowFocused;
+use rand::SeedableRng;
+
+use crate::cli::CliArgs;
+use crate::combat::ShipContacts;
+use crate::input::{PauseToggleEvent, SlowMotionToggleEvent};
+use crate::player::{Loadout, MAX_PLAYERS, Player, PlayerCount, Respawn, ShipAssets, spawn_point, spawn_ship};
+use crate::settings::Settings;
+use crate::trail::TrailSettings;
+use crate::weapons::{BombSettings, Bombs};
+
+// Marker for entities that belong to the current run and go away on a new game
+#[derive(Component)]
+pub struct GameplayEntity;
+
+// The states the game can be in
+#[derive(States, Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GameState {
+    #[default]
+    Loading,     // Assets are loading behind the loading screen
+    LoadFailed,  // An asset failed to load; the loading screen names it
+    Playing,     // The simulation runs
+    Paused,      // Everything is frozen until the player resumes
+    Upgrade,     // The garage is open between waves, with the simulation frozen
+    GameOver,    // Every ship is lost; the game-over screen is up over the frozen arena
+}
+
+// The kind of run being played, alongside whichever state the game is in
+#[derive(States, Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GameMode {
+    #[default]
+    Arcade,    // Hazards come in and the zone closes until every ship is lost
+    Practice,  // Target dummies to shoot at, and nothing that attacks
+}
+
+// Resource holding every player's score for the current run, indexed by player id
+#[derive(Reflect, Resource, Default)]
+#[reflect(Resource)]
+pub struct Score(pub [u32; MAX_PLAYERS]);
+
+// Resource holding how many lives every player has left, including the current one
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct Lives(pub [u32; MAX_PLAYERS]);
+
+impl Default for Lives {
+    fn default() -> Self {
+        Self([3; MAX_PLAYERS])
+    }
+}
+
+// Resource holding settings that apply to a whole game
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct GameSettings {
+    pub seed: u64,  // Seed every new game's randomness starts from
+}
+
+impl FromWorld for GameSettings {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            // A seed from a bug report can be passed back in with `--seed` to replay the same run
+            seed: world.get_resource::<CliArgs>().and_then(|cli_args| cli_args.seed).unwrap_or_else(rand::random),
+        }
+    }
+}
+
+// Resource controlling the pause that kicks in while the window is in the background
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct AutoPause {
+    pub enabled: bool,             // Whether losing focus pauses the game
+    pub resume_delay: f32,         // Seconds counted down after refocusing, 0 to resume at once
+    pub auto_resume: bool,         // Whether refocusing resumes by itself, or waits for the pause key
+    #[reflect(ignore)]
+    pub engaged: bool,             // Whether the current pause came from losing focus
+    #[reflect(ignore)]
+    pub countdown: Option<Timer>,  // Countdown to resuming, once the window is back in focus
+}
+
+impl Default for AutoPause {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            resume_delay: 3.0,
+            auto_resume: true,
+            engaged: false,
+            countdown: None,
+        }
+    }
+}
+
+// Resource controlling how fast game time runs compared to real time
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct TimeScale {
+    pub slow_motion_speed: f32,  // Speed of the game clock while slowed down
+    pub slowed: bool,            // Whether slow motion is on
+    pub debug_speed: f32,        // Further speed-up or slow-down from the developer tools, 1.0 normally
+    pub cinematic_speed: f32,    // Slow-down of a short cinematic like the death cam, 1.0 normally
+}
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self {
+            slow_motion_speed: 0.3,
+            slowed: false,
+            debug_speed: 1.0,
+            cinematic_speed: 1.0,
+        }
+    }
+}
+
+impl TimeScale {
+    // Speed the game clock should currently run at
+    pub fn speed(&self) -> f32 {
+        let speed = if self.slowed { self.slow_motion_speed } else { 1.0 };
+        speed * self.cinematic_speed * self.debug_speed
+    }
+}
+
+// Resource that all gameplay randomness is drawn from, so runs can be reproduced
+#[derive(Resource, Deref, DerefMut)]
+pub struct GameRng {
+    pub seed: u64,  // Seed the generator was last started from
+    #[deref]
+    pub rng: StdRng,  // The generator itself
+}
+
+impl GameRng {
+    // Create a generator starting from the given seed
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    // Restart the generator from the given seed
+    pub fn reseed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+}
+
+impl FromWorld for GameRng {
+    fn from_world(world: &mut World) -> Self {
+        world.init_resource::<GameSettings>();
+        let seed = world.resource::<GameSettings>().seed;
+        info!("Game seed: {}", seed);
+        Self::new(seed)
+    }
+}
+
+// Event asking for the current run to be thrown away and a fresh one started
+#[derive(Event, Default)]
+pub struct NewGameEvent {
+    pub seed: Option<u64>,  // Seed for the new run, or None to use the game settings
+}
+
+// Run condition for systems that stop while the game is paused but run in every other state
+pub fn not_paused(state: Res<State<GameState>>) -> bool {
+    *state.get() != GameState::Paused
+}
+
+// System to pause and resume the game when a player asks for it
+fn pause_toggle_system(
+    mut pause_events: EventReader<PauseToggleEvent>,          // Requests to pause or resume
+    state: Res<State<GameState>>,                             // The current state
+    mut next_state: ResMut<NextState<GameState>>,             // For switching state
+) {
+    if pause_events.read().count() == 0 {
+        return;
+    }
+    match state.get() {
+        GameState::Playing => next_state.set(GameState::Paused),
+        GameState::Paused => next_state.set(GameState::Playing),
+        // The garage is left through its own button, and nothing is running yet or any more to pause
+        GameState::Upgrade | GameState::GameOver | GameState::Loading | GameState::LoadFailed => {}
+    }
+}
+
+// System to switch slow motion on and off, and run the game clock at the chosen speed
+fn time_scale_system(
+    mut slow_motion_events: EventReader<SlowMotionToggleEvent>, // Requests to toggle slow motion
+    mut time_scale: ResMut<TimeScale>,                        // The chosen speed
+    mut virtual_time: ResMut<Time<Virtual>>,                  // The game clock everything runs on
+) {
+    if slow_motion_events.read().count() % 2 == 1 {
+        time_scale.slowed = !time_scale.slowed;
+    }
+    // The fixed timestep follows the game clock, so the simulation slows down with it
+    let speed = time_scale.speed().max(0.0);
+    if virtual_time.relative_speed() != speed {
+        virtual_time.set_relative_speed(speed);
+    }
+}
+
+// System to follow the auto-pause options in the settings
+fn auto_pause_settings_system(
+    settings: Res<Settings>,                                  // The player's preferences
+    mut auto_pause: ResMut<AutoPause>,                        // The options to update
+) {
+    auto_pause.enabled = settings.auto_pause;
+    auto_pause.resume_delay = settings.resume_delay.max(0.0);
+    auto_pause.auto_resume = settings.auto_resume;
+}
+
+// System to pause while the window is unfocused, and resume once it is focused again
+fn focus_pause_system(
+    mut focus_events: EventReader<WindowFocused>,             // Window focus changes
+    mut auto_pause: ResMut<AutoPause>,                        // Auto-pause settings and state
+    state: Res<State<GameState>>,                             // The current state
+    mut next_state: ResMut<NextState<GameState>>,             // For switching state
+) {
+    // Resuming by hand hands control back to the player
+    if *state.get() == GameState::Playing {
+        auto_pause.engaged = false;
+        auto_pause.countdown = None;
+    }
+    for event in focus_events.read() {
+        if !event.focused {
+            // Leave a pause the player chose alone so refocusing doesn't end it
+            if auto_pause.enabled && *state.get() == GameState::Playing {
+                auto_pause.engaged = true;
+                next_state.set(GameState::Paused);
+            }
+            auto_pause.countdown = None;
+        } else if auto_pause.engaged {
+            if !auto_pause.auto_resume {
+                // Stay paused like any other pause, for the player to end with the pause key
+                auto_pause.engaged = false;
+            } else if auto_pause.resume_delay > 0.0 {
+                auto_pause.countdown = Some(Timer::from_seconds(auto_pause.resume_delay, TimerMode::Once));
+            } else {
+                auto_pause.engaged = false;
+                next_state.set(GameState::Playing);
+            }
+        }
+    }
+}
+
+// System to count down to resuming after the window was focused again
+fn resume_countdown_system(
+    time: Res<Time<Real>>,                                    // For the countdown, unaffected by slow motion
+    mut auto_pause: ResMut<AutoPause>,                        // Auto-pause state
+    mut next_state: ResMut<NextState<GameState>>,             // For switching state
+) {
+    let Some(countdown) = auto_pause.countdown.as_mut() else {
+        return;
+    };
+    if countdown.tick(time.delta()).finished() {
+        auto_pause.engaged = false;
+        auto_pause.countdown = None;
+        next_state.set(GameState::Playing);
+    }
+}
+
+// Startup system putting the game on the practice range straight away when started with `--practice`
+fn game_mode_args_system(
+    cli_args: Res<CliArgs>,                                   // Options the game was launched with
+    mut next_mode: ResMut<NextState<GameMode>>,               // For switching mode
+) {
+    if cli_args.practice {
+        next_mode.set(GameMode::Practice);
+    }
+}
+
+// System to throw away the current run and spawn fresh ships
+fn new_game_system(
+    mut commands: Commands,                                   // For spawning and despawning
+    mut new_game_events: EventReader<NewGameEvent>,           // Requests for a new run
+    ship_assets: Res<ShipAssets>,                             // The ship's sprite sheet
+    trail_settings: Res<TrailSettings>,                       // For the ship's trail length
+    loadout: Res<Loadout>,                                    // Starting values of the ships
+    gameplay_query: Query<Entity, With<GameplayEntity>>,      // Get the old run
+    player_count: Res<PlayerCount>,                           // How many ships to spawn
+) {
+    if new_game_events.read().count() == 0 {
+        return;
+    }
+    for entity in gameplay_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for id in player_count.ids() {
+        let position = spawn_point(id, &player_count);
+        spawn_ship(&mut commands, &ship_assets, &trail_settings, &loadout, Player { id }, position);
+    }
+}
+
+// System to start playing again once a new run is asked for after a game over
+fn leave_game_over_system(
+    mut new_game_events: EventReader<NewGameEvent>,           // Requests for a new run
+    state: Res<State<GameState>>,                             // The current state
+    mut next_state: ResMut<NextState<GameState>>,             // For switching state
+) {
+    if new_game_events.read().count() > 0 && *state.get() == GameState::GameOver {
+        next_state.set(GameState::Playing);
+    }
+}
+
+// System to restart the random number generator for every new run
+fn reseed_rng_system(
+    mut new_game_events: EventReader<NewGameEvent>,           // Requests for a new run
+    game_settings: Res<GameSettings>,                         // Default seed
+    mut rng: ResMut<GameRng>,                                 // The generator to restart
+) {
+    for event in new_game_events.read() {
+        rng.reseed(event.seed.unwrap_or(game_settings.seed));
+        info!("Game seed: {}", rng.seed);
+    }
+}
+
+// System to restore bombs and clear the score when a new run starts
+fn reset_run_state_system(
+    mut new_game_events: EventReader<NewGameEvent>,           // Requests for a new run
+    mut score: ResMut<Score>,                                 // Score to clear
+    mut bombs: ResMut<Bombs>,                                 // Bombs to restore
+    mut bomb_settings: ResMut<BombSettings>,                  // Cooldown to restore
+    mut lives: ResMut<Lives>,                                 // Lives to restore
+    mut respawn: ResMut<Respawn>,                             // Respawn to cancel
+    mut contacts: ResMut<ShipContacts>,                       // Contacts from the old run
+) {
+    if new_game_events.read().count() == 0 {
+        return;
+    }
+    *score = Score::default();
+    *bombs = Bombs::default();
+    *lives = Lives::default();
+    *respawn = Respawn::default();
+    contacts.0.clear();
+    let cooldown = bomb_settings.cooldown.duration();
+    bomb_settings.cooldown.tick(cooldown);
+}
+
+// Plugin for the state of a run
+pub struct SessionPlugin;
+
+impl Plugin for SessionPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GameSettings>()
+            .register_type::<Score>()
+            .register_type::<Lives>()
+            .register_type::<AutoPause>()
+            .register_type::<TimeScale>()
+            .init_resource::<GameSettings>()
+            .init_resource::<AutoPause>()
+            .init_resource::<TimeScale>()
+            .init_resource::<GameRng>()
+            .init_resource::<Score>()
+            .init_resource::<Lives>()
+            .add_state::<GameState>()
+            .add_state::<GameMode>()
+            .add_event::<NewGameEvent>()
+            .add_systems(Startup, game_mode_args_system)
+            .add_systems(Update, (
+                new_game_system,
+                leave_game_over_system,
+                reseed_rng_system,
+                reset_run_state_system,
+                pause_toggle_system,
+                auto_pause_settings_system.run_if(resource_changed::<Settings>()),
+                focus_pause_system,
+                time_scale_system,
+                resume_countdown_system.run_if(in_state(GameState::Paused)),
+            ));
+    }
+}
@@ -0,0 +1,250 @@
+// Player preferences kept between runs of the game
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowMode, WindowResized};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use crate::cli::CliArgs;
+use crate::graphics::GraphicsSettings;
+use crate::input::ControlScheme;
+use crate::palette::ColorPreset;
+use crate::storage::{self, data_path};
+
+// Seconds the settings have to stay unchanged before they are written out
+const SAVE_DELAY: f32 = 1.0;
+
+// Size and mode of the game window
+#[derive(Reflect, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct WindowSettings {
+    pub width: f32,        // Width of the window when not fullscreen, in logical pixels
+    pub height: f32,       // Height of the window when not fullscreen, in logical pixels
+    pub fullscreen: bool,  // Whether the window fills the screen
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            width: 1280.0,
+            height: 720.0,
+            fullscreen: false,
+        }
+    }
+}
+
+// Resource holding every preference the player can change; the systems that depend on one
+// follow this resource, so editing it is all a settings menu has to do
+#[derive(Reflect, Resource, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Settings {
+    pub control_scheme: ControlScheme,  // Keys a lone player flies with
+    pub toggle_thrust: bool,            // Tap thrust to latch the engine on, tap again to cut it
+    pub strafing: bool,                 // Whether the strafe keys push the ship sideways
+    pub aim_assist: bool,               // Whether the lead reticle is drawn
+    pub color_scheme: ColorPreset,      // Colors the game is drawn in
+    pub auto_fire: bool,                // Fire whenever the weapon is ready, without holding the button
+    pub difficulty_ramp: bool,          // Whether hostiles get tougher the longer a run lasts
+    pub auto_pause: bool,               // Whether losing focus pauses the game
+    pub resume_delay: f32,              // Seconds counted down after refocusing, 0 to resume at once
+    pub auto_resume: bool,              // Whether refocusing resumes by itself, or waits for the pause key
+    pub smooth_motion: bool,            // Whether ships, shots and hostiles are drawn between the simulation's ticks
+    pub window: WindowSettings,         // Size of the window
+    pub graphics: GraphicsSettings,     // Present mode and frame rate cap
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            control_scheme: ControlScheme::default(),
+            toggle_thrust: false,
+            strafing: true,
+            aim_assist: true,
+            color_scheme: ColorPreset::default(),
+            auto_fire: false,
+            difficulty_ramp: true,
+            auto_pause: true,
+            resume_delay: 3.0,
+            auto_resume: true,
+            smooth_motion: true,
+            window: WindowSettings::default(),
+            graphics: GraphicsSettings::default(),
+        }
+    }
+}
+
+impl Settings {
+    // Write the settings to a RON file
+    fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        storage::write(path, &ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?)?;
+        Ok(())
+    }
+
+    // Read the settings back from a RON file; missing fields take their defaults, and so do
+    // fields that don't parse, without losing the ones that do
+    fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = storage::read(path)?;
+        if let Ok(settings) = ron::from_str(&text) {
+            return Ok(settings);
+        }
+        let ron::Value::Map(fields) = ron::from_str(&text)? else {
+            return Err("settings file doesn't hold a set of fields".into());
+        };
+        let mut valid = ron::Map::new();
+        for (key, value) in fields.iter() {
+            let mut single = ron::Map::new();
+            single.insert(key.clone(), value.clone());
+            match Self::deserialize(ron::Value::Map(single)) {
+                Ok(_) => {
+                    valid.insert(key.clone(), value.clone());
+                }
+                Err(err) => warn!("Ignoring the setting {:?} in {}: {}", key, path.display(), err),
+            }
+        }
+        Ok(Self::deserialize(ron::Value::Map(valid))?)
+    }
+
+    // Read the settings, falling back to the defaults when there are none yet or they can't be read
+    fn load_or_default(path: &Path) -> Self {
+        if !storage::exists(path) {
+            return Self::default();
+        }
+        Self::load(path).unwrap_or_else(|err| {
+            warn!("Failed to read the settings from {}, using the defaults: {}", path.display(), err);
+            Self::default()
+        })
+    }
+}
+
+// Where the settings live
+fn settings_path() -> PathBuf {
+    data_path("settings.ron")
+}
+
+// Resource holding back the write of changed settings until they settle
+#[derive(Resource)]
+struct SettingsSaver {
+    pending: bool,  // Whether there are changes not yet written
+    delay: Timer,   // Time since the last change, reset by every new one
+}
+
+impl Default for SettingsSaver {
+    fn default() -> Self {
+        Self {
+            pending: false,
+            delay: Timer::from_seconds(SAVE_DELAY, TimerMode::Once),
+        }
+    }
+}
+
+// System to write the settings out once they have stopped changing for a moment
+fn settings_save_system(
+    time: Res<Time<Real>>,                                    // For the delay, even while paused
+    settings: Res<Settings>,                                  // The settings to write
+    mut saver: ResMut<SettingsSaver>,                         // Whether a write is due
+) {
+    if settings.is_changed() && !settings.is_added() {
+        saver.pending = true;
+        saver.delay.reset();
+    }
+    if !saver.pending || !saver.delay.tick(time.delta()).finished() {
+        return;
+    }
+    saver.pending = false;
+    let path = settings_path();
+    match settings.save(&path) {
+        Ok(()) => info!("Saved the settings to {}", path.display()),
+        Err(err) => warn!("Failed to save the settings to {}: {}", path.display(), err),
+    }
+}
+
+// System to switch between fullscreen and a window on Alt+Enter
+fn fullscreen_toggle_system(
+    keyboard_input: Res<Input<KeyCode>>,                      // For keyboard input
+    mut settings: ResMut<Settings>,                           // Where the mode is kept
+) {
+    if keyboard_input.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]) && keyboard_input.just_pressed(KeyCode::Return) {
+        settings.window.fullscreen = !settings.window.fullscreen;
+    }
+}
+
+// System to size the window and switch its mode whenever the settings change
+fn window_settings_system(
+    settings: Res<Settings>,                                  // The chosen window settings
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>, // Get the window to change
+    mut windowed_position: Local<Option<WindowPosition>>,     // Where the window was before going fullscreen
+) {
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+    // Back in a window it goes where it was, at the size kept in the settings
+    if settings.window.fullscreen != (window.mode != WindowMode::Windowed) {
+        if settings.window.fullscreen {
+            *windowed_position = Some(window.position);
+            window.mode = WindowMode::BorderlessFullscreen;
+        } else {
+            window.mode = WindowMode::Windowed;
+            if let Some(position) = windowed_position.take() {
+                window.position = position;
+            }
+        }
+    }
+    // A fullscreen window keeps the size of the screen, and a canvas the size of its page
+    let size = Vec2::new(settings.window.width, settings.window.height);
+    if window.mode == WindowMode::Windowed && !cfg!(target_arch = "wasm32")
+        && size.cmpgt(Vec2::ZERO).all() && (Vec2::new(window.width(), window.height()) - size).abs().max_element() > 1.0 {
+        window.resolution.set(size.x, size.y);
+    }
+}
+
+// System to remember the size the player gave the window
+fn window_resize_system(
+    mut resize_events: EventReader<WindowResized>,            // Window size changes
+    primary_query: Query<(Entity, &Window), With<PrimaryWindow>>, // Get the primary window
+    mut settings: ResMut<Settings>,                           // Where the size is kept
+) {
+    // The size of a fullscreen window is the screen's, and a canvas's is its page's, not a choice worth keeping
+    let Ok((primary, primary_window)) = primary_query.get_single() else {
+        return;
+    };
+    if primary_window.mode != WindowMode::Windowed || cfg!(target_arch = "wasm32") {
+        return;
+    }
+    let Some(event) = resize_events.read().filter(|event| event.window == primary).last() else {
+        return;
+    };
+    let window = WindowSettings {
+        width: event.width,
+        height: event.height,
+        fullscreen: settings.window.fullscreen,
+    };
+    if settings.window != window {
+        settings.window = window;
+    }
+}
+
+// Plugin for the settings file
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        // Loaded straight away so every other plugin starts from the player's choices,
+        // with a window size or fullscreen given on the command line taking over from the saved one
+        let mut settings = Settings::load_or_default(&settings_path());
+        if let Some(cli_args) = app.world.get_resource::<CliArgs>() {
+            settings.window.width = cli_args.width.unwrap_or(settings.window.width);
+            settings.window.height = cli_args.height.unwrap_or(settings.window.height);
+            settings.window.fullscreen |= cli_args.fullscreen;
+        }
+        app.register_type::<Settings>()
+            .insert_resource(settings)
+            .init_resource::<SettingsSaver>()
+            .add_systems(Update, (
+                fullscreen_toggle_system,
+                window_resize_system,
+                window_settings_system.run_if(resource_changed::<Settings>()),
+                settings_save_system,
+            ).chain());
+    }
+}
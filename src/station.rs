@@ -0,0 +1,155 @@
+// A station ships dock with to refuel and repair in safety
+use bevy::prelude::*;
+
+use crate::combat::{Health, Invulnerable};
+use crate::input::{PlayerInputSet, PlayerInputs};
+use crate::physics::Velocity;
+use crate::player::{Engine, Player, Starship};
+use crate::session::GameState;
+use crate::GameSet;
+
+// Where the station sits in the arena
+const STATION_POSITION: Vec2 = Vec2::new(-400.0, 200.0);
+
+// Define the Station component for the place ships dock with
+#[derive(Reflect, Component)]
+#[reflect(Component)]
+pub struct Station {
+    pub dock_radius: f32,     // How close a ship has to come to dock, and how far it goes to undock
+    pub max_dock_speed: f32,  // Fastest a ship may go to dock; faster ships bounce off
+    pub refuel_rate: f32,     // Fuel per second put into a docked ship
+    pub repair_rate: f32,     // Hull per second repaired on a docked ship
+}
+
+impl Default for Station {
+    fn default() -> Self {
+        Self {
+            dock_radius: 60.0,
+            max_dock_speed: 60.0,
+            refuel_rate: 200.0,
+            repair_rate: 10.0,
+        }
+    }
+}
+
+// Define the Docked component for ships parked at a station, out of harm's way
+#[derive(Component)]
+pub struct Docked {
+    pub station: Entity,  // The station docked with
+}
+
+// Setup system that places the station
+fn setup_station(mut commands: Commands) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgb(0.4, 0.45, 0.55),
+                custom_size: Some(Vec2::splat(40.0)),
+                ..default()
+            },
+            // Drawn under the ships docking on it
+            transform: Transform::from_translation(STATION_POSITION.extend(-1.0)),
+            ..default()
+        },
+        Station::default(),
+    ));
+}
+
+// Components of a ship involved in docking
+type DockingShipData<'a> = (
+    Entity,
+    &'a Transform,
+    &'a mut Velocity,
+    &'a mut Engine,
+    &'a mut Health,
+    &'a Player,
+    Option<&'a Docked>,
+);
+
+// System to dock ships that come in slowly with the dock key held, bounce off the ones that come
+// in too fast, look after docked ships and let them go once they move away
+fn dock_system(
+    mut commands: Commands,                                   // For docking and undocking
+    time: Res<Time>,                                          // For refuelling and repairing
+    player_inputs: Res<PlayerInputs>,                         // Who holds the dock key
+    station_query: Query<(Entity, &Transform, &Station)>,     // Get the stations
+    mut ship_query: Query<DockingShipData, With<Starship>>,   // Get the ships
+) {
+    for (ship, transform, mut velocity, mut engine, mut health, player, docked) in ship_query.iter_mut() {
+        let position = transform.translation.truncate();
+
+        if let Some(docked) = docked {
+            // A station that went away lets its ships go as well
+            let Ok((_, station_transform, station)) = station_query.get(docked.station) else {
+                commands.entity(ship).remove::<(Docked, Invulnerable)>();
+                continue;
+            };
+            if position.distance(station_transform.translation.truncate()) > station.dock_radius {
+                commands.entity(ship).remove::<(Docked, Invulnerable)>();
+                info!("Player {} undocked", player.id + 1);
+                continue;
+            }
+            engine.fuel = (engine.fuel + station.refuel_rate * time.delta_seconds()).min(engine.max_fuel);
+            health.current = (health.current + station.repair_rate * time.delta_seconds()).min(health.max);
+            continue;
+        }
+
+        for (station_entity, station_transform, station) in station_query.iter() {
+            let offset = position - station_transform.translation.truncate();
+            if offset.length() > station.dock_radius {
+                continue;
+            }
+            let speed = velocity.as_vec2().length();
+            if speed > station.max_dock_speed {
+                // Too fast to dock: bounce off, keeping any motion along the station's edge
+                let normal = offset.normalize_or_zero();
+                let inward = velocity.as_vec2().dot(normal);
+                if inward < 0.0 {
+                    let bounced = velocity.as_vec2() - 2.0 * inward * normal;
+                    velocity.x = bounced.x;
+                    velocity.y = bounced.y;
+                }
+            } else if player_inputs[player.id].dock {
+                // Park on the station; thrusting away undocks again
+                velocity.x = 0.0;
+                velocity.y = 0.0;
+                commands.entity(ship).insert((Docked { station: station_entity }, Invulnerable));
+                info!("Player {} docked", player.id + 1);
+            }
+            break;
+        }
+    }
+}
+
+// System to draw the docking range around every station, lit up while a ship is docked
+fn station_range_system(
+    mut gizmos: Gizmos,                                       // For drawing the range
+    station_query: Query<(Entity, &Transform, &Station)>,     // Get the stations
+    docked_query: Query<&Docked>,                             // Get the docked ships
+) {
+    for (entity, transform, station) in station_query.iter() {
+        let occupied = docked_query.iter().any(|docked| docked.station == entity);
+        let color = if occupied {
+            Color::rgba(0.3, 1.0, 0.4, 0.8)
+        } else {
+            Color::rgba(0.6, 0.7, 0.9, 0.4)
+        };
+        gizmos.circle_2d(transform.translation.truncate(), station.dock_radius, color);
+    }
+}
+
+// Plugin for the docking station
+pub struct StationPlugin;
+
+impl Plugin for StationPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Station>()
+            .add_systems(Startup, setup_station)
+            .add_systems(Update, station_range_system)
+            // Checked once the ships have moved this tick, like any other contact
+            .add_systems(FixedUpdate, dock_system
+                .after(PlayerInputSet)
+                .in_set(GameSet::Collision)
+                .run_if(in_state(GameState::Playing)));
+    }
+}
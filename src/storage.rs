@@ -0,0 +1,117 @@
+// Keeping files between runs: on disk natively, and in the browser's local storage on the web,
+// where there is no file system to write to
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+// Where a file the game keeps between runs lives: the platform's data directory, or next to the game without one
+pub fn data_path(file_name: &str) -> PathBuf {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let data_dir = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home.map(|home| home.join("Library").join("Application Support"))
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.map(|home| home.join(".local").join("share")))
+    };
+    data_dir.unwrap_or_else(|| PathBuf::from(".")).join("star-ship_game").join(file_name)
+}
+
+// Where a file in the assets folder is, for reading it directly rather than through the asset server
+pub fn asset_path(file_name: &str) -> PathBuf {
+    #[cfg(not(target_arch = "wasm32"))]
+    let base = bevy::asset::io::file::FileAssetReader::get_base_path();
+    // The browser fetches assets relative to the page
+    #[cfg(target_arch = "wasm32")]
+    let base = PathBuf::new();
+    base.join("assets").join(file_name)
+}
+
+// Whether anything was written to a path yet
+pub fn exists(path: &Path) -> bool {
+    backend::exists(path)
+}
+
+// Read back what was written to a path
+pub fn read(path: &Path) -> Result<String, Box<dyn Error>> {
+    backend::read(path)
+}
+
+// Write to a path, replacing what was there and creating its directory on the first write
+pub fn write(path: &Path, contents: &str) -> Result<(), Box<dyn Error>> {
+    backend::write(path, contents)
+}
+
+// Plain files next to the game or in the platform's data directory
+#[cfg(not(target_arch = "wasm32"))]
+mod backend {
+    use std::error::Error;
+    use std::fs;
+    use std::path::Path;
+
+    pub fn exists(path: &Path) -> bool {
+        path.exists()
+    }
+
+    pub fn read(path: &Path) -> Result<String, Box<dyn Error>> {
+        Ok(fs::read_to_string(path)?)
+    }
+
+    pub fn write(path: &Path, contents: &str) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+// The page's local storage, one entry per path; it answers straight away, so nothing waits on disk
+#[cfg(target_arch = "wasm32")]
+mod backend {
+    use std::error::Error;
+    use std::path::Path;
+    use web_sys::Storage;
+
+    // The page's local storage, missing when the browser has it switched off
+    fn local_storage() -> Result<Storage, Box<dyn Error>> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or_else(|| "local storage is not available".into())
+    }
+
+    // Entry a path is kept under
+    fn key(path: &Path) -> String {
+        path.to_string_lossy().into_owned()
+    }
+
+    pub fn exists(path: &Path) -> bool {
+        local_storage().is_ok_and(|storage| storage.get_item(&key(path)).ok().flatten().is_some())
+    }
+
+    pub fn read(path: &Path) -> Result<String, Box<dyn Error>> {
+        local_storage()?
+            .get_item(&key(path))
+            .map_err(|err| format!("{:?}", err))?
+            .ok_or_else(|| format!("nothing stored for {}", path.display()).into())
+    }
+
+    pub fn write(path: &Path, contents: &str) -> Result<(), Box<dyn Error>> {
+        // Fails when the storage is full
+        local_storage()?.set_item(&key(path), contents).map_err(|err| format!("{:?}", err).into())
+    }
+}
+
+// Seconds since the Unix epoch; the system clock isn't reachable from the browser, so the page's is used there
+pub fn unix_time() -> u64 {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs())
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        (web_sys::js_sys::Date::now() / 1000.0) as u64
+    }
+}
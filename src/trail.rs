@@ -0,0 +1,115 @@
+// Fading trails drawn behind the ship and its projectiles
+use bevy::prelude::*;
+
+use crate::physics::InterpolationSet;
+use crate::pool::Pooled;
+use crate::session::GameState;
+use crate::weapons::Projectile;
+
+// Define the Trail component recording where an entity has recently been
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Trail {
+    pub points: Vec<Vec2>,  // Recent positions, oldest first
+    pub max_len: usize,     // How many positions are kept before old ones drop off
+}
+
+impl Trail {
+    // Create an empty trail keeping up to max_len points
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            points: Vec::with_capacity(max_len + 1),
+            max_len,
+        }
+    }
+
+    // Record a new position and drop the oldest ones past max_len
+    pub fn push(&mut self, point: Vec2, teleport_distance: f32) {
+        // A big jump means the entity teleported (screen wrap, dash), so start over
+        // instead of drawing a line across the screen
+        if let Some(last) = self.points.last() {
+            if last.distance(point) > teleport_distance {
+                self.points.clear();
+            }
+        }
+        self.points.push(point);
+        if self.points.len() > self.max_len {
+            let excess = self.points.len() - self.max_len;
+            self.points.drain(..excess);
+        }
+    }
+}
+
+// Resource controlling how trails look
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct TrailSettings {
+    pub ship_length: usize,        // Points kept behind the ship
+    pub projectile_length: usize,  // Points kept behind each projectile
+    pub fade: f32,                 // Opacity at the head, fading to nothing at the tail
+    pub teleport_distance: f32,    // Jumps longer than this clear the trail
+    pub ship_color: Color,         // Color of the ship's trail
+    pub projectile_color: Color,   // Color of projectile trails
+}
+
+impl Default for TrailSettings {
+    fn default() -> Self {
+        Self {
+            ship_length: 40,
+            projectile_length: 8,
+            fade: 0.8,
+            teleport_distance: 100.0,
+            ship_color: Color::rgb(0.4, 0.7, 1.0),
+            projectile_color: Color::rgb(1.0, 0.9, 0.3),
+        }
+    }
+}
+
+// System to record the latest position of every entity with a trail, leaving out projectiles
+// waiting in the pool
+fn trail_record_system(
+    mut query: Query<(&Transform, &mut Trail, Option<&Pooled>)>, // Get positions and trails
+    trail_settings: Res<TrailSettings>,                       // For the teleport threshold
+) {
+    for (transform, mut trail, pooled) in query.iter_mut() {
+        if pooled.is_some_and(|pooled| !pooled.in_use()) {
+            continue;
+        }
+        trail.push(transform.translation.truncate(), trail_settings.teleport_distance);
+    }
+}
+
+// System to draw trails as polylines fading towards their oldest point
+fn trail_draw_system(
+    mut gizmos: Gizmos,                                       // For drawing lines
+    query: Query<(&Trail, Has<Projectile>)>,                  // Get trails and what owns them
+    trail_settings: Res<TrailSettings>,                       // For colors and fade
+) {
+    for (trail, is_projectile) in query.iter() {
+        if trail.points.len() < 2 {
+            continue;
+        }
+        let color = if is_projectile {
+            trail_settings.projectile_color
+        } else {
+            trail_settings.ship_color
+        };
+        let len = trail.points.len() as f32;
+        gizmos.linestrip_gradient_2d(trail.points.iter().enumerate().map(|(i, point)| {
+            (*point, color.with_a(trail_settings.fade * (i + 1) as f32 / len))
+        }));
+    }
+}
+
+// Plugin for recording and drawing trails
+pub struct TrailPlugin;
+
+impl Plugin for TrailPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Trail>()
+            .register_type::<TrailSettings>()
+            .init_resource::<TrailSettings>()
+            .add_systems(Update, trail_record_system.after(InterpolationSet).run_if(in_state(GameState::Playing)))
+            .add_systems(Update, trail_draw_system);
+    }
+}
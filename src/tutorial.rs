@@ -0,0 +1,234 @@
+// Hints teaching new players the controls during their first game
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::Path;
+
+use crate::input::{Action, KeyBindings, PlayerInput, PlayerInputs};
+use crate::session::GameState;
+use crate::storage;
+
+// File remembering whether the tutorial has been finished
+const TUTORIAL_PATH: &str = "tutorial.ron";
+
+// Key skipping the rest of the tutorial
+const TUTORIAL_SKIP_KEY: KeyCode = KeyCode::Return;
+
+// Settings kept between runs of the game
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+struct TutorialSettings {
+    completed: bool,  // Whether the tutorial was finished or skipped
+}
+
+impl TutorialSettings {
+    // Write the settings to a RON file
+    fn save(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        storage::write(Path::new(path), &ron::to_string(self)?)?;
+        Ok(())
+    }
+
+    // Read the settings back from a RON file
+    fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(ron::from_str(&storage::read(Path::new(path))?)?)
+    }
+}
+
+// One thing the tutorial teaches, in the order they are shown
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Hint {
+    Thrust,  // Fire the engine
+    Fire,    // Fire the weapon
+}
+
+impl Hint {
+    // Every hint, in the order they are shown
+    pub const ALL: [Hint; 2] = [Hint::Thrust, Hint::Fire];
+
+    // The action the hint asks the player to perform
+    fn action(self) -> Action {
+        match self {
+            Hint::Thrust => Action::Thrust,
+            Hint::Fire => Action::Fire,
+        }
+    }
+
+    // What the hint calls the action
+    fn verb(self) -> &'static str {
+        match self {
+            Hint::Thrust => "thrust",
+            Hint::Fire => "fire",
+        }
+    }
+
+    // Whether the player's intent this frame performs the action
+    pub fn performed(self, input: &PlayerInput) -> bool {
+        match self {
+            Hint::Thrust => input.thrust > 0.0,
+            Hint::Fire => input.fire,
+        }
+    }
+}
+
+// Where the tutorial is up to
+#[derive(Reflect, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TutorialStage {
+    #[default]
+    Waiting,        // Counting down to the next hint
+    Showing(Hint),  // A hint is on screen until its action is performed
+    Done,           // Every hint is completed, or the tutorial was skipped
+}
+
+// Resource tracking the tutorial's progress
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct Tutorial {
+    pub stage: TutorialStage,  // Where the tutorial is up to
+    pub completed: [bool; 2],  // Which hints are done, in the order of Hint::ALL
+    delay: Timer,              // Time until the next hint appears
+}
+
+impl Default for Tutorial {
+    fn default() -> Self {
+        Self {
+            stage: TutorialStage::Waiting,
+            completed: [false; Hint::ALL.len()],
+            delay: Timer::from_seconds(2.0, TimerMode::Once),
+        }
+    }
+}
+
+impl Tutorial {
+    // The first hint still to be completed
+    pub fn current(&self) -> Option<Hint> {
+        Hint::ALL.into_iter().zip(self.completed).find(|(_, completed)| !completed).map(|(hint, _)| hint)
+    }
+
+    // Whether the player has performed the hint's action
+    pub fn is_completed(&self, hint: Hint) -> bool {
+        self.completed[hint as usize]
+    }
+
+    // Mark every hint whose action the input performs as completed
+    pub fn observe(&mut self, input: &PlayerInput) {
+        for (hint, completed) in Hint::ALL.into_iter().zip(self.completed.iter_mut()) {
+            *completed |= hint.performed(input);
+        }
+    }
+}
+
+// Marker for the text showing the current hint
+#[derive(Component)]
+struct TutorialText;
+
+// Setup system that loads whether the tutorial is still to be played and builds its text
+fn setup_tutorial(mut commands: Commands, mut tutorial: ResMut<Tutorial>) {
+    // Without a settings file this is the first playthrough
+    if TutorialSettings::load(TUTORIAL_PATH).is_ok_and(|settings| settings.completed) {
+        tutorial.stage = TutorialStage::Done;
+    }
+
+    // Spawn the hint text along the bottom of the screen, hidden until a hint is due
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                bottom: Val::Px(40.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        })
+        .insert(TutorialText)
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::rgb(0.6, 0.9, 1.0),
+                    ..default()
+                },
+            ));
+        });
+}
+
+// System to step through the hints as the first player performs them, or skip them all
+fn tutorial_system(
+    time: Res<Time<Real>>,                                    // For the delay between hints, however fast the game runs
+    keyboard_input: Res<Input<KeyCode>>,                      // For skipping the tutorial
+    player_inputs: Res<PlayerInputs>,                         // This frame's intent of every player
+    mut tutorial: ResMut<Tutorial>,                           // Progress to update
+) {
+    if tutorial.stage == TutorialStage::Done {
+        return;
+    }
+    if keyboard_input.just_pressed(TUTORIAL_SKIP_KEY) {
+        tutorial.completed = [true; Hint::ALL.len()];
+    }
+    tutorial.observe(&player_inputs[0]);
+
+    let stage = tutorial.stage;
+    tutorial.stage = match stage {
+        TutorialStage::Waiting if tutorial.delay.tick(time.delta()).finished() => {
+            tutorial.current().map_or(TutorialStage::Done, TutorialStage::Showing)
+        }
+        // Leave a pause before the next hint once this one is done
+        TutorialStage::Showing(hint) if tutorial.is_completed(hint) => {
+            tutorial.delay.reset();
+            TutorialStage::Waiting
+        }
+        stage => stage,
+    };
+
+    if tutorial.current().is_none() {
+        // Remember the tutorial is done so later runs start straight into the game
+        tutorial.stage = TutorialStage::Done;
+        match (TutorialSettings { completed: true }).save(TUTORIAL_PATH) {
+            Ok(()) => info!("Tutorial completed"),
+            Err(err) => error!("Failed to save tutorial progress to {}: {}", TUTORIAL_PATH, err),
+        }
+    }
+}
+
+// System to show the current hint, naming the key bound to its action
+fn tutorial_text_system(
+    tutorial: Res<Tutorial>,                                  // Progress to show
+    key_bindings: Res<KeyBindings>,                           // Keys to name in the hint
+    mut node_query: Query<(&mut Visibility, &Children), With<TutorialText>>, // Get the hint banner
+    mut text_query: Query<&mut Text>,                         // Get the words on the banner
+) {
+    let Ok((mut visibility, children)) = node_query.get_single_mut() else {
+        return;
+    };
+    let TutorialStage::Showing(hint) = tutorial.stage else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    *visibility = Visibility::Inherited;
+    let key = key_bindings
+        .first()
+        .and_then(|input_map| input_map.keys(hint.action()).first())
+        .map_or_else(|| "the button".to_string(), |key| format!("{:?}", key));
+    for &child in children.iter() {
+        if let Ok(mut text) = text_query.get_mut(child) {
+            text.sections[0].value = format!("Press {} to {}   ({:?} to skip)", key, hint.verb(), TUTORIAL_SKIP_KEY);
+        }
+    }
+}
+
+// Plugin for the first-game tutorial
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Tutorial>()
+            .register_type::<Hint>()
+            .init_resource::<Tutorial>()
+            .add_systems(Startup, setup_tutorial)
+            .add_systems(Update, (
+                tutorial_system,
+                tutorial_text_system,
+            ).chain().run_if(in_state(GameState::Playing)));
+    }
+}
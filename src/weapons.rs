@@ -0,0 +1,709 @@
+// The ship's gun, its ammo and heat, and the smart bomb
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{DamageEvent, DamageSource, Health};
+use crate::config::GameConfig;
+use crate::hostiles::Hostile;
+use crate::input::{PlayerInputSet, PlayerInputs};
+use crate::palette::Tint;
+use crate::physics::{Collider, Interpolated, InterpolationSet, Velocity, intercept_time, ray_circle_distance};
+use crate::player::{Player, Starship};
+use crate::pool::{PoolKind, Pooled, release_pooled, spawn_pooled};
+use crate::session::{GameState, GameplayEntity};
+use crate::settings::Settings;
+use crate::trail::{Trail, TrailSettings};
+use crate::GameSet;
+
+// The kinds of weapon the ship can carry
+#[derive(Reflect, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WeaponType {
+    #[default]
+    Standard,  // Basic cannon
+    Boosted,   // Faster, harder-hitting shots that cost more energy
+    Beam,      // Continuous beam that burns whatever it touches while fire is held
+}
+
+impl WeaponType {
+    // Multiplier applied to the ammo pool's base cost for each shot
+    pub fn cost_multiplier(self) -> f32 {
+        match self {
+            WeaponType::Standard => 1.0,
+            WeaponType::Boosted => 2.5,
+            // The beam drains energy by the second instead, see BeamSettings
+            WeaponType::Beam => 0.0,
+        }
+    }
+
+    // Damage dealt by a single projectile
+    pub fn damage(self) -> f32 {
+        match self {
+            WeaponType::Standard => 10.0,
+            WeaponType::Boosted => 25.0,
+            // The beam deals damage by the second instead, see BeamSettings
+            WeaponType::Beam => 0.0,
+        }
+    }
+
+    // How many targets a single projectile can hit before it is used up
+    pub fn pierce(self) -> u32 {
+        match self {
+            WeaponType::Standard => 1,
+            WeaponType::Boosted => 2,
+            // The beam stops at the first target it touches
+            WeaponType::Beam => 1,
+        }
+    }
+
+    // Whether holding fire keeps shooting, or each shot needs its own press
+    pub fn full_auto(self) -> bool {
+        match self {
+            WeaponType::Standard => true,
+            // Heavy shots are aimed one at a time
+            WeaponType::Boosted => false,
+            WeaponType::Beam => true,
+        }
+    }
+}
+
+// Define the Weapon component that lets an entity shoot
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Weapon {
+    pub weapon_type: WeaponType,  // Which kind of weapon is equipped
+    pub cooldown: Timer,          // Minimum time between two shots
+    pub trigger_held: bool,       // Whether fire was already held on the last tick
+    pub damage_bonus: f32,        // Extra damage on top of the weapon type's, as a fraction of it
+}
+
+impl Weapon {
+    // Damage dealt by a single projectile, bonus included
+    pub fn damage(&self) -> f32 {
+        self.weapon_type.damage() * (1.0 + self.damage_bonus)
+    }
+}
+
+// Define the AmmoPool component holding the energy weapons draw from
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct AmmoPool {
+    pub energy: f32,         // Energy currently available
+    pub max: f32,            // Maximum energy the pool can hold
+    pub cost_per_shot: f32,  // Base energy cost of one shot
+    pub recharge: f32,       // Energy regained per second while not firing
+}
+
+impl AmmoPool {
+    // Energy needed to fire one shot with the given weapon type
+    pub fn shot_cost(&self, weapon_type: WeaponType) -> f32 {
+        self.cost_per_shot * weapon_type.cost_multiplier()
+    }
+
+    // Spend energy for one shot, returning false when there isn't enough
+    pub fn try_consume(&mut self, weapon_type: WeaponType) -> bool {
+        let cost = self.shot_cost(weapon_type);
+        if self.energy < cost {
+            return false;
+        }
+        self.energy -= cost;
+        true
+    }
+
+    // Spend energy drained continuously, returning false when there isn't enough
+    pub fn try_drain(&mut self, amount: f32) -> bool {
+        if self.energy < amount {
+            return false;
+        }
+        self.energy -= amount;
+        true
+    }
+
+    // Regain energy over time without going past the maximum
+    pub fn recharge(&mut self, delta_seconds: f32) {
+        self.energy = (self.energy + self.recharge * delta_seconds).min(self.max);
+    }
+}
+
+// Define the Heat component that builds up with sustained firing
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Heat {
+    pub current: f32,       // Heat currently built up
+    pub max: f32,           // Heat at which the weapon overheats
+    pub cool_rate: f32,     // Heat shed per second while not firing
+    pub per_shot: f32,      // Heat added by each shot
+    pub resume_below: f32,  // Heat the weapon must drop under to unlock after overheating
+    pub overheated: bool,   // Whether firing is locked until the weapon cools down
+}
+
+// Fraction of max heat at which the HUD gauge turns red
+pub const HEAT_WARNING_FRACTION: f32 = 0.8;
+
+impl Heat {
+    // Add the heat of one shot, locking the weapon once it reaches the maximum
+    pub fn add_shot(&mut self) {
+        self.add(self.per_shot);
+    }
+
+    // Add heat, locking the weapon once it reaches the maximum
+    pub fn add(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.max);
+        if self.current >= self.max {
+            self.overheated = true;
+        }
+    }
+
+    // Shed heat over time, unlocking the weapon once it's cool enough
+    pub fn cool(&mut self, delta_seconds: f32) {
+        self.current = (self.current - self.cool_rate * delta_seconds).max(0.0);
+        if self.overheated && self.current < self.resume_below {
+            self.overheated = false;
+        }
+    }
+
+    // Fraction of the maximum heat currently built up
+    pub fn fraction(&self) -> f32 {
+        self.current / self.max
+    }
+}
+
+// Define the Turret component for the gun mounted on top of the ship
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Turret {
+    pub barrel_length: f32,  // Distance from the pivot to the muzzle, in local units
+}
+
+// Define the Projectile component for shots fired by a weapon
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Projectile {
+    pub damage: f32,      // Damage dealt on hit
+    pub lifetime: Timer,  // Despawns the projectile when finished
+    pub owner: usize,     // Id of the player who fired it
+    pub pierce: u32,      // Targets it can still hit before it is used up
+    pub hit: Vec<Entity>, // Targets already hit, which it passes through from then on
+}
+
+// Define the Beam component holding how far a ship's beam reaches this tick
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Beam {
+    pub length: f32,  // Distance from the muzzle to where the beam stops, 0.0 while it's off
+}
+
+// Resource configuring the beam weapon
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct BeamSettings {
+    pub range: f32,              // Furthest the beam reaches
+    pub damage_per_second: f32,  // Damage dealt to the target while it's held on it
+    pub energy_per_second: f32,  // Energy drained from the ammo pool while firing
+    pub heat_per_second: f32,    // Heat built up while firing
+}
+
+impl Default for BeamSettings {
+    fn default() -> Self {
+        Self {
+            range: 350.0,
+            damage_per_second: 40.0,
+            energy_per_second: 25.0,
+            heat_per_second: 35.0,
+        }
+    }
+}
+
+// Resource holding how many smart bombs the player has left
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct Bombs(pub u32);
+
+impl Default for Bombs {
+    fn default() -> Self {
+        Self(3)
+    }
+}
+
+// Resource configuring the smart bomb
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct BombSettings {
+    pub radius: f32,      // Everything closer than this to the ship is destroyed
+    pub cooldown: Timer,  // Time that must pass between two bombs
+}
+
+impl Default for BombSettings {
+    fn default() -> Self {
+        let mut cooldown = Timer::from_seconds(10.0, TimerMode::Once);
+        // The first bomb can be used straight away
+        cooldown.tick(cooldown.duration());
+        Self {
+            radius: 300.0,
+            cooldown,
+        }
+    }
+}
+
+// Define the BombBlast component for the expanding ring left by a smart bomb
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+struct BombBlast {
+    timer: Timer,  // How long the ring takes to reach full size
+    radius: f32,   // Size of the ring at the end of the timer
+}
+
+// System to point every turret where its player aims, independently of the hull, so a replay
+// aims exactly as the recorded run did
+fn turret_aim_system(
+    player_inputs: Res<PlayerInputs>,                         // Where every player aims
+    parent_query: Query<(&Transform, &Player), Without<Turret>>, // Get the hull carrying each turret
+    mut turret_query: Query<(&Parent, &mut Transform), With<Turret>>, // Get the turrets to aim
+) {
+    for (parent, mut transform) in turret_query.iter_mut() {
+        let Ok((hull, player)) = parent_query.get(parent.get()) else {
+            continue;
+        };
+        let Some(aim) = player_inputs[player.id].aim else {
+            continue;
+        };
+        // Undo the hull's rotation so the turret ignores where the hull is facing
+        transform.rotation = hull.rotation.inverse() * Quat::from_rotation_z(aim);
+    }
+}
+
+// Where a ship's shots leave from and the way they go: the turret's muzzle,
+// or the hull's nose without a turret
+fn muzzle(
+    transform: &Transform,                                    // The ship's hull
+    children: Option<&Children>,                              // The ship's children, which may hold the turret
+    turret_query: &Query<(&GlobalTransform, &Turret)>,        // Get where turrets point
+) -> (Vec2, Vec2) {
+    let turret = children
+        .into_iter()
+        .flatten()
+        .find_map(|child| turret_query.get(*child).ok());
+    match turret {
+        Some((turret_transform, turret)) => (
+            turret_transform.transform_point(Vec3::new(0.0, turret.barrel_length, 0.0)).truncate(),
+            turret_transform.up().truncate(),
+        ),
+        None => (transform.translation.truncate(), transform.up().truncate()),
+    }
+}
+
+// Resource switching on auto-fire, which keeps every ship's trigger held for players who can't
+// hold the fire button
+#[derive(Reflect, Resource, Default)]
+#[reflect(Resource)]
+pub struct AutoFire(pub bool);
+
+// The fire buttons of every player, held down for them while auto-fire is on
+#[derive(SystemParam)]
+struct Triggers<'w> {
+    player_inputs: Res<'w, PlayerInputs>,                     // This frame's intent of every player
+    auto_fire: Res<'w, AutoFire>,                             // Whether to fire without the button
+}
+
+impl Triggers<'_> {
+    // Whether a player's trigger is down; auto-fire lets go of it while the pool can't pay for a
+    // shot, so the energy comes back instead of the weapon running dry for good
+    fn held(&self, player_id: usize, ammo: &AmmoPool, weapon_type: WeaponType) -> bool {
+        self.player_inputs[player_id].fire
+            || (self.auto_fire.0 && ammo.energy >= ammo.shot_cost(weapon_type).max(ammo.cost_per_shot))
+    }
+}
+
+// Components of a shooter involved in firing its weapon
+type ShooterData<'a> = (
+    &'a Transform,
+    &'a Velocity,
+    &'a mut Weapon,
+    &'a mut AmmoPool,
+    Option<&'a mut Heat>,
+    Option<&'a Children>,
+    &'a Player,
+);
+
+// System to handle firing the ship's weapon
+fn fire_weapon_system(
+    mut commands: Commands,                                   // For spawning projectiles
+    time: Res<Time>,                                          // For cooldowns and recharge
+    mut query: Query<ShooterData>,                            // Get the shooters
+    turret_query: Query<(&GlobalTransform, &Turret)>,         // Get where turrets point
+    triggers: Triggers,                                       // Whose trigger is down
+    trail_settings: Res<TrailSettings>,                       // For projectile trail length
+    config: Res<GameConfig>,                                  // For the muzzle speeds
+) {
+    for (transform, velocity, mut weapon, mut ammo, mut heat, children, player) in query.iter_mut() {
+        let fire = triggers.held(player.id, &ammo, weapon.weapon_type);
+        // Let the weapon cool down between shots
+        weapon.cooldown.tick(time.delta());
+
+        // A semi-auto weapon only fires on the tick the trigger goes down, so one picked up
+        // while fire is held waits for the next press; auto-fire fires it on every cooldown
+        let trigger_held = std::mem::replace(&mut weapon.trigger_held, fire);
+        let trigger_ready = weapon.weapon_type.full_auto() || !trigger_held || triggers.auto_fire.0;
+
+        // Heat bleeds off while the trigger is released, or during a forced cooldown
+        if let Some(heat) = heat.as_mut() {
+            if !fire || heat.overheated {
+                heat.cool(time.delta_seconds());
+            }
+        }
+
+        // Energy only comes back while the trigger is released
+        if !fire {
+            ammo.recharge(time.delta_seconds());
+            continue;
+        }
+
+        // An overheated weapon stays locked until it has cooled down
+        if heat.as_ref().is_some_and(|heat| heat.overheated) {
+            continue;
+        }
+
+        // The beam burns continuously instead of firing shots
+        if weapon.weapon_type == WeaponType::Beam {
+            continue;
+        }
+
+        // Fire when the trigger allows it, the cooldown is over and the pool can pay for the shot
+        if !trigger_ready || !weapon.cooldown.finished() || !ammo.try_consume(weapon.weapon_type) {
+            continue;
+        }
+        weapon.cooldown.reset();
+        if let Some(heat) = heat.as_mut() {
+            heat.add_shot();
+        }
+
+        // Shoot from the muzzle of the turret, or from the hull facing forward without one
+        let (muzzle, direction) = muzzle(transform, children, &turret_query);
+
+        // Inherit the shooter's velocity so shots don't lag behind a moving ship
+        let speed = config.weapons.projectile_speed(weapon.weapon_type);
+        spawn_pooled(&mut commands, PoolKind::Projectile, (
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::new(4.0, 4.0)),
+                    ..default()
+                },
+                transform: Transform::from_translation(muzzle.extend(0.0)),
+                ..default()
+            },
+            Tint::Projectile,
+            GameplayEntity,
+            Velocity {
+                x: velocity.x + direction.x * speed,
+                y: velocity.y + direction.y * speed,
+            },
+            Interpolated,
+            Projectile {
+                damage: weapon.damage(),
+                lifetime: Timer::from_seconds(1.5, TimerMode::Once),
+                owner: player.id,
+                pierce: weapon.weapon_type.pierce(),
+                hit: Vec::new(),
+            },
+            Trail::new(trail_settings.projectile_length),
+        ));
+    }
+}
+
+// Components of a shooter involved in firing its beam
+type BeamShooterData<'a> = (
+    &'a Transform,
+    &'a Weapon,
+    &'a mut AmmoPool,
+    Option<&'a mut Heat>,
+    Option<&'a Children>,
+    &'a Player,
+    &'a mut Beam,
+);
+
+// System to burn the first hostile in the path of every ship's beam while fire is held
+fn beam_system(
+    time: Res<Time>,                                          // For damage, energy and heat per second
+    beam_settings: Res<BeamSettings>,                         // Reach and strength of the beam
+    mut query: Query<BeamShooterData>,                        // Get the shooters
+    turret_query: Query<(&GlobalTransform, &Turret)>,         // Get where turrets point
+    hostile_query: Query<(Entity, &Transform, &Collider), With<Hostile>>, // Get what the beam can hit
+    triggers: Triggers,                                       // Whose trigger is down
+    mut damage_events: EventWriter<DamageEvent>,              // For burning the target
+) {
+    let delta_seconds = time.delta_seconds();
+    for (transform, weapon, mut ammo, mut heat, children, player, mut beam) in query.iter_mut() {
+        beam.length = 0.0;
+        if weapon.weapon_type != WeaponType::Beam || !triggers.held(player.id, &ammo, weapon.weapon_type) {
+            continue;
+        }
+        // Like shots, the beam cuts out while overheated or out of energy
+        if heat.as_ref().is_some_and(|heat| heat.overheated)
+            || !ammo.try_drain(beam_settings.energy_per_second * delta_seconds)
+        {
+            continue;
+        }
+        if let Some(heat) = heat.as_mut() {
+            heat.add(beam_settings.heat_per_second * delta_seconds);
+        }
+
+        // The beam stops at the closest hostile along it
+        let (origin, direction) = muzzle(transform, children, &turret_query);
+        let hit = hostile_query
+            .iter()
+            .filter_map(|(entity, hostile_transform, collider)| {
+                let distance =
+                    ray_circle_distance(origin, direction, hostile_transform.translation.truncate(), collider.radius)?;
+                (distance <= beam_settings.range).then_some((entity, distance))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+
+        beam.length = hit.map_or(beam_settings.range, |(_, distance)| distance);
+        if let Some((target, _)) = hit {
+            damage_events.send(DamageEvent {
+                target,
+                amount: beam_settings.damage_per_second * (1.0 + weapon.damage_bonus) * delta_seconds,
+                source: DamageSource::PlayerBullet { owner: player.id },
+            });
+        }
+    }
+}
+
+// System to draw every ship's beam up to where it stops
+fn beam_draw_system(
+    mut gizmos: Gizmos,                                       // For drawing the beam
+    query: Query<(&Transform, Option<&Children>, &Beam)>,     // Get the shooters
+    turret_query: Query<(&GlobalTransform, &Turret)>,         // Get where turrets point
+) {
+    for (transform, children, beam) in query.iter() {
+        if beam.length <= 0.0 {
+            continue;
+        }
+        let (origin, direction) = muzzle(transform, children, &turret_query);
+        gizmos.line_2d(origin, origin + direction * beam.length, Color::rgb(0.6, 0.4, 1.0));
+    }
+}
+
+// System to put projectiles back in the pool once they have flown long enough
+fn projectile_lifetime_system(
+    mut commands: Commands,                                   // For pooling projectiles
+    time: Res<Time>,                                          // For ticking lifetimes
+    mut query: Query<(Entity, &mut Projectile, &Pooled)>,     // Get every projectile
+) {
+    for (entity, mut projectile, pooled) in query.iter_mut() {
+        if pooled.in_use() && projectile.lifetime.tick(time.delta()).finished() {
+            release_pooled(&mut commands, entity);
+        }
+    }
+}
+
+// System to count down the time until the next smart bomb can go off
+fn bomb_cooldown_system(
+    time: Res<Time>,                                          // For the cooldown
+    mut bomb_settings: ResMut<BombSettings>,                  // Cooldown to tick
+) {
+    bomb_settings.cooldown.tick(time.delta());
+}
+
+// Anything a smart bomb can destroy
+type BombTargetFilter = (With<Health>, Without<Starship>);
+
+// Resource controlling the lead reticle drawn ahead of the nearest hostile
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+struct AimAssist {
+    enabled: bool,  // Whether the reticle is drawn
+    radius: f32,    // Size of the reticle
+    color: Color,   // Color of the reticle
+}
+
+impl Default for AimAssist {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            radius: 6.0,
+            color: Color::rgba(0.4, 1.0, 0.6, 0.6),
+        }
+    }
+}
+
+// System to show or hide the reticle as the settings ask
+fn aim_assist_settings_system(
+    settings: Res<Settings>,                                  // The player's preferences
+    mut aim_assist: ResMut<AimAssist>,                        // The reticle to switch
+) {
+    aim_assist.enabled = settings.aim_assist;
+}
+
+// System to switch auto-fire on or off as the settings ask
+fn auto_fire_settings_system(
+    settings: Res<Settings>,                                  // The player's preferences
+    mut auto_fire: ResMut<AutoFire>,                          // The switch to set
+) {
+    auto_fire.0 = settings.auto_fire;
+}
+
+// System to mark where each ship should aim for its shots to meet the nearest hostile
+fn aim_assist_system(
+    mut gizmos: Gizmos,                                       // For drawing the reticle
+    aim_assist: Res<AimAssist>,                               // Reticle look
+    ship_query: Query<(&Transform, &Velocity, &Weapon), With<Starship>>, // Get the shooters
+    hostile_query: Query<(&Transform, &Velocity), With<Hostile>>, // Get the asteroids, mines and enemies
+    config: Res<GameConfig>,                                  // For the muzzle speeds
+) {
+    if !aim_assist.enabled {
+        return;
+    }
+    for (ship_transform, ship_velocity, weapon) in ship_query.iter() {
+        // The beam hits instantly, so there is nothing to lead
+        if weapon.weapon_type == WeaponType::Beam {
+            continue;
+        }
+        let ship_position = ship_transform.translation.truncate();
+        let nearest = hostile_query.iter().min_by(|(a, _), (b, _)| {
+            let distance_a = a.translation.truncate().distance_squared(ship_position);
+            let distance_b = b.translation.truncate().distance_squared(ship_position);
+            distance_a.total_cmp(&distance_b)
+        });
+        let Some((target_transform, target_velocity)) = nearest else {
+            continue;
+        };
+
+        // Shots inherit the ship's velocity, so lead the target relative to the ship
+        let offset = target_transform.translation.truncate() - ship_position;
+        let relative_velocity = target_velocity.as_vec2() - ship_velocity.as_vec2();
+        // A target too fast to catch gets no reticle
+        let Some(time) = intercept_time(offset, relative_velocity, config.weapons.projectile_speed(weapon.weapon_type)) else {
+            continue;
+        };
+        let aim_point = ship_position + offset + relative_velocity * time;
+        gizmos.circle_2d(aim_point, aim_assist.radius, aim_assist.color);
+        gizmos.line_2d(
+            aim_point - Vec2::X * aim_assist.radius * 0.5,
+            aim_point + Vec2::X * aim_assist.radius * 0.5,
+            aim_assist.color,
+        );
+        gizmos.line_2d(
+            aim_point - Vec2::Y * aim_assist.radius * 0.5,
+            aim_point + Vec2::Y * aim_assist.radius * 0.5,
+            aim_assist.color,
+        );
+    }
+}
+
+// System to set off a smart bomb, destroying everything near the ship
+fn bomb_system(
+    mut commands: Commands,                                   // For spawning the blast ring
+    mut player_inputs: ResMut<PlayerInputs>,                  // Bomb presses to consume
+    mut bombs: ResMut<Bombs>,                                 // Bombs left
+    mut bomb_settings: ResMut<BombSettings>,                  // Radius and cooldown
+    ship_query: Query<(&Transform, &Player), With<Starship>>, // Get where the bomb goes off
+    target_query: Query<(Entity, &Transform), BombTargetFilter>, // Get what it can hit
+    mut damage_events: EventWriter<DamageEvent>,              // For destroying what's in range
+) {
+    // Each press is seen by one tick only, whether or not it sets off a bomb
+    let pressed = player_inputs.0.map(|player_input| player_input.bomb);
+    for player_input in player_inputs.iter_mut() {
+        player_input.bomb = false;
+    }
+    if bombs.0 == 0 || !bomb_settings.cooldown.finished() {
+        return;
+    }
+    // The players share one stock of bombs; the first one asking sets one off around their ship
+    let Some((ship_transform, player)) = ship_query
+        .iter()
+        .find(|(_, player)| pressed[player.id])
+    else {
+        return;
+    };
+    bombs.0 -= 1;
+    bomb_settings.cooldown.reset();
+
+    // Destroy everything in range; the destroy systems award the score
+    let center = ship_transform.translation.truncate();
+    for (entity, transform) in target_query.iter() {
+        if transform.translation.truncate().distance(center) <= bomb_settings.radius {
+            damage_events.send(DamageEvent {
+                target: entity,
+                amount: f32::MAX,
+                source: DamageSource::PlayerBullet { owner: player.id },
+            });
+        }
+    }
+
+    // Leave an expanding ring behind to show the reach of the blast
+    commands.spawn((
+        GameplayEntity,
+        BombBlast {
+            timer: Timer::from_seconds(0.5, TimerMode::Once),
+            radius: bomb_settings.radius,
+        },
+        TransformBundle::from_transform(Transform::from_translation(center.extend(0.0))),
+    ));
+}
+
+// System to grow and fade the smart bomb's ring, removing it when done
+fn bomb_blast_system(
+    mut commands: Commands,                                   // For despawning finished rings
+    time: Res<Time>,                                          // For animating the ring
+    mut gizmos: Gizmos,                                       // For drawing the ring
+    mut query: Query<(Entity, &Transform, &mut BombBlast)>,   // Get every ring
+) {
+    for (entity, transform, mut blast) in query.iter_mut() {
+        if blast.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let progress = blast.timer.percent();
+        gizmos.circle_2d(
+            transform.translation.truncate(),
+            blast.radius * progress,
+            Color::rgba(1.0, 0.8, 0.4, 1.0 - progress),
+        );
+    }
+}
+
+// Plugin for the ship's gun and bombs
+pub struct WeaponsPlugin;
+
+impl Plugin for WeaponsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Weapon>()
+            .register_type::<AmmoPool>()
+            .register_type::<Heat>()
+            .register_type::<Projectile>()
+            .register_type::<Turret>()
+            .register_type::<Bombs>()
+            .register_type::<BombSettings>()
+            .register_type::<BombBlast>()
+            .register_type::<AimAssist>()
+            .register_type::<AutoFire>()
+            .register_type::<Beam>()
+            .register_type::<BeamSettings>()
+            .init_resource::<Bombs>()
+            .init_resource::<BombSettings>()
+            .init_resource::<AimAssist>()
+            .init_resource::<AutoFire>()
+            .init_resource::<BeamSettings>()
+            .add_systems(Update, (
+                aim_assist_settings_system,
+                auto_fire_settings_system,
+            ).run_if(resource_changed::<Settings>()))
+            .add_systems(Update, (
+                bomb_blast_system,
+                // Aimed from the interpolated positions the player sees
+                aim_assist_system.after(InterpolationSet),
+                beam_draw_system.after(InterpolationSet),
+            ).run_if(in_state(GameState::Playing)))
+            // Shooting, cooldowns and heat advance with the simulation
+            .add_systems(FixedUpdate, (
+                (
+                    (fire_weapon_system, beam_system).chain(),
+                    (bomb_cooldown_system, bomb_system).chain(),
+                ).after(PlayerInputSet).in_set(GameSet::Input),
+                projectile_lifetime_system.in_set(GameSet::Cleanup),
+                // Aimed once the hulls have turned for the tick, so the turret ends it facing the aim
+                turret_aim_system.in_set(GameSet::Cleanup),
+            ).run_if(in_state(GameState::Playing)));
+    }
+}
@@ -0,0 +1,87 @@
+// Faint copies of the ships on the far side of the arena as they near an edge, showing where they
+// will come back in once they wrap over it
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::physics::{InterpolationSet, WorldBounds, wrap_ghost_offsets};
+use crate::player::Starship;
+use crate::session::GameplayEntity;
+
+// Distance from an edge at which a ship's copy shows up on the other side, in pixels
+const WRAP_GHOST_MARGIN: f32 = 48.0;
+
+// How opaque a copy is next to the ship itself
+const WRAP_GHOST_ALPHA: f32 = 0.35;
+
+// Define the WrapGhost component for the copy of a ship across one axis of the arena
+#[derive(Component)]
+struct WrapGhost {
+    ship: Entity,  // The ship copied
+    axis: usize,   // 0 for the copy across the width, 1 for the one across the height
+}
+
+// Components of a ship copied near the edges
+type CopiedShipData<'a> = (Entity, &'a Transform, &'a TextureAtlasSprite, &'a Handle<TextureAtlas>);
+
+// System to place a copy of every ship on the opposite edge while it is within the margin of an
+// edge, two at a corner, and take the copies away once it moves clear or is gone
+fn wrap_ghost_system(
+    mut commands: Commands,                                   // For adding and removing copies
+    arena: Res<WorldBounds>,                                  // Where the edges are
+    ship_query: Query<CopiedShipData, With<Starship>>,        // Get the ships to copy
+    mut ghost_query: Query<(Entity, &WrapGhost, &mut Transform, &mut TextureAtlasSprite), Without<Starship>>, // Get the copies
+) {
+    let mut ghosts: HashMap<(Entity, usize), Entity> = ghost_query
+        .iter()
+        .map(|(entity, ghost, _, _)| ((ghost.ship, ghost.axis), entity))
+        .collect();
+    for (ship, ship_transform, ship_sprite, atlas) in ship_query.iter() {
+        let offsets = wrap_ghost_offsets(ship_transform.translation.truncate(), arena.half_extents(), WRAP_GHOST_MARGIN);
+        for (axis, offset) in offsets.into_iter().enumerate() {
+            let existing = ghosts.remove(&(ship, axis));
+            let Some(offset) = offset else {
+                if let Some(ghost) = existing {
+                    commands.entity(ghost).despawn();
+                }
+                continue;
+            };
+            // Drawn just under the ship, turned and scaled like it
+            let mut transform = *ship_transform;
+            transform.translation += offset.extend(-0.1);
+            let mut sprite = ship_sprite.clone();
+            sprite.color.set_a(sprite.color.a() * WRAP_GHOST_ALPHA);
+            match existing.and_then(|ghost| ghost_query.get_mut(ghost).ok()) {
+                Some((_, _, mut ghost_transform, mut ghost_sprite)) => {
+                    *ghost_transform = transform;
+                    *ghost_sprite = sprite;
+                }
+                None => {
+                    commands.spawn((
+                        SpriteSheetBundle {
+                            texture_atlas: atlas.clone(),
+                            sprite,
+                            transform,
+                            ..default()
+                        },
+                        GameplayEntity,
+                        WrapGhost { ship, axis },
+                    ));
+                }
+            }
+        }
+    }
+    // Copies of ships that are gone
+    for ghost in ghosts.into_values() {
+        commands.entity(ghost).despawn();
+    }
+}
+
+// Plugin for the copies of ships across the arena's edges
+pub struct WrapPlugin;
+
+impl Plugin for WrapPlugin {
+    fn build(&self, app: &mut App) {
+        // Over the drawn positions, so a copy moves smoothly with its ship between ticks
+        app.add_systems(Update, wrap_ghost_system.after(InterpolationSet));
+    }
+}
@@ -0,0 +1,122 @@
+// The safe zone: a circle around the middle of the arena that closes in over a run, hurting
+// ships caught outside it and herding them towards the middle
+use bevy::prelude::*;
+
+use crate::combat::{DamageEvent, DamageSource, Invulnerable};
+use crate::config::GameConfig;
+use crate::player::{Starship, player_exists};
+use crate::session::{GameMode, GameState, NewGameEvent};
+use crate::GameSet;
+
+// Color of the zone's edge
+const SAFE_ZONE_COLOR: Color = Color::rgba(1.0, 0.35, 0.2, 0.6);
+
+// Resource holding how far the safe zone reaches and how it closes in
+#[derive(Reflect, Resource)]
+#[reflect(Resource)]
+pub struct SafeZone {
+    pub radius: f32,             // How far the zone reaches from the middle of the arena
+    pub shrink_rate: f32,        // Pixels per second the radius shrinks by
+    pub min_radius: f32,         // Smallest the zone gets
+    pub damage_per_second: f32,  // Damage a ship outside the zone takes every second
+}
+
+impl Default for SafeZone {
+    fn default() -> Self {
+        let config = GameConfig::default().safe_zone;
+        Self {
+            radius: config.start_radius,
+            shrink_rate: config.shrink_rate,
+            min_radius: config.min_radius,
+            damage_per_second: config.damage_per_second,
+        }
+    }
+}
+
+// Run condition for the safe zone being switched on in the config, and not on the practice range
+fn safe_zone_enabled(config: Res<GameConfig>, mode: Res<State<GameMode>>) -> bool {
+    config.safe_zone.enabled && *mode.get() == GameMode::Arcade
+}
+
+// System to close the zone in, down to its smallest
+fn zone_shrink_system(
+    time: Res<Time>,                                          // For frame-independent shrinking
+    mut zone: ResMut<SafeZone>,                               // The zone to shrink
+) {
+    let radius = (zone.radius - zone.shrink_rate * time.delta_seconds()).max(zone.min_radius);
+    if radius < zone.radius {
+        zone.radius = radius;
+    }
+}
+
+// Filter for the ships the zone can hurt; invulnerable ships, in god mode or docked, are spared
+// even though hazards otherwise get through invulnerability
+type ExposedShipFilter = (With<Starship>, Without<Invulnerable>);
+
+// System to hurt every ship outside the zone, a little every tick
+fn zone_damage_system(
+    time: Res<Time>,                                          // For damage per second
+    zone: Res<SafeZone>,                                      // Where it is safe
+    ship_query: Query<(Entity, &Transform), ExposedShipFilter>, // Get every ship it can hurt
+    mut damage_events: EventWriter<DamageEvent>,              // For dealing the damage
+) {
+    for (ship, transform) in ship_query.iter() {
+        if transform.translation.truncate().length() > zone.radius {
+            damage_events.send(DamageEvent {
+                target: ship,
+                amount: zone.damage_per_second * time.delta_seconds(),
+                source: DamageSource::Hazard,
+            });
+        }
+    }
+}
+
+// System to draw the edge of the zone
+fn zone_draw_system(zone: Res<SafeZone>, mut gizmos: Gizmos) {
+    gizmos.circle_2d(Vec2::ZERO, zone.radius, SAFE_ZONE_COLOR).segments(128);
+}
+
+// System to take the zone's tuning from the game config as it is loaded or reloaded; the zone
+// only starts from the new radius on the next run, unless the game was just launched
+fn zone_config_system(
+    config: Res<GameConfig>,                                  // The balance values
+    mut zone: ResMut<SafeZone>,                               // The zone to tune
+) {
+    let safe_zone = &config.safe_zone;
+    zone.shrink_rate = safe_zone.shrink_rate;
+    zone.min_radius = safe_zone.min_radius;
+    zone.damage_per_second = safe_zone.damage_per_second;
+    if config.is_added() {
+        zone.radius = safe_zone.start_radius;
+    }
+}
+
+// System to open the zone back up when a new run starts
+fn zone_reset_system(
+    mut new_game_events: EventReader<NewGameEvent>,           // Requests for a new run
+    config: Res<GameConfig>,                                  // How big the zone starts
+    mut zone: ResMut<SafeZone>,                               // The zone to open up
+) {
+    if new_game_events.read().count() > 0 {
+        zone.radius = config.safe_zone.start_radius;
+    }
+}
+
+// Plugin for the safe zone
+pub struct SafeZonePlugin;
+
+impl Plugin for SafeZonePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SafeZone>()
+            .init_resource::<SafeZone>()
+            .add_systems(PreUpdate, zone_config_system.run_if(resource_changed::<GameConfig>()))
+            .add_systems(Update, zone_reset_system)
+            // Only closing in while someone is flying, and hurting ahead of the collisions so the
+            // damage is dealt in the same tick
+            .add_systems(FixedUpdate, (
+                zone_shrink_system,
+                zone_damage_system,
+            ).chain().in_set(GameSet::Physics).run_if(player_exists).run_if(safe_zone_enabled).run_if(in_state(GameState::Playing)))
+            .add_systems(Update, zone_draw_system.run_if(safe_zone_enabled).run_if(in_state(GameState::Playing)));
+    }
+}
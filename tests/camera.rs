@@ -0,0 +1,55 @@
+// The camera zoom: the mouse wheel moves it, but never past its limits
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+
+use star_ship_game::camera::{CameraPlugin, CameraZoom, PlayerCamera};
+use star_ship_game::headless::{headless_app, step, with_commands};
+
+// Scale of the first player's camera
+fn camera_scale(app: &mut App) -> f32 {
+    app.world
+        .query_filtered::<&OrthographicProjection, With<PlayerCamera>>()
+        .single(&app.world)
+        .scale
+}
+
+// Turn the mouse wheel by the given notches, positive zooming in
+fn scroll(app: &mut App, notches: f32) {
+    app.world.send_event(MouseWheel {
+        unit: MouseScrollUnit::Line,
+        x: 0.0,
+        y: notches,
+        window: Entity::PLACEHOLDER,
+    });
+}
+
+// Scrolling zooms the camera in and out, stopping at the closest and furthest zoom however far
+// the wheel is turned
+#[test]
+fn scroll_zoom_stays_within_limits() {
+    let mut app = headless_app();
+    app.add_plugins(CameraPlugin);
+    with_commands(&mut app, |commands| {
+        commands.spawn((Camera2dBundle::default(), PlayerCamera { id: 0 }));
+    });
+    let (min_scale, max_scale) = {
+        let zoom = app.world.resource::<CameraZoom>();
+        (zoom.min_scale, zoom.max_scale)
+    };
+    let start = camera_scale(&mut app);
+
+    scroll(&mut app, 2.0);
+    step(&mut app, 5);
+    let zoomed_in = camera_scale(&mut app);
+    assert!(zoomed_in < start, "scale went from {} to {}", start, zoomed_in);
+
+    scroll(&mut app, 100.0);
+    step(&mut app, 120);
+    let closest = camera_scale(&mut app);
+    assert!(closest >= min_scale && closest - min_scale < 1e-2, "closest scale {}", closest);
+
+    scroll(&mut app, -100.0);
+    step(&mut app, 120);
+    let furthest = camera_scale(&mut app);
+    assert!(furthest <= max_scale && max_scale - furthest < 1e-2, "furthest scale {}", furthest);
+}
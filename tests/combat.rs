@@ -0,0 +1,154 @@
+// Combat: how overlaps are reported, how asteroids bounce, what a crash costs a ship and how a hit shows
+use bevy::ecs::event::ManualEventReader;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use star_ship_game::combat::{CollisionEvent, DamageEvent, DamageFlash, DamageSource, Health, Shield};
+use star_ship_game::config::GameConfig;
+use star_ship_game::headless::{headless_app, spawn_test_ship, step, stop_spawning, with_commands};
+use star_ship_game::hostiles::spawn_asteroid;
+use star_ship_game::palette::ColorScheme;
+use star_ship_game::physics::Velocity;
+use star_ship_game::player::Player;
+use star_ship_game::session::GameMode;
+
+// What a ship has left to lose, shield and hull together
+fn toughness(app: &App, ship: Entity) -> f32 {
+    app.world.get::<Health>(ship).unwrap().current + app.world.get::<Shield>(ship).unwrap().current
+}
+
+// Damage a still ship takes from an asteroid flying straight into it at the given speed
+fn impact_damage(speed: f32) -> f32 {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    with_commands(&mut app, |commands| {
+        spawn_asteroid(commands, Vec2::new(0.0, 60.0), Vec2::new(0.0, -speed), 20.0);
+    });
+    let start = toughness(&app, ship);
+    step(&mut app, 60);
+    start - toughness(&app, ship)
+}
+
+// The faster the two close in, the harder the hit
+#[test]
+fn slow_impact_hurts_less_than_fast() {
+    let slow = impact_damage(60.0);
+    let fast = impact_damage(300.0);
+    assert!(fast > 0.0, "a fast impact did no damage");
+    assert!(slow < fast, "slow {} fast {}", slow, fast);
+}
+
+// A hit lights the ship up in the flash color on the very next frame, and the ship fades back to
+// its own color once the flash is over
+#[test]
+fn hit_flashes_ship_straight_away() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    step(&mut app, 1);
+    let color = |app: &App| app.world.get::<TextureAtlasSprite>(ship).unwrap().color;
+    let (flash_color, ship_color) = {
+        let color_scheme = app.world.resource::<ColorScheme>();
+        (color_scheme.damage_flash, color_scheme.ship(Player { id: 0 }))
+    };
+    assert_eq!(color(&app), ship_color);
+
+    app.world.send_event(DamageEvent {
+        target: ship,
+        amount: 5.0,
+        source: DamageSource::Collision,
+    });
+    step(&mut app, 1);
+    assert!(app.world.get::<DamageFlash>(ship).is_some());
+    assert_ne!(color(&app), ship_color);
+    let from_flash = (Vec4::from(color(&app)) - Vec4::from(flash_color)).length();
+    assert!(from_flash < 0.1, "flash color {:?}, ship drawn {:?}", flash_color, color(&app));
+
+    step(&mut app, 60);
+    assert!(app.world.get::<DamageFlash>(ship).is_none());
+    assert_eq!(color(&app), ship_color);
+}
+
+// Things left overlapping for several ticks are each sent as a pair once, on the tick they first
+// touch, whichever of the two is named first
+#[test]
+fn overlap_sends_one_event_per_pair() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    let (rammed, first, second) = with_commands(&mut app, |commands| {
+        (
+            spawn_asteroid(commands, Vec2::new(0.0, 20.0), Vec2::ZERO, 40.0),
+            spawn_asteroid(commands, Vec2::new(-200.0, 150.0), Vec2::ZERO, 20.0),
+            spawn_asteroid(commands, Vec2::new(-180.0, 150.0), Vec2::ZERO, 20.0),
+        )
+    });
+
+    let mut reader = ManualEventReader::<CollisionEvent>::default();
+    let mut sent: HashMap<(Entity, Entity), u32> = HashMap::new();
+    for _ in 0..10 {
+        step(&mut app, 1);
+        let events = app.world.resource::<Events<CollisionEvent>>();
+        for event in reader.read(events) {
+            *sent.entry((event.a.min(event.b), event.a.max(event.b))).or_default() += 1;
+        }
+    }
+    let pair = |a: Entity, b: Entity| (a.min(b), a.max(b));
+    assert_eq!(sent.len(), 2, "pairs sent {:?}", sent);
+    assert_eq!(sent.get(&pair(ship, rammed)), Some(&1));
+    assert_eq!(sent.get(&pair(first, second)), Some(&1));
+}
+
+// Two asteroids of a size flying head-on into each other bounce back the way they came, and end
+// up apart instead of stuck together
+#[test]
+fn head_on_asteroids_rebound() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let (left, right) = with_commands(&mut app, |commands| {
+        (
+            spawn_asteroid(commands, Vec2::new(-60.0, 100.0), Vec2::new(120.0, 0.0), 20.0),
+            spawn_asteroid(commands, Vec2::new(60.0, 100.0), Vec2::new(-120.0, 0.0), 20.0),
+        )
+    });
+    step(&mut app, 60);
+
+    let velocity = |entity: Entity| app.world.get::<Velocity>(entity).unwrap().as_vec2();
+    let position = |entity: Entity| app.world.get::<Transform>(entity).unwrap().translation.truncate();
+    assert!(velocity(left).x < 0.0 && velocity(right).x > 0.0, "{} and {}", velocity(left), velocity(right));
+    assert!(velocity(left).abs_diff_eq(-velocity(right), 1e-3));
+    assert!(position(left).distance(position(right)) > 40.0);
+    assert!(position(left).y == 100.0 && position(right).y == 100.0);
+}
+
+// Shields recharge in the kinds of run the config turns recharging on for, and stay down in the
+// others
+#[test]
+fn shield_regen_follows_game_mode() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    {
+        let mut config = app.world.resource_mut::<GameConfig>();
+        config.shield_regen.arcade = false;
+        config.shield_regen.practice = true;
+        config.shield_regen.delay = 0.5;
+    }
+    let shield = |app: &App| app.world.get::<Shield>(ship).unwrap().current;
+    let drain = |app: &mut App| {
+        let mut shield = app.world.get_mut::<Shield>(ship).unwrap();
+        shield.current = 0.0;
+        shield.since_hit = 0.0;
+    };
+
+    drain(&mut app);
+    step(&mut app, 90);
+    assert_eq!(shield(&app), 0.0);
+
+    app.world.resource_mut::<NextState<GameMode>>().set(GameMode::Practice);
+    step(&mut app, 1);
+    drain(&mut app);
+    step(&mut app, 90);
+    assert!(shield(&app) > 0.0);
+}
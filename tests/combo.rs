@@ -0,0 +1,29 @@
+// Combos: quick kills multiplying their points, until the player lets the window close
+use std::time::Duration;
+
+use star_ship_game::combo::ComboMeter;
+use star_ship_game::config::ComboConfig;
+
+// Kills each inside the window of the one before keep the combo going and step the multiplier up
+// every tier, and waiting out the window starts the count over
+#[test]
+fn quick_kills_raise_multiplier_until_a_gap() {
+    let config = ComboConfig::default();
+    let mut combo = ComboMeter::default();
+    let within = Duration::from_secs_f32(config.window * 0.75);
+
+    let mut multipliers = Vec::new();
+    for _ in 0..config.tier_kills * 2 {
+        multipliers.push(combo.register_kill(&config));
+        combo.tick(within);
+    }
+    assert_eq!(combo.count, config.tier_kills * 2);
+    assert_eq!(multipliers.first(), Some(&1));
+    assert_eq!(multipliers[config.tier_kills as usize - 1], 2);
+    assert_eq!(multipliers.last(), Some(&3));
+    assert!(multipliers.windows(2).all(|pair| pair[1] >= pair[0]), "multipliers {:?}", multipliers);
+
+    combo.tick(Duration::from_secs_f32(config.window));
+    assert_eq!(combo.count, 0);
+    assert_eq!(combo.register_kill(&config), 1);
+}
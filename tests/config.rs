@@ -0,0 +1,46 @@
+// The balance config: what editing it while a run is under way changes
+
+use star_ship_game::config::{ConfigPlugin, GameConfig};
+use star_ship_game::garage::{GarageSettings, Upgrades};
+use star_ship_game::headless::{headless_app, spawn_test_ship, step, stop_spawning};
+use star_ship_game::player::{Engine, ShipClass, Starship};
+
+// An edit to another part of the config leaves the flying ship as it is, tuning changed in the
+// inspector included; an edit to its loadout changes its handling but not the fuel left
+#[test]
+fn reload_only_retunes_ships_when_their_loadout_changes() {
+    let mut app = headless_app();
+    app.add_plugins(ConfigPlugin)
+        .init_resource::<Upgrades>()
+        .init_resource::<GarageSettings>();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    step(&mut app, 1);
+    let fuel = {
+        let mut engine = app.world.get_mut::<Engine>(ship).unwrap();
+        engine.fuel = engine.max_fuel / 2.0;
+        engine.fuel
+    };
+    app.world.get_mut::<Starship>(ship).unwrap().max_speed = 999.0;
+
+    app.world.resource_mut::<GameConfig>().combo.window += 1.0;
+    step(&mut app, 1);
+    assert_eq!(app.world.get::<Starship>(ship).unwrap().max_speed, 999.0);
+    assert_eq!(app.world.get::<Engine>(ship).unwrap().fuel, fuel);
+
+    let thrust = {
+        let ship_class = *app.world.resource::<ShipClass>();
+        let mut config = app.world.resource_mut::<GameConfig>();
+        let loadout = match ship_class {
+            ShipClass::Scout => &mut config.ships.scout,
+            ShipClass::Tank => &mut config.ships.tank,
+            ShipClass::Racer => &mut config.ships.racer,
+        };
+        loadout.thrust *= 2.0;
+        loadout.thrust
+    };
+    step(&mut app, 1);
+    let engine = app.world.get::<Engine>(ship).unwrap();
+    assert_eq!(engine.thrust, thrust);
+    assert_eq!(engine.fuel, fuel);
+}
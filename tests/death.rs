@@ -0,0 +1,39 @@
+// The death cam: the slow moment between losing the last ship and the game over
+use bevy::prelude::*;
+
+use star_ship_game::death::{DeathSequence, DeathSequencePlugin};
+use star_ship_game::headless::{headless_app, step, stop_spawning};
+use star_ship_game::physics::FIXED_TICK_RATE;
+use star_ship_game::player::{MAX_PLAYERS, Player, ShipDestroyed};
+use star_ship_game::replay::ReplayRecorder;
+use star_ship_game::session::{GameState, Lives};
+
+// The state the game is in
+fn state(app: &App) -> GameState {
+    *app.world.resource::<State<GameState>>().get()
+}
+
+// Losing the last ship with no lives left holds the game in play while the death cam runs, and
+// brings the game over once its timer is up
+#[test]
+fn death_cam_ends_in_game_over() {
+    let mut app = headless_app();
+    app.add_plugins(DeathSequencePlugin).init_resource::<ReplayRecorder>();
+    stop_spawning(&mut app);
+    app.world.insert_resource(Lives([0; MAX_PLAYERS]));
+    app.world.send_event(ShipDestroyed {
+        player: Player { id: 0 },
+        position: Vec2::new(40.0, -20.0),
+    });
+    step(&mut app, 1);
+
+    let duration = app.world.resource::<DeathSequence>().timer.duration().as_secs_f64();
+    let ticks = (duration * FIXED_TICK_RATE).round() as u32;
+    step(&mut app, ticks - 2);
+    assert_eq!(state(&app), GameState::Playing);
+    assert!(app.world.contains_resource::<DeathSequence>());
+
+    step(&mut app, 3);
+    assert_eq!(state(&app), GameState::GameOver);
+    assert!(!app.world.contains_resource::<DeathSequence>());
+}
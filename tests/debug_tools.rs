@@ -0,0 +1,23 @@
+// The developer tools: in development builds, and left out of release builds unless asked for
+use star_ship_game::DEBUG_TOOLS_ENABLED;
+#[cfg(all(not(debug_assertions), not(feature = "release-inspector")))]
+use star_ship_game::{add_debug_tools, headless::headless_app};
+
+// A development build with the tools' feature has them
+#[cfg(debug_assertions)]
+#[test]
+fn development_build_has_debug_tools() {
+    assert_eq!(DEBUG_TOOLS_ENABLED, cfg!(all(feature = "debug-inspector", not(target_arch = "wasm32"))));
+}
+
+// A release build without the `release-inspector` feature adds none of the tools, whatever the
+// other features; run with `cargo test --release`
+#[cfg(all(not(debug_assertions), not(feature = "release-inspector")))]
+#[test]
+fn release_build_leaves_debug_tools_out() {
+    assert!(!DEBUG_TOOLS_ENABLED);
+    let mut app = headless_app();
+    add_debug_tools(&mut app);
+    #[cfg(feature = "debug-inspector")]
+    assert!(!app.world.contains_resource::<star_ship_game::debug::DebugMode>());
+}
@@ -0,0 +1,30 @@
+// The difficulty ramp: the longer a run lasts, the faster hostiles come, up to a cap
+use bevy::prelude::*;
+
+use star_ship_game::difficulty::{Difficulty, DifficultyCurve, SurvivalTimer};
+use star_ship_game::headless::{headless_app, spawn_test_ship, step, stop_spawning};
+use star_ship_game::hostiles::AsteroidSpawner;
+
+// Seconds between two spawns once the run has lasted the given time
+fn spawn_interval_after(app: &mut App, elapsed: f32) -> f32 {
+    app.world.resource_mut::<SurvivalTimer>().elapsed = elapsed;
+    step(app, 1);
+    app.world.resource::<AsteroidSpawner>().current_interval(app.world.resource::<Difficulty>())
+}
+
+// Five minutes in, hostiles come in more often than at the start, but never more often than the
+// cap allows
+#[test]
+fn spawn_interval_shrinks_over_five_minutes() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    // Only time with a ship in play counts as surviving
+    spawn_test_ship(&mut app);
+    let start = spawn_interval_after(&mut app, 0.0);
+    let five_minutes = spawn_interval_after(&mut app, 300.0);
+    assert!(five_minutes < start, "{} at the start, {} after five minutes", start, five_minutes);
+
+    let max_spawn_rate = app.world.resource::<DifficultyCurve>().max_spawn_rate;
+    let much_later = spawn_interval_after(&mut app, 100_000.0);
+    assert!(much_later >= start / max_spawn_rate - 1e-4, "{} after a long run", much_later);
+}
@@ -0,0 +1,24 @@
+// Effects: explosions grow, fade and clear themselves away
+use bevy::prelude::*;
+
+use star_ship_game::effects::{EffectsPlugin, Explosion, spawn_explosion};
+use star_ship_game::headless::{headless_app, step, stop_spawning, with_commands};
+use star_ship_game::physics::FIXED_TICK_RATE;
+use star_ship_game::session::GameRng;
+
+// An explosion stays up while its ring expands, and is gone once its timer has run out
+#[test]
+fn explosion_despawns_after_its_timer() {
+    let mut app = headless_app();
+    app.add_plugins(EffectsPlugin);
+    stop_spawning(&mut app);
+    let mut rng = GameRng::new(1);
+    let explosion = with_commands(&mut app, |commands| spawn_explosion(commands, &mut rng, Vec2::ZERO, 20.0));
+    let duration = app.world.get::<Explosion>(explosion).unwrap().timer.duration().as_secs_f64();
+    let ticks = (duration * FIXED_TICK_RATE).round() as u32;
+
+    step(&mut app, ticks - 2);
+    assert!(app.world.get_entity(explosion).is_some());
+    step(&mut app, 4);
+    assert!(app.world.get_entity(explosion).is_none());
+}
@@ -0,0 +1,45 @@
+// EMP mines: a ship that runs into one loses its engine for a while, and nobody scores for the mine
+use bevy::prelude::*;
+
+use star_ship_game::combat::LastHitBy;
+use star_ship_game::headless::{headless_app, hold_input, spawn_test_ship, step, stop_spawning, with_commands};
+use star_ship_game::hostiles::{EmpMine, spawn_emp_mine};
+use star_ship_game::input::PlayerInput;
+use star_ship_game::physics::Velocity;
+use star_ship_game::player::Disabled;
+use star_ship_game::session::Score;
+
+// A disabled ship ignores thrust, and answers it again once the engine is back
+#[test]
+fn disabled_ship_ignores_thrust_until_recovered() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    app.world.entity_mut(ship).insert(Disabled {
+        timer: Timer::from_seconds(60.0, TimerMode::Once),
+    });
+    hold_input(&mut app, 0, PlayerInput { thrust: 1.0, ..default() });
+    step(&mut app, 30);
+    assert_eq!(app.world.get::<Velocity>(ship).unwrap().as_vec2(), Vec2::ZERO);
+
+    app.world.entity_mut(ship).remove::<Disabled>();
+    step(&mut app, 30);
+    assert!(app.world.get::<Velocity>(ship).unwrap().y > 0.0);
+}
+
+// Flying into a mine a player already shot disables the ship, uses the mine up and scores nothing
+#[test]
+fn ramming_a_shot_mine_scores_nothing() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    with_commands(&mut app, |commands| {
+        let mine = spawn_emp_mine(commands, Vec2::ZERO, Vec2::ZERO);
+        commands.entity(mine).insert(LastHitBy(0));
+    });
+    step(&mut app, 3);
+
+    assert!(app.world.get::<Disabled>(ship).is_some());
+    assert_eq!(app.world.query::<&EmpMine>().iter(&app.world).count(), 0);
+    assert_eq!(app.world.resource::<Score>().0[0], 0);
+}
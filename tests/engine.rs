@@ -0,0 +1,251 @@
+// Ship movement and the engine, stepped headlessly one fixed tick at a time
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use std::time::Duration;
+
+use star_ship_game::headless::{headless_app, hold_input, press_key, spawn_test_ship, step, stop_spawning, with_commands};
+use star_ship_game::input::PlayerInput;
+use star_ship_game::physics::{FIXED_TICK_RATE, Mass, PreviousTransform, Velocity};
+use star_ship_game::player::{Engine, EngineThrottle, ThrottleResponse};
+use star_ship_game::settings::Settings;
+
+// Length of one fixed tick, as the headless clock advances it
+const TICK: f32 = 1.0 / FIXED_TICK_RATE as f32;
+
+// Holding thrust burns fuel
+#[test]
+fn held_thrust_burns_fuel() {
+    let mut app = headless_app();
+    let ship = spawn_test_ship(&mut app);
+    step(&mut app, 1);
+    let full = app.world.get::<Engine>(ship).unwrap().fuel;
+
+    press_key(&mut app, KeyCode::Up);
+    step(&mut app, 30);
+    let fuel = app.world.get::<Engine>(ship).unwrap().fuel;
+    assert!(fuel < full, "fuel went from {} to {}", full, fuel);
+}
+
+// Without thrust the tank stays as it is
+#[test]
+fn idle_engine_keeps_fuel() {
+    let mut app = headless_app();
+    let ship = spawn_test_ship(&mut app);
+    step(&mut app, 1);
+    let full = app.world.get::<Engine>(ship).unwrap().fuel;
+
+    step(&mut app, 30);
+    assert_eq!(app.world.get::<Engine>(ship).unwrap().fuel, full);
+}
+
+// A moving entity covers its velocity times the tick's length every tick
+#[test]
+fn velocity_moves_transform_by_v_dt() {
+    let mut app = headless_app();
+    let entity = with_commands(&mut app, |commands| {
+        commands
+            .spawn((TransformBundle::default(), Velocity { x: 120.0, y: -60.0 }))
+            .id()
+    });
+
+    step(&mut app, 1);
+    let moved = app.world.get::<Transform>(entity).unwrap().translation.truncate();
+    assert!(moved.abs_diff_eq(Vec2::new(120.0, -60.0) * TICK, 1e-4), "moved to {}", moved);
+
+    step(&mut app, 9);
+    let moved = app.world.get::<Transform>(entity).unwrap().translation.truncate();
+    assert!(moved.abs_diff_eq(Vec2::new(120.0, -60.0) * TICK * 10.0, 1e-3), "moved to {}", moved);
+}
+
+// Fuel burned by a ship holding the given throttle for the given ticks, with the engine answering
+// straight away so both throttles are held for the whole time
+fn fuel_burned(throttle: f32, ticks: u32) -> f32 {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    app.world.resource_mut::<ThrottleResponse>().attack = 0.0;
+    let ship = spawn_test_ship(&mut app);
+    let full = app.world.get::<Engine>(ship).unwrap().fuel;
+    hold_input(&mut app, 0, PlayerInput { thrust: throttle, ..default() });
+    step(&mut app, ticks);
+    full - app.world.get::<Engine>(ship).unwrap().fuel
+}
+
+// Half throttle burns well under half the fuel of full throttle over the same time, a quarter
+// along the default curve
+#[test]
+fn half_throttle_is_more_economical() {
+    let full = fuel_burned(1.0, 30);
+    let half = fuel_burned(0.5, 30);
+    assert!(full > 0.0);
+    assert!((half / full - 0.25).abs() < 1e-3, "half throttle burned {} against {}", half, full);
+}
+
+// Where a ship flying a fixed course ends up after a second of game time, with the game clock
+// advancing by the given frame length every update
+fn ship_after_one_second(frame: Duration) -> Transform {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(frame));
+    let ship = spawn_test_ship(&mut app);
+    hold_input(&mut app, 0, PlayerInput { thrust: 1.0, rotate: 0.5, ..default() });
+    let updates = (Duration::from_secs(1).as_secs_f64() / frame.as_secs_f64()).round() as u32;
+    step(&mut app, updates);
+    *app.world.get::<Transform>(ship).unwrap()
+}
+
+// Whether frames come in at the tick rate, three ticks at a time or two to a tick, the same number
+// of fixed ticks leaves the ship in the same place
+#[test]
+fn frame_pacing_does_not_change_the_course() {
+    let tick = Duration::from_secs_f64(1.0 / FIXED_TICK_RATE);
+    let steady = ship_after_one_second(tick);
+    assert!(steady.translation.truncate().length() > 0.0);
+    assert_eq!(ship_after_one_second(tick * 3), steady);
+    // Rounded up, so the halves never fall short of a whole tick
+    assert_eq!(ship_after_one_second(tick / 2 + Duration::from_nanos(1)), steady);
+}
+
+// Thrust given in a tick is already in that tick's move: the ship is off the mark after one tick,
+// by exactly the velocity the engine just gave it
+#[test]
+fn thrust_moves_ship_in_same_tick() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    hold_input(&mut app, 0, PlayerInput { thrust: 1.0, ..default() });
+    step(&mut app, 1);
+
+    let velocity = app.world.get::<Velocity>(ship).unwrap().as_vec2();
+    let position = app.world.get::<Transform>(ship).unwrap().translation.truncate();
+    assert!(velocity.y > 0.0, "velocity {}", velocity);
+    assert!(position.abs_diff_eq(velocity * TICK, 1e-6), "moved to {} at {}", position, velocity);
+}
+
+// Speed a ship of the given mass picks up from a few ticks of full thrust
+fn speed_gained(mass: f32) -> f32 {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    app.world.resource_mut::<ThrottleResponse>().attack = 0.0;
+    let ship = spawn_test_ship(&mut app);
+    app.world.entity_mut(ship).insert(Mass(mass));
+    hold_input(&mut app, 0, PlayerInput { thrust: 1.0, ..default() });
+    step(&mut app, 10);
+    app.world.get::<Velocity>(ship).unwrap().as_vec2().length()
+}
+
+// The same engine pushes a ship twice as heavy along half as fast
+#[test]
+fn double_mass_halves_acceleration() {
+    let light = speed_gained(1.0);
+    let heavy = speed_gained(2.0);
+    assert!(light > 0.0);
+    assert!((heavy / light - 0.5).abs() < 1e-4, "heavy ship gained {} against {}", heavy, light);
+}
+
+// Strafing pushes the ship square to its heading, to its right for positive strafe, and leaves
+// the heading as it was
+#[test]
+fn strafe_moves_ship_sideways_to_its_heading() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    // Nose pointing left, so its right is straight up
+    let heading = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+    app.world.get_mut::<Transform>(ship).unwrap().rotation = heading;
+    hold_input(&mut app, 0, PlayerInput { strafe: 1.0, ..default() });
+    step(&mut app, 30);
+
+    let velocity = app.world.get::<Velocity>(ship).unwrap().as_vec2();
+    let transform = *app.world.get::<Transform>(ship).unwrap();
+    assert!(velocity.y > 0.0 && velocity.x.abs() < 1e-3, "velocity {}", velocity);
+    assert!(transform.translation.y > 0.0 && transform.translation.x.abs() < 1e-3, "moved to {}", transform.translation);
+    assert!(transform.rotation.abs_diff_eq(heading, 1e-6), "turned to {}", transform.rotation);
+}
+
+// Held thrust opens the throttle a little more every tick, only reaching full after the attack
+// time, and letting go closes it over the release time
+#[test]
+fn throttle_rises_gradually_under_held_thrust() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    let response = app.world.resource::<ThrottleResponse>();
+    let (attack_ticks, release_ticks) = (response.attack / TICK, response.release / TICK);
+    let throttle = |app: &App| app.world.get::<EngineThrottle>(ship).unwrap().current;
+
+    hold_input(&mut app, 0, PlayerInput { thrust: 1.0, ..default() });
+    let mut readings = Vec::new();
+    while throttle(&app) < 1.0 {
+        step(&mut app, 1);
+        readings.push(throttle(&app));
+        assert!(readings.len() < 600, "the throttle never opened fully");
+    }
+    assert!(readings[0] > 0.0 && readings[0] < 0.25, "first tick opened to {}", readings[0]);
+    assert!(readings.windows(2).all(|pair| pair[1] > pair[0]), "throttle went {:?}", readings);
+    assert!((readings.len() as f32 - attack_ticks).abs() <= 1.0, "full after {} ticks", readings.len());
+
+    hold_input(&mut app, 0, PlayerInput::default());
+    step(&mut app, 1);
+    assert!(throttle(&app) > 0.0 && throttle(&app) < 1.0);
+    step(&mut app, release_ticks.ceil() as u32);
+    assert_eq!(throttle(&app), 0.0);
+}
+
+// With smooth motion on, a frame landing halfway to the next tick draws the ship halfway between
+// where the last two ticks left it, in place and in heading
+#[test]
+fn ship_drawn_between_ticks_at_mid_frame() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    app.world.resource_mut::<Settings>().smooth_motion = true;
+    let ship = spawn_test_ship(&mut app);
+    hold_input(&mut app, 0, PlayerInput { thrust: 1.0, rotate: 1.0, ..default() });
+    step(&mut app, 20);
+
+    let tick = Duration::from_secs_f64(1.0 / FIXED_TICK_RATE);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(tick + tick / 2));
+    step(&mut app, 1);
+
+    let drawn = *app.world.get::<Transform>(ship).unwrap();
+    let states = app.world.get::<PreviousTransform>(ship).unwrap();
+    let (from, to) = (states.previous, states.simulated);
+    assert!(states.blended);
+    assert_ne!(from.translation, to.translation);
+    let midpoint = from.translation.lerp(to.translation, 0.5);
+    assert!(drawn.translation.abs_diff_eq(midpoint, 1e-3), "drawn at {}, halfway is {}", drawn.translation, midpoint);
+    let halfway = from.rotation.angle_between(to.rotation) / 2.0;
+    assert!((drawn.rotation.angle_between(from.rotation) - halfway).abs() < 1e-3);
+    assert!((drawn.rotation.angle_between(to.rotation) - halfway).abs() < 1e-3);
+}
+
+// A held turn spins the ship a little faster every tick until it reaches the full rotation
+// speed, and letting go starts the next turn back at the slow end
+#[test]
+fn held_turn_speeds_up_and_resets_on_release() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    let heading = |app: &App| app.world.get::<Transform>(ship).unwrap().rotation.to_euler(EulerRot::ZYX).0;
+    let turn_for = |app: &mut App, ticks: usize| -> Vec<f32> {
+        hold_input(app, 0, PlayerInput { rotate: 1.0, ..default() });
+        (0..ticks)
+            .map(|_| {
+                let before = heading(app);
+                step(app, 1);
+                heading(app) - before
+            })
+            .collect()
+    };
+
+    let turns = turn_for(&mut app, 60);
+    assert!(turns[0] > 0.0);
+    assert!(turns.windows(2).take(20).all(|pair| pair[1] > pair[0]), "turned {:?}", turns);
+    assert!(turns[59] > turns[0] * 2.0, "turned {:?}", turns);
+    // The ramp stops at the full speed
+    assert!((turns[59] - turns[58]).abs() < 1e-5, "turned {:?}", turns);
+
+    hold_input(&mut app, 0, PlayerInput::default());
+    step(&mut app, 1);
+    let again = turn_for(&mut app, 1);
+    assert!((again[0] - turns[0]).abs() < 1e-5, "turned {} after letting go, {} at first", again[0], turns[0]);
+}
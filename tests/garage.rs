@@ -0,0 +1,38 @@
+// The garage between waves: spending credits on upgrades for the rest of the run
+use bevy::prelude::*;
+
+use star_ship_game::garage::{Credits, GarageButton, GaragePlugin, GarageSettings, Upgrade, Upgrades};
+use star_ship_game::headless::{headless_app, spawn_test_ship, step, stop_spawning};
+use star_ship_game::player::Engine;
+use star_ship_game::replay::ReplayRecorder;
+use star_ship_game::session::GameState;
+
+// Clicking the thrust upgrade in the garage makes the engine stronger and takes its cost off the
+// credits
+#[test]
+fn buying_thrust_upgrade_raises_thrust() {
+    let mut app = headless_app();
+    app.add_plugins(GaragePlugin).init_resource::<ReplayRecorder>();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    app.world.resource_mut::<Credits>().balance = 1000;
+    step(&mut app, 1);
+    let thrust = app.world.get::<Engine>(ship).unwrap().thrust;
+    let cost = app.world.resource::<Upgrades>().cost(Upgrade::Thrust, app.world.resource::<GarageSettings>());
+
+    app.world.resource_mut::<NextState<GameState>>().set(GameState::Upgrade);
+    step(&mut app, 1);
+    let button = app
+        .world
+        .query::<(Entity, &GarageButton)>()
+        .iter(&app.world)
+        .find(|(_, button)| button.0 == Upgrade::Thrust)
+        .map(|(entity, _)| entity)
+        .unwrap();
+    app.world.entity_mut(button).insert(Interaction::Pressed);
+    step(&mut app, 1);
+
+    assert!(app.world.get::<Engine>(ship).unwrap().thrust > thrust);
+    assert_eq!(app.world.resource::<Upgrades>().level(Upgrade::Thrust), 1);
+    assert_eq!(app.world.resource::<Credits>().balance, 1000 - cost);
+}
@@ -0,0 +1,59 @@
+// The headless harness itself: players driven by injected input for a number of fixed ticks
+use bevy::prelude::*;
+
+use star_ship_game::headless::{headless_app, hold_input, release_input, spawn_test_ship, step, stop_spawning};
+use star_ship_game::input::PlayerInput;
+use star_ship_game::physics::Velocity;
+
+// A ship held at full thrust speeds up along its nose, which starts out pointing up
+#[test]
+fn injected_thrust_moves_ship_forwards() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    hold_input(&mut app, 0, PlayerInput { thrust: 1.0, ..default() });
+    step(&mut app, 60);
+
+    let velocity = app.world.get::<Velocity>(ship).unwrap().as_vec2();
+    let position = app.world.get::<Transform>(ship).unwrap().translation.truncate();
+    assert!(velocity.y > 0.0 && velocity.x.abs() < 1e-3, "velocity {}", velocity);
+    assert!(position.y > 0.0 && position.x.abs() < 1e-3, "position {}", position);
+}
+
+// A ship held turning spins on the spot, and stops turning once the input is gone
+#[test]
+fn injected_rotation_turns_ship_in_place() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    hold_input(&mut app, 0, PlayerInput { rotate: 1.0, ..default() });
+    step(&mut app, 20);
+
+    let angle = |app: &App| app.world.get::<Transform>(ship).unwrap().rotation.to_euler(EulerRot::ZYX).0;
+    let turned = angle(&app);
+    assert!(turned > 0.0, "turned {}", turned);
+    assert_eq!(app.world.get::<Velocity>(ship).unwrap().as_vec2(), Vec2::ZERO);
+    assert_eq!(app.world.get::<Transform>(ship).unwrap().translation.truncate(), Vec2::ZERO);
+
+    hold_input(&mut app, 0, PlayerInput::default());
+    step(&mut app, 10);
+    assert_eq!(angle(&app), turned);
+    release_input(&mut app, 0);
+}
+
+// The same injected input gives the same ship, run after run
+#[test]
+fn same_input_gives_same_ship() {
+    let run = || {
+        let mut app = headless_app();
+        stop_spawning(&mut app);
+        let ship = spawn_test_ship(&mut app);
+        hold_input(&mut app, 0, PlayerInput { thrust: 1.0, rotate: 0.5, ..default() });
+        step(&mut app, 90);
+        (
+            *app.world.get::<Transform>(ship).unwrap(),
+            app.world.get::<Velocity>(ship).unwrap().as_vec2(),
+        )
+    };
+    assert_eq!(run(), run());
+}
@@ -0,0 +1,32 @@
+// Where hostiles come in: along the edges, never on top of a ship
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use star_ship_game::headless::HEADLESS_ARENA;
+use star_ship_game::hostiles::random_spawn_position;
+use star_ship_game::physics::nearest_wrapped;
+
+// Every spot picked is at least the minimum distance from the ships, measured across the seams,
+// wherever the ships are, including right up against an edge
+#[test]
+fn spawn_positions_keep_clear_of_ships() {
+    let half_extents = HEADLESS_ARENA / 2.0;
+    let min_distance = 200.0;
+    let mut rng = StdRng::seed_from_u64(3);
+    let layouts: [&[Vec2]; 4] = [
+        &[Vec2::ZERO],
+        &[Vec2::new(half_extents.x - 5.0, 0.0)],
+        &[Vec2::new(-half_extents.x + 10.0, half_extents.y - 10.0)],
+        &[Vec2::new(0.0, half_extents.y), Vec2::new(0.0, -half_extents.y + 50.0)],
+    ];
+    for ships in layouts {
+        for _ in 0..500 {
+            let position = random_spawn_position(&mut rng, half_extents, ships, min_distance);
+            for &ship in ships {
+                let distance = ship.distance(nearest_wrapped(position, ship, half_extents));
+                assert!(distance >= min_distance, "spawned {} only {} from a ship at {}", position, distance, ship);
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// The HUD: what the rings and bars around the arena show, where they sit on screen, and when the
+// fuel warning sounds
+use bevy::ecs::event::ManualEventReader;
+use bevy::prelude::*;
+use bevy::render::camera::{ManualTextureViews, camera_system};
+use bevy::window::{PrimaryWindow, WindowCreated, WindowResized, WindowResolution};
+use std::time::Duration;
+
+use star_ship_game::config::GameConfig;
+use star_ship_game::headless::{HEADLESS_ARENA, headless_app, hold_input, spawn_test_ship, step, stop_spawning};
+use star_ship_game::hud::{HudPlugin, LowFuelBeep, LowFuelWarning, health_bar_position, recharged_fraction};
+use star_ship_game::input::PlayerInput;
+use star_ship_game::physics::FIXED_TICK_RATE;
+use star_ship_game::player::Engine;
+use star_ship_game::weapons::BombSettings;
+
+// A ring fills by the share of its cooldown gone by, and disappears once the cooldown is over
+#[test]
+fn ring_fraction_matches_cooldown_elapsed() {
+    let mut cooldown = Timer::from_seconds(2.0, TimerMode::Once);
+    assert_eq!(recharged_fraction(&cooldown), Some(0.0));
+    cooldown.tick(Duration::from_secs_f32(0.5));
+    assert_eq!(recharged_fraction(&cooldown), Some(0.25));
+    cooldown.tick(Duration::from_secs_f32(1.5));
+    assert_eq!(recharged_fraction(&cooldown), None);
+}
+
+// The bomb's ring shows how much of its cooldown has run since it went off
+#[test]
+fn bomb_ring_follows_the_cooldown() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    spawn_test_ship(&mut app);
+    assert_eq!(recharged_fraction(&app.world.resource::<BombSettings>().cooldown), None);
+
+    hold_input(&mut app, 0, PlayerInput { bomb: true, ..default() });
+    step(&mut app, 1);
+    hold_input(&mut app, 0, PlayerInput::default());
+    step(&mut app, 2 * FIXED_TICK_RATE as u32);
+
+    let cooldown = &app.world.resource::<BombSettings>().cooldown;
+    let elapsed = cooldown.elapsed_secs() / cooldown.duration().as_secs_f32();
+    let fraction = recharged_fraction(cooldown).unwrap();
+    assert_eq!(fraction, elapsed);
+    assert!((fraction - 2.0 / cooldown.duration().as_secs_f32()).abs() < 0.01, "ring at {}", fraction);
+}
+
+// The low fuel warning comes on, with a beep, once the tank drops below the threshold, and goes
+// off the moment it is filled back above it
+#[test]
+fn low_fuel_warning_follows_the_threshold() {
+    let mut app = headless_app();
+    app.add_plugins(HudPlugin).init_asset::<Pitch>();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    let threshold = app.world.resource::<GameConfig>().low_fuel.threshold;
+    let set_fuel = |app: &mut App, share: f32| {
+        let mut engine = app.world.get_mut::<Engine>(ship).unwrap();
+        engine.fuel = engine.max_fuel * share;
+    };
+    let active = |app: &App| app.world.resource::<LowFuelWarning>().active;
+    let mut beeps = ManualEventReader::<LowFuelBeep>::default();
+    step(&mut app, 1);
+    assert!(!active(&app));
+
+    set_fuel(&mut app, threshold * 0.5);
+    step(&mut app, 1);
+    assert!(active(&app));
+    assert_eq!(beeps.read(app.world.resource::<Events<LowFuelBeep>>()).count(), 1);
+
+    set_fuel(&mut app, threshold * 1.5);
+    step(&mut app, 1);
+    assert!(!active(&app));
+}
+
+// A camera the size of the headless arena looking at the given spot, with its viewport worked out
+// as the renderer would for a window that size
+fn arena_camera(center: Vec2) -> (Camera, GlobalTransform) {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_asset::<Image>()
+        .add_event::<WindowCreated>()
+        .add_event::<WindowResized>()
+        .init_resource::<ManualTextureViews>()
+        .add_systems(Update, camera_system::<OrthographicProjection>);
+    app.world.spawn((
+        Window {
+            resolution: WindowResolution::new(HEADLESS_ARENA.x, HEADLESS_ARENA.y),
+            ..default()
+        },
+        PrimaryWindow,
+    ));
+    let camera = app.world.spawn(Camera2dBundle::default()).id();
+    app.update();
+    let camera = app.world.get::<Camera>(camera).unwrap().clone();
+    (camera, Transform::from_translation(center.extend(999.9)).into())
+}
+
+// A bar is placed where its spot in the world shows in the view, counted from the top left, and
+// left out when that spot is off the edge of the view or behind the camera
+#[test]
+fn health_bar_maps_world_to_screen() {
+    let (camera, camera_transform) = arena_camera(Vec2::new(100.0, 50.0));
+    let position = |world: Vec3| health_bar_position(&camera, &camera_transform, world);
+    let shows_at = |world: Vec3, screen: Vec2| {
+        let shown = position(world).unwrap_or_else(|| panic!("{} left out", world));
+        assert!(shown.abs_diff_eq(screen, 1e-3), "{} shows at {}, not {}", world, shown, screen);
+    };
+
+    shows_at(Vec3::new(100.0, 50.0, 0.0), HEADLESS_ARENA / 2.0);
+    shows_at(Vec3::new(300.0, 150.0, 0.0), Vec2::new(600.0, 200.0));
+    shows_at(Vec3::new(-300.0, -250.0, 0.0), Vec2::new(0.0, 600.0));
+    assert_eq!(position(Vec3::new(100.0 + HEADLESS_ARENA.x, 50.0, 0.0)), None);
+    assert_eq!(position(Vec3::new(100.0, 50.0 - HEADLESS_ARENA.y, 0.0)), None);
+    assert_eq!(position(Vec3::new(100.0, 50.0, 2000.0)), None);
+}
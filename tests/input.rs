@@ -0,0 +1,83 @@
+// Turning controls into player intent, for one player or two sharing a keyboard
+use bevy::prelude::*;
+
+use star_ship_game::headless::{headless_app, press_key, step, stop_spawning};
+use star_ship_game::input::{Action, KeyBindings, PlayerInput, StickSettings, apply_stick_response};
+use star_ship_game::physics::Velocity;
+use star_ship_game::player::{Player, PlayerCount, Starship};
+use star_ship_game::session::NewGameEvent;
+
+// A bomb or item press waiting for the next tick doesn't hold the gamepad off, but steering does
+#[test]
+fn pending_presses_are_not_held_input() {
+    assert!(!PlayerInput::default().is_held());
+    assert!(!PlayerInput { bomb: true, ..default() }.is_held());
+    assert!(!PlayerInput { aim: Some(1.0), ..default() }.is_held());
+    assert!(PlayerInput { thrust: 0.5, ..default() }.is_held());
+    assert!(PlayerInput { rotate: -1.0, bomb: true, ..default() }.is_held());
+    assert!(PlayerInput { fire: true, ..default() }.is_held());
+}
+
+// A stick resting inside the deadzone, right up to its edge, reads as nothing
+#[test]
+fn stick_at_deadzone_edge_reads_zero() {
+    let settings = StickSettings::default();
+    assert_eq!(apply_stick_response(0.0, &settings), 0.0);
+    assert_eq!(apply_stick_response(settings.inner_deadzone, &settings), 0.0);
+    assert_eq!(apply_stick_response(-settings.inner_deadzone, &settings), 0.0);
+    // Just past the edge the output starts from zero rather than jumping
+    let past = apply_stick_response(settings.inner_deadzone + 0.01, &settings);
+    assert!(past > 0.0 && past < 0.05, "just past the deadzone reads {}", past);
+}
+
+// A stick pushed to the saturation point or beyond reads as full deflection
+#[test]
+fn stick_fully_deflected_reads_full() {
+    let settings = StickSettings::default();
+    assert_eq!(apply_stick_response(settings.outer_saturation, &settings), 1.0);
+    assert_eq!(apply_stick_response(1.0, &settings), 1.0);
+}
+
+// Pushing the other way gives the same response, negated
+#[test]
+fn stick_negative_mirrors_positive() {
+    let settings = StickSettings::default();
+    for value in [0.2, 0.5, 0.8, 1.0] {
+        assert_eq!(apply_stick_response(-value, &settings), -apply_stick_response(value, &settings));
+    }
+    assert_eq!(apply_stick_response(-1.0, &settings), -1.0);
+}
+
+// A two-player game spawns a ship for each player, each flown by its own half of the keyboard
+#[test]
+fn two_players_get_their_own_keys() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    app.world.insert_resource(PlayerCount(2));
+    app.world.send_event(NewGameEvent::default());
+    step(&mut app, 1);
+
+    let mut ships: Vec<(usize, Entity)> = app
+        .world
+        .query_filtered::<(&Player, Entity), With<Starship>>()
+        .iter(&app.world)
+        .map(|(player, entity)| (player.id, entity))
+        .collect();
+    ships.sort();
+    assert_eq!(ships.iter().map(|(id, _)| *id).collect::<Vec<_>>(), [0, 1]);
+
+    let key_bindings = app.world.resource::<KeyBindings>();
+    assert_eq!(key_bindings.len(), 2);
+    for action in [Action::Thrust, Action::RotateLeft, Action::Fire, Action::Bomb] {
+        let (first, second) = (key_bindings[0].keys(action), key_bindings[1].keys(action));
+        assert!(first.iter().all(|key| !second.contains(key)), "both players use {:?} for {:?}", first, action);
+    }
+
+    // The second player's thrust moves the second ship alone
+    let thrust = key_bindings[1].keys(Action::Thrust)[0];
+    press_key(&mut app, thrust);
+    step(&mut app, 10);
+    let speed = |app: &App, ship: Entity| app.world.get::<Velocity>(ship).unwrap().as_vec2().length();
+    assert_eq!(speed(&app, ships[0].1), 0.0);
+    assert!(speed(&app, ships[1].1) > 0.0);
+}
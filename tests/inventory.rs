@@ -0,0 +1,41 @@
+// The item inventory: using an item spends one and does what the item does
+use bevy::prelude::*;
+
+use star_ship_game::combat::Health;
+use star_ship_game::headless::{headless_app, hold_input, spawn_test_ship, step, stop_spawning};
+use star_ship_game::input::PlayerInput;
+use star_ship_game::inventory::{Inventory, InventoryPlugin, Item};
+use star_ship_game::powerups::PowerUpsPlugin;
+
+// Ask for the first player's ship to use an item, for a single tick
+fn use_item(app: &mut App, item: Item) {
+    hold_input(app, 0, PlayerInput { item: Some(item), ..default() });
+    step(app, 1);
+    hold_input(app, 0, PlayerInput::default());
+    step(app, 1);
+}
+
+// A repair kit patches up the hull and is used up; with none left, asking again does nothing
+#[test]
+fn repair_kit_heals_once_per_kit() {
+    let mut app = headless_app();
+    app.add_plugins((PowerUpsPlugin, InventoryPlugin));
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    app.world.resource_mut::<Inventory>().repair_kits = 1;
+    {
+        let mut health = app.world.get_mut::<Health>(ship).unwrap();
+        health.current = health.max / 4.0;
+    }
+    let health = |app: &App| app.world.get::<Health>(ship).unwrap().current;
+    let damaged = health(&app);
+
+    use_item(&mut app, Item::RepairKit);
+    let repaired = health(&app);
+    assert!(repaired > damaged, "health went from {} to {}", damaged, repaired);
+    assert_eq!(app.world.resource::<Inventory>().repair_kits, 0);
+
+    use_item(&mut app, Item::RepairKit);
+    assert_eq!(health(&app), repaired);
+    assert_eq!(app.world.resource::<Inventory>().repair_kits, 0);
+}
@@ -0,0 +1,29 @@
+// Booting through the loading screen, even when the images aren't where they should be
+use bevy::prelude::*;
+use std::time::{Duration, Instant};
+
+use star_ship_game::headless::loading_app;
+use star_ship_game::player::{ShipAssets, Starship};
+use star_ship_game::session::GameState;
+
+// With the assets folder pointing nowhere, the game still gets past the loading screen with a
+// placeholder for the ship's sprite sheet, and flies
+#[test]
+fn bogus_asset_root_boots_with_placeholders() {
+    // Somewhere outside the repository, since the engine makes the folder it is pointed at
+    let asset_root = std::env::temp_dir().join("star-ship_game-no-such-assets");
+    let mut app = loading_app(&asset_root.to_string_lossy());
+    // The images load on other threads, so give them real time to fail
+    let start = Instant::now();
+    while *app.world.resource::<State<GameState>>().get() != GameState::Playing {
+        assert!(start.elapsed() < Duration::from_secs(10), "stuck in {:?}", app.world.resource::<State<GameState>>().get());
+        app.update();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    app.update();
+
+    let atlas = app.world.resource::<ShipAssets>().texture_atlas.clone();
+    let texture = app.world.resource::<Assets<TextureAtlas>>().get(&atlas).unwrap().texture.clone();
+    assert!(app.world.resource::<Assets<Image>>().get(&texture).is_some());
+    assert_eq!(app.world.query::<&Starship>().iter(&app.world).count(), 1);
+}
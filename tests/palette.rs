@@ -0,0 +1,29 @@
+// The color scheme: what is spawned, and what is already in play, follows the preset picked
+use bevy::prelude::*;
+
+use star_ship_game::headless::{headless_app, step, stop_spawning, with_commands};
+use star_ship_game::hostiles::spawn_asteroid;
+use star_ship_game::palette::{ColorPreset, ColorScheme};
+use star_ship_game::settings::Settings;
+
+// Picking another preset in the settings colors new asteroids in it, and recolors the old ones
+#[test]
+fn changing_scheme_changes_spawn_color() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let color = |app: &App, entity: Entity| app.world.get::<Sprite>(entity).unwrap().color;
+    let old = with_commands(&mut app, |commands| spawn_asteroid(commands, Vec2::ZERO, Vec2::ZERO, 20.0));
+    step(&mut app, 1);
+    let default_color = ColorScheme::preset(ColorPreset::Default).asteroid;
+    assert_eq!(color(&app, old), default_color);
+
+    app.world.resource_mut::<Settings>().color_scheme = ColorPreset::Deuteranopia;
+    step(&mut app, 1);
+    let new = with_commands(&mut app, |commands| spawn_asteroid(commands, Vec2::new(100.0, 0.0), Vec2::ZERO, 20.0));
+    step(&mut app, 1);
+
+    let deuteranopia_color = ColorScheme::preset(ColorPreset::Deuteranopia).asteroid;
+    assert_ne!(deuteranopia_color, default_color);
+    assert_eq!(color(&app, new), deuteranopia_color);
+    assert_eq!(color(&app, old), deuteranopia_color);
+}
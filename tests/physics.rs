@@ -0,0 +1,70 @@
+// Pure physics helpers: leading a moving target, and where wrap copies show
+use bevy::prelude::*;
+
+use star_ship_game::headless::HEADLESS_ARENA;
+use star_ship_game::physics::{intercept_time, wrap_ghost_offsets};
+
+// A target sitting still is met after its distance over the shot speed
+#[test]
+fn intercept_of_still_target() {
+    let time = intercept_time(Vec2::new(100.0, 0.0), Vec2::ZERO, 50.0).unwrap();
+    assert!((time - 2.0).abs() < 1e-5, "time {}", time);
+}
+
+// A target coming in is met sooner, and one crossing is met where the shot and target arrive together
+#[test]
+fn intercept_of_moving_target() {
+    let closing = intercept_time(Vec2::new(100.0, 0.0), Vec2::new(-50.0, 0.0), 100.0).unwrap();
+    assert!((closing - 2.0 / 3.0).abs() < 1e-5, "time {}", closing);
+
+    let offset = Vec2::new(100.0, 0.0);
+    let velocity = Vec2::new(0.0, 30.0);
+    let time = intercept_time(offset, velocity, 50.0).unwrap();
+    let meeting = offset + velocity * time;
+    assert!((meeting.length() - 50.0 * time).abs() < 1e-3, "meeting {} at {}", meeting, time);
+}
+
+// A target exactly as fast as the shot can only be met while it comes towards the shooter
+#[test]
+fn intercept_at_equal_speed() {
+    let time = intercept_time(Vec2::new(100.0, 0.0), Vec2::new(-50.0, 0.0), 50.0).unwrap();
+    assert!((time - 1.0).abs() < 1e-5, "time {}", time);
+    assert_eq!(intercept_time(Vec2::new(100.0, 0.0), Vec2::new(50.0, 0.0), 50.0), None);
+    assert_eq!(intercept_time(Vec2::new(100.0, 0.0), Vec2::new(0.0, 50.0), 50.0), None);
+}
+
+// A target running away faster than the shot is never met
+#[test]
+fn intercept_of_faster_target_has_no_solution() {
+    assert_eq!(intercept_time(Vec2::new(100.0, 0.0), Vec2::new(80.0, 0.0), 50.0), None);
+    assert_eq!(intercept_time(Vec2::new(0.0, -100.0), Vec2::new(60.0, -60.0), 50.0), None);
+}
+
+// Something within the margin of an edge shows a copy exactly one arena over on the far side,
+// two at a corner, and none while it is clear of the edges
+#[test]
+fn wrap_ghost_sits_across_the_arena() {
+    let half_extents = HEADLESS_ARENA / 2.0;
+    let margin = 48.0;
+
+    let near_right = Vec2::new(half_extents.x - 10.0, 30.0);
+    let [across_width, across_height] = wrap_ghost_offsets(near_right, half_extents, margin);
+    let ghost = near_right + across_width.unwrap();
+    assert_eq!(ghost, Vec2::new(-half_extents.x - 10.0, 30.0));
+    assert_eq!(across_height, None);
+
+    let near_bottom = Vec2::new(-50.0, -half_extents.y + 5.0);
+    assert_eq!(
+        wrap_ghost_offsets(near_bottom, half_extents, margin),
+        [None, Some(Vec2::new(0.0, HEADLESS_ARENA.y))]
+    );
+
+    let near_corner = half_extents - Vec2::splat(1.0);
+    assert_eq!(
+        wrap_ghost_offsets(near_corner, half_extents, margin),
+        [Some(Vec2::new(-HEADLESS_ARENA.x, 0.0)), Some(Vec2::new(0.0, -HEADLESS_ARENA.y))]
+    );
+
+    let clear = half_extents - Vec2::splat(margin + 1.0);
+    assert_eq!(wrap_ghost_offsets(clear, half_extents, margin), [None, None]);
+}
@@ -0,0 +1,75 @@
+// The player's ship: how each class starts out, which nozzles fire, and carrying on without one
+use bevy::prelude::*;
+
+use star_ship_game::headless::{headless_app, hold_input, spawn_test_ship, step, stop_spawning};
+use star_ship_game::input::PlayerInput;
+use star_ship_game::player::{Engine, Nozzle, ShipClass, Starship};
+use star_ship_game::weapons::Weapon;
+
+// With no ship in the arena, as between losing one and the respawn, every control can be held
+// through a tick without anything panicking
+#[test]
+fn ticks_without_ship_do_not_panic() {
+    let mut app = headless_app();
+    assert_eq!(app.world.query::<&Starship>().iter(&app.world).count(), 0);
+    hold_input(&mut app, 0, PlayerInput {
+        rotate: 1.0,
+        thrust: 1.0,
+        strafe: 1.0,
+        fire: true,
+        bomb: true,
+        aim: Some(1.0),
+        ..default()
+    });
+    step(&mut app, 1);
+}
+
+// Picking a class fits the next ship out with that class's starting values
+#[test]
+fn ship_classes_spawn_with_their_loadout() {
+    for ship_class in [ShipClass::Scout, ShipClass::Tank, ShipClass::Racer] {
+        let mut app = headless_app();
+        stop_spawning(&mut app);
+        app.world.insert_resource(ship_class);
+        step(&mut app, 1);
+        let ship = spawn_test_ship(&mut app);
+
+        let loadout = ship_class.loadout();
+        let starship = app.world.get::<Starship>(ship).unwrap();
+        assert_eq!((starship.rotation_speed, starship.max_speed), (loadout.rotation_speed, loadout.max_speed));
+        let engine = app.world.get::<Engine>(ship).unwrap();
+        assert_eq!(
+            (engine.thrust, engine.strafe_thrust, engine.fuel, engine.max_fuel),
+            (loadout.thrust, loadout.strafe_thrust, loadout.fuel, loadout.max_fuel),
+            "{:?}", ship_class,
+        );
+        let cooldown = app.world.get::<Weapon>(ship).unwrap().cooldown.duration().as_secs_f32();
+        assert_eq!(cooldown, loadout.fire_cooldown);
+    }
+}
+
+// Strafing lights the flank nozzle that pushes the ship that way, and only that one
+#[test]
+fn strafe_fires_matching_nozzle() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    let firing = |app: &mut App| -> Vec<Nozzle> {
+        app.world
+            .query::<(&Nozzle, &Parent, &Visibility)>()
+            .iter(&app.world)
+            .filter(|(_, parent, visibility)| parent.get() == ship && **visibility != Visibility::Hidden)
+            .map(|(nozzle, _, _)| *nozzle)
+            .collect()
+    };
+    step(&mut app, 1);
+    assert_eq!(firing(&mut app), []);
+
+    hold_input(&mut app, 0, PlayerInput { strafe: 1.0, ..default() });
+    step(&mut app, 1);
+    assert_eq!(firing(&mut app), [Nozzle::StrafeRight]);
+
+    hold_input(&mut app, 0, PlayerInput { strafe: -1.0, ..default() });
+    step(&mut app, 1);
+    assert_eq!(firing(&mut app), [Nozzle::StrafeLeft]);
+}
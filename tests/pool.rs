@@ -0,0 +1,125 @@
+// The entity pool: a shot or particle going back in and coming out again starts its next use clean
+use bevy::ecs::system::CommandQueue;
+use bevy::prelude::*;
+use std::time::{Duration, Instant};
+
+use star_ship_game::combat::{DamageFlash, Invulnerable, LastHitBy};
+use star_ship_game::headless::{headless_app, with_commands};
+use star_ship_game::physics::{Lifetime, Velocity};
+use star_ship_game::pool::{Pool, PoolKind, Pooled, release_pooled, spawn_pooled};
+
+// A particle at the given spot drifting at the given speed, as the pool would be handed one
+fn particle(position: Vec2, velocity: Vec2) -> (SpriteBundle, Velocity) {
+    (
+        SpriteBundle {
+            transform: Transform::from_translation(position.extend(0.0)),
+            ..default()
+        },
+        Velocity {
+            x: velocity.x,
+            y: velocity.y,
+        },
+    )
+}
+
+// Whether the entity carries any of what gameplay adds to a shot or particle after it spawns
+fn tagged(app: &App, entity: Entity) -> bool {
+    let entity = app.world.entity(entity);
+    entity.contains::<Invulnerable>()
+        || entity.contains::<DamageFlash>()
+        || entity.contains::<LastHitBy>()
+        || entity.contains::<Lifetime>()
+}
+
+// Everything added to an entity during one use is gone once it is back in the pool, and stays gone
+// when the next spawn of its kind takes it out again
+#[test]
+fn reused_entity_loses_what_its_last_use_added() {
+    let mut app = headless_app();
+    with_commands(&mut app, |commands| {
+        spawn_pooled(commands, PoolKind::Particle, particle(Vec2::new(10.0, 20.0), Vec2::new(5.0, 0.0)));
+    });
+    let entity = app
+        .world
+        .query_filtered::<Entity, With<Pooled>>()
+        .single(&app.world);
+    app.world.entity_mut(entity).insert((
+        Invulnerable,
+        DamageFlash::default(),
+        LastHitBy(1),
+        Lifetime(Timer::from_seconds(1.0, TimerMode::Once)),
+    ));
+    assert!(tagged(&app, entity));
+
+    with_commands(&mut app, |commands| release_pooled(commands, entity));
+    assert!(app.world.get::<Pooled>(entity).unwrap().free);
+    assert!(!tagged(&app, entity));
+    assert_eq!(app.world.get::<Visibility>(entity), Some(&Visibility::Hidden));
+    assert_eq!(app.world.get::<Velocity>(entity).unwrap().as_vec2(), Vec2::ZERO);
+    assert_eq!(app.world.resource::<Pool>().len(PoolKind::Particle), 1);
+
+    with_commands(&mut app, |commands| {
+        spawn_pooled(commands, PoolKind::Particle, particle(Vec2::new(-30.0, 40.0), Vec2::new(0.0, -8.0)));
+    });
+    assert_eq!(app.world.resource::<Pool>().reused, 1);
+    assert_eq!(app.world.resource::<Pool>().len(PoolKind::Particle), 0);
+    assert!(app.world.get::<Pooled>(entity).unwrap().in_use());
+    assert!(!tagged(&app, entity));
+    assert_eq!(app.world.get::<Visibility>(entity), Some(&Visibility::Inherited));
+    assert_eq!(app.world.get::<Transform>(entity).unwrap().translation, Vec3::new(-30.0, 40.0, 0.0));
+    assert_eq!(app.world.get::<Velocity>(entity).unwrap().as_vec2(), Vec2::new(0.0, -8.0));
+}
+
+// Time spent spawning the given number of particles a frame and ending the last frame's, over the
+// given frames, through the pool or by spawning and despawning
+fn churn(pooled: bool, per_frame: usize, frames: usize) -> Duration {
+    let mut world = World::new();
+    world.init_resource::<Pool>();
+    let mut live: Vec<Entity> = Vec::new();
+    let mut elapsed = Duration::ZERO;
+    for frame in 0..frames {
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        for entity in live.drain(..) {
+            if pooled {
+                release_pooled(&mut commands, entity);
+            } else {
+                commands.entity(entity).despawn();
+            }
+        }
+        for index in 0..per_frame {
+            let bundle = particle(Vec2::new(index as f32, frame as f32), Vec2::X);
+            if pooled {
+                spawn_pooled(&mut commands, PoolKind::Particle, bundle);
+            } else {
+                live.push(commands.spawn(bundle).id());
+            }
+        }
+        let start = Instant::now();
+        queue.apply(&mut world);
+        elapsed += start.elapsed();
+        if pooled {
+            let mut query = world.query::<(Entity, &Pooled)>();
+            live.extend(query.iter(&world).filter(|(_, pooled)| pooled.in_use()).map(|(entity, _)| entity));
+        }
+    }
+    elapsed
+}
+
+// Timing of particle churn with and without the pool, at the rate of a busy fight and of a stress
+// scene; run with `cargo test --release -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn bench_pooled_vs_spawned() {
+    for per_frame in [84, 1000] {
+        let frames = 2000;
+        let pooled = churn(true, per_frame, frames);
+        let spawned = churn(false, per_frame, frames);
+        println!(
+            "{} particles a frame: pooled {:?}, spawned {:?} per frame",
+            per_frame,
+            pooled / frames as u32,
+            spawned / frames as u32
+        );
+    }
+}
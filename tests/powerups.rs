@@ -0,0 +1,64 @@
+// Power-ups: what flying into one does for the ship, and how a magnet draws them in
+use bevy::prelude::*;
+
+use star_ship_game::combat::Health;
+use star_ship_game::headless::{headless_app, spawn_test_ship, step, stop_spawning, with_commands};
+use star_ship_game::inventory::Inventory;
+use star_ship_game::powerups::{Magnet, PowerUp, PowerUpKind, PowerUpSettings, PowerUpsPlugin, spawn_power_up};
+
+// A headless app that also drops and collects power-ups, with a ship in the middle
+fn powerup_app() -> (App, Entity) {
+    let mut app = headless_app();
+    app.add_plugins(PowerUpsPlugin).init_resource::<Inventory>();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    (app, ship)
+}
+
+// Picking up a Health power-up repairs the hull and uses the power-up up
+#[test]
+fn health_power_up_repairs_hull() {
+    let (mut app, ship) = powerup_app();
+    let damaged = {
+        let mut health = app.world.get_mut::<Health>(ship).unwrap();
+        health.current = health.max / 2.0;
+        health.current
+    };
+    let restore = app.world.resource::<PowerUpSettings>().health_restore;
+    with_commands(&mut app, |commands| {
+        spawn_power_up(commands, PowerUpKind::Health, Vec2::ZERO, Vec2::ZERO, 10.0);
+    });
+    step(&mut app, 1);
+
+    assert_eq!(app.world.get::<Health>(ship).unwrap().current, damaged + restore);
+    assert_eq!(app.world.query::<&PowerUp>().iter(&app.world).count(), 0);
+}
+
+// With a magnet on, a pickup within its reach closes in on the ship tick after tick, while one out
+// of reach stays where it is
+#[test]
+fn magnet_pulls_pickup_in_reach() {
+    let (mut app, ship) = powerup_app();
+    app.world.entity_mut(ship).insert(Magnet {
+        radius: 200.0,
+        strength: 300.0,
+        timer: Timer::from_seconds(10.0, TimerMode::Once),
+    });
+    let (near, far) = with_commands(&mut app, |commands| {
+        (
+            spawn_power_up(commands, PowerUpKind::Fuel, Vec2::new(150.0, 0.0), Vec2::ZERO, 10.0),
+            spawn_power_up(commands, PowerUpKind::Fuel, Vec2::new(-250.0, 0.0), Vec2::ZERO, 10.0),
+        )
+    });
+    let position = |app: &App, pickup: Entity| app.world.get::<Transform>(pickup).unwrap().translation.truncate();
+
+    let mut distance = position(&app, near).length();
+    for _ in 0..3 {
+        step(&mut app, 10);
+        let closer = position(&app, near).length();
+        assert!(closer < distance, "pickup went from {} to {} away", distance, closer);
+        distance = closer;
+    }
+    assert_eq!(position(&app, near).y, 0.0);
+    assert_eq!(position(&app, far), Vec2::new(-250.0, 0.0));
+}
@@ -0,0 +1,87 @@
+// The practice range: dummies that take hits and show what each one took off
+use bevy::prelude::*;
+
+use star_ship_game::combat::{DamageEvent, DamageSource, Health};
+use star_ship_game::headless::{headless_app, hold_input, spawn_test_ship, step, stop_spawning};
+use star_ship_game::input::PlayerInput;
+use star_ship_game::practice::{DamageText, TargetDummy};
+use star_ship_game::session::GameMode;
+use star_ship_game::weapons::Weapon;
+
+// A headless app on the practice range, with its dummies up
+fn practice_app() -> App {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    app.world.resource_mut::<NextState<GameMode>>().set(GameMode::Practice);
+    step(&mut app, 2);
+    app
+}
+
+// Every damage number floating in the arena
+fn damage_texts(app: &mut App) -> Vec<f32> {
+    app.world
+        .query::<&DamageText>()
+        .iter(&app.world)
+        .map(|damage_text| damage_text.amount)
+        .collect()
+}
+
+// The dummy straight ahead of a ship at the middle of the arena
+fn dummy_ahead(app: &mut App) -> Entity {
+    app.world
+        .query_filtered::<(Entity, &Transform), With<TargetDummy>>()
+        .iter(&app.world)
+        .find(|(_, transform)| transform.translation.x == 0.0)
+        .map(|(entity, _)| entity)
+        .unwrap()
+}
+
+// Only the practice range has dummies
+#[test]
+fn dummies_stand_only_on_the_practice_range() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    step(&mut app, 2);
+    assert_eq!(app.world.query::<&TargetDummy>().iter(&app.world).count(), 0);
+
+    let mut app = practice_app();
+    assert_eq!(app.world.query::<&TargetDummy>().iter(&app.world).count(), 3);
+}
+
+// A shot landing on a dummy floats the shot's damage above it
+#[test]
+fn hitting_a_dummy_shows_the_damage() {
+    let mut app = practice_app();
+    let ship = spawn_test_ship(&mut app);
+    let damage = app.world.get::<Weapon>(ship).unwrap().damage();
+    hold_input(&mut app, 0, PlayerInput { fire: true, ..default() });
+    for _ in 0..120 {
+        step(&mut app, 1);
+        if !damage_texts(&mut app).is_empty() {
+            break;
+        }
+    }
+    hold_input(&mut app, 0, PlayerInput::default());
+
+    let texts = damage_texts(&mut app);
+    assert!(!texts.is_empty(), "no damage number after firing at the dummy");
+    assert!(texts.iter().all(|amount| *amount == damage), "shown {:?}, dealt {}", texts, damage);
+}
+
+// A hit bigger than the dummy's health shows only the health it took off, and the dummy stays up
+#[test]
+fn overkill_shows_the_health_taken() {
+    let mut app = practice_app();
+    let dummy = dummy_ahead(&mut app);
+    app.world.get_mut::<Health>(dummy).unwrap().current = 250.0;
+    app.world.send_event(DamageEvent {
+        target: dummy,
+        amount: f32::MAX,
+        source: DamageSource::PlayerBullet { owner: 0 },
+    });
+    step(&mut app, 2);
+
+    assert_eq!(damage_texts(&mut app), vec![250.0]);
+    let health = app.world.get::<Health>(dummy).unwrap();
+    assert_eq!(health.current, health.max);
+}
@@ -0,0 +1,77 @@
+// Seeded randomness: the same seed plays out the same run
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use star_ship_game::headless::{headless_app, spawn_test_ship, step};
+use star_ship_game::hostiles::{Asteroid, AsteroidSpawner, EmpMine, Hostile};
+use star_ship_game::physics::{Collider, Velocity};
+use star_ship_game::session::GameRng;
+
+// Where each asteroid was on its first tick in play, in the order they came, over the given
+// ticks of a run from the seed
+fn asteroid_spawns(seed: u64, ticks: u32) -> Vec<Vec2> {
+    let mut app = headless_app();
+    app.world.resource_mut::<GameRng>().reseed(seed);
+    let mut seen = HashSet::new();
+    let mut spawns = Vec::new();
+    for _ in 0..ticks {
+        step(&mut app, 1);
+        let mut asteroids = app.world.query_filtered::<(Entity, &Transform), With<Asteroid>>();
+        for (entity, transform) in asteroids.iter(&app.world) {
+            if seen.insert(entity) {
+                spawns.push(transform.translation.truncate());
+            }
+        }
+    }
+    spawns
+}
+
+// Two runs from the same seed bring their asteroids in at exactly the same spots
+#[test]
+fn same_seed_spawns_asteroids_in_same_places() {
+    let first = asteroid_spawns(12345, 600);
+    assert!(first.len() >= 3, "only {} asteroids came in", first.len());
+    assert_eq!(asteroid_spawns(12345, 600), first);
+    assert_ne!(asteroid_spawns(54321, 600), first);
+}
+
+// One hostile coming in: whether it is a mine, where it is, how it moves and how big it is
+type Spawn = (bool, Vec2, Vec2, f32);
+
+// Every hostile that came in, in the order they came, over the given ticks of a busy run from the
+// seed, with a ship to keep clear of and mines among the asteroids
+fn spawn_sequence(seed: u64, ticks: u32) -> Vec<Spawn> {
+    let mut app = headless_app();
+    {
+        let mut spawner = app.world.resource_mut::<AsteroidSpawner>();
+        spawner.interval = 0.25;
+        spawner.emp_chance = 0.3;
+    }
+    spawn_test_ship(&mut app);
+    app.world.resource_mut::<GameRng>().reseed(seed);
+    let mut seen = HashSet::new();
+    let mut spawns = Vec::new();
+    for _ in 0..ticks {
+        step(&mut app, 1);
+        let mut hostiles = app
+            .world
+            .query_filtered::<(Entity, &Transform, &Velocity, &Collider, Has<EmpMine>), With<Hostile>>();
+        for (entity, transform, velocity, collider, mine) in hostiles.iter(&app.world) {
+            if seen.insert(entity) {
+                spawns.push((mine, transform.translation.truncate(), velocity.as_vec2(), collider.radius));
+            }
+        }
+    }
+    spawns
+}
+
+// Two runs from the same seed bring in the same hostiles, of the same kinds and sizes, at the same
+// spots and speeds, in the same order
+#[test]
+fn same_seed_gives_same_spawn_sequence() {
+    let first = spawn_sequence(777, 600);
+    assert!(first.len() >= 10, "only {} hostiles came in", first.len());
+    assert!(first.iter().any(|(mine, ..)| *mine) && first.iter().any(|(mine, ..)| !*mine));
+    assert_eq!(spawn_sequence(777, 600), first);
+    assert_ne!(spawn_sequence(778, 600), first);
+}
@@ -0,0 +1,88 @@
+// The run around the simulation: slow motion, and pausing when the window goes to the background
+use bevy::prelude::*;
+use bevy::window::WindowFocused;
+
+use star_ship_game::headless::{headless_app, step, with_commands};
+use star_ship_game::input::PauseToggleEvent;
+use star_ship_game::physics::{FIXED_TICK_RATE, Velocity};
+use star_ship_game::session::{AutoPause, GameState, TimeScale};
+
+// Tell the game the window gained or lost focus
+fn focus(app: &mut App, focused: bool) {
+    app.world.send_event(WindowFocused {
+        window: Entity::PLACEHOLDER,
+        focused,
+    });
+}
+
+// The state the game is in
+fn state(app: &App) -> GameState {
+    *app.world.resource::<State<GameState>>().get()
+}
+
+// Losing focus pauses the game, and it carries on by itself once the window is back and the
+// countdown has run out
+#[test]
+fn losing_focus_pauses_until_refocused() {
+    let mut app = headless_app();
+    focus(&mut app, false);
+    step(&mut app, 2);
+    assert_eq!(state(&app), GameState::Paused);
+
+    focus(&mut app, true);
+    step(&mut app, 2);
+    assert_eq!(state(&app), GameState::Paused);
+    let resume_delay = app.world.resource::<AutoPause>().resume_delay;
+    step(&mut app, (resume_delay as f64 * FIXED_TICK_RATE) as u32 + 10);
+    assert_eq!(state(&app), GameState::Playing);
+}
+
+// A pause the player chose isn't ended by the window losing and regaining focus
+#[test]
+fn refocusing_keeps_a_manual_pause() {
+    let mut app = headless_app();
+    app.world.send_event(PauseToggleEvent);
+    step(&mut app, 2);
+    assert_eq!(state(&app), GameState::Paused);
+
+    focus(&mut app, false);
+    step(&mut app, 2);
+    focus(&mut app, true);
+    step(&mut app, 300);
+    assert_eq!(state(&app), GameState::Paused);
+}
+
+// Speed of the drifting entity, in pixels/second
+const DRIFT_SPEED: f32 = 100.0;
+
+// How far something drifting at a steady speed gets over two seconds of real time, with the game
+// clock running at the given speed
+fn distance_in_two_seconds(speed: f32) -> f32 {
+    let mut app = headless_app();
+    {
+        let mut time_scale = app.world.resource_mut::<TimeScale>();
+        time_scale.slow_motion_speed = speed;
+        time_scale.slowed = true;
+    }
+    let entity = with_commands(&mut app, |commands| {
+        commands
+            .spawn((TransformBundle::default(), Velocity { x: DRIFT_SPEED, y: 0.0 }))
+            .id()
+    });
+    // The clock picks up the new speed on the first update
+    step(&mut app, 1);
+    let start = app.world.get::<Transform>(entity).unwrap().translation;
+    step(&mut app, 2 * FIXED_TICK_RATE as u32);
+    app.world.get::<Transform>(entity).unwrap().translation.distance(start)
+}
+
+// Running the game clock at half speed halves how far things get in the same real time, give or
+// take the one fixed tick the halved clock may not have made up yet
+#[test]
+fn half_speed_halves_the_distance() {
+    let full = distance_in_two_seconds(1.0);
+    let half = distance_in_two_seconds(0.5);
+    let one_tick = DRIFT_SPEED / FIXED_TICK_RATE as f32;
+    assert!(full > 0.0);
+    assert!((half - full / 2.0).abs() <= one_tick + 1e-3, "half speed went {} against {}", half, full);
+}
@@ -0,0 +1,148 @@
+// The spatial grid against checking every pair: the same overlaps, found faster
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::Instant;
+
+use star_ship_game::headless::HEADLESS_ARENA;
+use star_ship_game::physics::{SpatialGrid, nearest_wrapped};
+
+// Hit circles scattered over the arena: entity, position and radius
+fn scatter(count: u32, seed: u64) -> Vec<(Entity, Vec2, f32)> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let half_extents = HEADLESS_ARENA / 2.0;
+    (0..count)
+        .map(|index| {
+            let position = Vec2::new(
+                rng.gen_range(-half_extents.x..half_extents.x),
+                rng.gen_range(-half_extents.y..half_extents.y),
+            );
+            (Entity::from_raw(index), position, rng.gen_range(4.0..20.0))
+        })
+        .collect()
+}
+
+// Every overlapping pair, found by checking each circle against every other
+fn naive_pairs(circles: &[(Entity, Vec2, f32)]) -> Vec<(Entity, Entity)> {
+    let half_extents = HEADLESS_ARENA / 2.0;
+    let mut pairs = Vec::new();
+    for (index, &(a, position_a, radius_a)) in circles.iter().enumerate() {
+        for &(b, position_b, radius_b) in &circles[index + 1..] {
+            let position_b = nearest_wrapped(position_b, position_a, half_extents);
+            if position_a.distance(position_b) <= radius_a + radius_b {
+                pairs.push((a.min(b), a.max(b)));
+            }
+        }
+    }
+    pairs
+}
+
+// Every overlapping pair, found through the grid the way the collision systems use it: each
+// pair only from the circle with the lower entity
+fn grid_pairs(grid: &mut SpatialGrid, circles: &[(Entity, Vec2, f32)]) -> Vec<(Entity, Entity)> {
+    let largest = circles.iter().map(|(_, _, radius)| *radius).fold(0.0, f32::max);
+    grid.clear(largest, HEADLESS_ARENA / 2.0);
+    for &(entity, position, radius) in circles {
+        grid.insert(entity, position, radius);
+    }
+    let mut pairs = Vec::new();
+    for &(a, position_a, radius_a) in circles {
+        for (b, position_b, radius_b) in grid.query(position_a, radius_a) {
+            if b > a && position_a.distance(position_b) <= radius_a + radius_b {
+                pairs.push((a, b));
+            }
+        }
+    }
+    pairs
+}
+
+// The pairs as a set, checking none was found twice
+fn unique(pairs: Vec<(Entity, Entity)>) -> HashSet<(Entity, Entity)> {
+    let count = pairs.len();
+    let set: HashSet<_> = pairs.into_iter().collect();
+    assert_eq!(set.len(), count, "a pair was found more than once");
+    set
+}
+
+// Time finding the pairs both ways over the given number of rounds, and check the grid is faster
+fn bench_naive_vs_grid(count: u32, rounds: u32) {
+    let circles = scatter(count, 7);
+
+    let start = Instant::now();
+    for _ in 0..rounds {
+        std::hint::black_box(naive_pairs(std::hint::black_box(&circles)));
+    }
+    let naive = start.elapsed() / rounds;
+
+    let mut grid = SpatialGrid::default();
+    let start = Instant::now();
+    for _ in 0..rounds {
+        std::hint::black_box(grid_pairs(&mut grid, std::hint::black_box(&circles)));
+    }
+    let grid = start.elapsed() / rounds;
+
+    println!("{} circles: naive {:?}, grid {:?} per tick", count, naive, grid);
+    assert!(grid < naive, "grid {:?} is no faster than naive {:?}", grid, naive);
+}
+
+// The grid finds exactly the overlaps checking every pair does, with 500 circles
+#[test]
+fn grid_matches_naive_at_500() {
+    let circles = scatter(500, 7);
+    let naive = unique(naive_pairs(&circles));
+    assert!(!naive.is_empty());
+    assert_eq!(unique(grid_pairs(&mut SpatialGrid::default(), &circles)), naive);
+}
+
+// Timing of both ways with 500 circles; run with `cargo test --release -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn bench_naive_vs_grid_at_500() {
+    bench_naive_vs_grid(500, 200);
+}
+
+// The grid still finds exactly the overlaps checking every pair does with 1000 circles, packed
+// closely enough that many of them touch across the seams
+#[test]
+fn grid_matches_naive_at_1000() {
+    let circles = scatter(1000, 11);
+    let naive = unique(naive_pairs(&circles));
+    let across_seam = naive.iter().any(|(a, b)| {
+        let position = |entity: &Entity| circles[entity.index() as usize].1;
+        position(a).distance(position(b)) > 40.0
+    });
+    assert!(across_seam, "no pair touches across a seam");
+    assert_eq!(unique(grid_pairs(&mut SpatialGrid::default(), &circles)), naive);
+}
+
+// Circles touching over an edge or a corner of the arena are found as a pair once, not once
+// for every copy of the cell searched across the seam
+#[test]
+fn pairs_across_wrap_seam_found_once() {
+    let half_extents = HEADLESS_ARENA / 2.0;
+    let circles = [
+        // Across the left and right edges
+        (Entity::from_raw(0), Vec2::new(-half_extents.x + 3.0, 0.0), 10.0),
+        (Entity::from_raw(1), Vec2::new(half_extents.x - 3.0, 0.0), 10.0),
+        // Across the top and bottom edges
+        (Entity::from_raw(2), Vec2::new(100.0, half_extents.y - 2.0), 10.0),
+        (Entity::from_raw(3), Vec2::new(100.0, -half_extents.y + 2.0), 10.0),
+        // In two opposite corners
+        (Entity::from_raw(4), Vec2::new(-half_extents.x + 2.0, -half_extents.y + 2.0), 10.0),
+        (Entity::from_raw(5), Vec2::new(half_extents.x - 2.0, half_extents.y - 2.0), 10.0),
+    ];
+    let expected: HashSet<_> = [(0, 1), (2, 3), (4, 5)]
+        .into_iter()
+        .map(|(a, b)| (Entity::from_raw(a), Entity::from_raw(b)))
+        .collect();
+    assert_eq!(unique(naive_pairs(&circles)), expected);
+    assert_eq!(unique(grid_pairs(&mut SpatialGrid::default(), &circles)), expected);
+}
+
+// Timing of both ways with 1000 circles; run with `cargo test --release -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn bench_naive_vs_grid_at_1000() {
+    bench_naive_vs_grid(1000, 100);
+}
@@ -0,0 +1,59 @@
+// The docking station: a slow, careful approach docks and refuels, a fast one bounces off
+use bevy::prelude::*;
+
+use star_ship_game::headless::{headless_app, hold_input, spawn_test_ship, step, stop_spawning, with_commands};
+use star_ship_game::input::PlayerInput;
+use star_ship_game::physics::Velocity;
+use star_ship_game::player::Engine;
+use star_ship_game::station::{Docked, Station, StationPlugin};
+
+// Where the station is put, straight ahead of the ship
+const STATION_POSITION: Vec2 = Vec2::new(0.0, 150.0);
+
+// A half-empty ship drifting straight at a station with the dock key held, at the given speed
+fn approach(speed: f32) -> (App, Entity) {
+    let mut app = headless_app();
+    app.add_plugins(StationPlugin);
+    stop_spawning(&mut app);
+    with_commands(&mut app, |commands| {
+        commands.spawn((
+            TransformBundle::from_transform(Transform::from_translation(STATION_POSITION.extend(0.0))),
+            Station::default(),
+        ));
+    });
+    let ship = spawn_test_ship(&mut app);
+    *app.world.get_mut::<Velocity>(ship).unwrap() = Velocity { x: 0.0, y: speed };
+    {
+        let mut engine = app.world.get_mut::<Engine>(ship).unwrap();
+        engine.fuel = engine.max_fuel / 2.0;
+    }
+    hold_input(&mut app, 0, PlayerInput { dock: true, ..default() });
+    (app, ship)
+}
+
+// Coming in slowly docks the ship, and the station starts filling its tank
+#[test]
+fn slow_approach_docks_and_refuels() {
+    let max_dock_speed = Station::default().max_dock_speed;
+    let (mut app, ship) = approach(max_dock_speed * 0.5);
+    let mut ticks = 0;
+    while app.world.get::<Docked>(ship).is_none() {
+        step(&mut app, 1);
+        ticks += 1;
+        assert!(ticks < 600, "the ship never docked");
+    }
+    let fuel = app.world.get::<Engine>(ship).unwrap().fuel;
+    step(&mut app, 30);
+    assert!(app.world.get::<Docked>(ship).is_some());
+    assert!(app.world.get::<Engine>(ship).unwrap().fuel > fuel);
+}
+
+// Coming in too fast bounces the ship back off the station without docking
+#[test]
+fn fast_approach_bounces_off() {
+    let max_dock_speed = Station::default().max_dock_speed;
+    let (mut app, ship) = approach(max_dock_speed * 3.0);
+    step(&mut app, 120);
+    assert!(app.world.get::<Docked>(ship).is_none());
+    assert!(app.world.get::<Velocity>(ship).unwrap().y < 0.0);
+}
@@ -0,0 +1,23 @@
+// The first-game tutorial: each hint is ticked off by doing what it asks
+use bevy::prelude::*;
+
+use star_ship_game::headless::{headless_app, press_key, spawn_test_ship, step, stop_spawning};
+use star_ship_game::tutorial::{Hint, Tutorial, TutorialPlugin};
+
+// Thrusting marks the thrust hint complete, and leaves the fire hint still to do
+#[test]
+fn thrusting_completes_the_thrust_hint() {
+    let mut app = headless_app();
+    app.add_plugins(TutorialPlugin);
+    stop_spawning(&mut app);
+    spawn_test_ship(&mut app);
+    step(&mut app, 1);
+    assert!(!app.world.resource::<Tutorial>().is_completed(Hint::Thrust));
+
+    press_key(&mut app, KeyCode::Up);
+    step(&mut app, 1);
+    let tutorial = app.world.resource::<Tutorial>();
+    assert!(tutorial.is_completed(Hint::Thrust));
+    assert!(!tutorial.is_completed(Hint::Fire));
+    assert_eq!(tutorial.current(), Some(Hint::Fire));
+}
@@ -0,0 +1,265 @@
+// Weapons: what it takes to get a shot off, where it leaves from, how hot it runs, what a shot
+// passes through, what a beam burns and what a smart bomb reaches
+use bevy::prelude::*;
+
+use star_ship_game::combat::Health;
+use star_ship_game::headless::{headless_app, hold_input, spawn_test_ship, step, stop_spawning, with_commands};
+use star_ship_game::hostiles::spawn_asteroid;
+use star_ship_game::input::PlayerInput;
+use star_ship_game::pool::Pooled;
+use star_ship_game::physics::FIXED_TICK_RATE;
+use star_ship_game::weapons::{AmmoPool, AutoFire, BombSettings, Bombs, Heat, Projectile, Turret, Weapon, WeaponType};
+
+// Shots in play, leaving out the ones waiting in the pool
+fn live_projectiles(app: &mut App) -> usize {
+    app.world
+        .query_filtered::<&Pooled, With<Projectile>>()
+        .iter(&app.world)
+        .filter(|pooled| pooled.in_use())
+        .count()
+}
+
+// Without the energy for a shot the trigger does nothing; with the trigger let go the pool fills
+// back up and the next pull fires
+#[test]
+fn firing_needs_energy_and_works_after_recharge() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    let cost = {
+        let mut ammo = app.world.get_mut::<AmmoPool>(ship).unwrap();
+        ammo.energy = ammo.cost_per_shot / 2.0;
+        ammo.cost_per_shot
+    };
+
+    hold_input(&mut app, 0, PlayerInput { fire: true, ..default() });
+    step(&mut app, 10);
+    assert_eq!(live_projectiles(&mut app), 0);
+    // Nothing comes back while the trigger is held
+    assert_eq!(app.world.get::<AmmoPool>(ship).unwrap().energy, cost / 2.0);
+
+    hold_input(&mut app, 0, PlayerInput::default());
+    step(&mut app, 60);
+    assert!(app.world.get::<AmmoPool>(ship).unwrap().energy >= cost);
+
+    hold_input(&mut app, 0, PlayerInput { fire: true, ..default() });
+    step(&mut app, 1);
+    assert_eq!(live_projectiles(&mut app), 1);
+}
+
+// Holding the trigger down heats the weapon until it locks; it stays locked, firing nothing, until
+// it has cooled down, and then fires again
+#[test]
+fn sustained_fire_overheats_until_cooled() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    {
+        // Plenty of energy, so only the heat holds the weapon back
+        let mut ammo = app.world.get_mut::<AmmoPool>(ship).unwrap();
+        ammo.max = 10_000.0;
+        ammo.energy = ammo.max;
+    }
+    let energy = |app: &App| app.world.get::<AmmoPool>(ship).unwrap().energy;
+
+    hold_input(&mut app, 0, PlayerInput { fire: true, ..default() });
+    let mut ticks = 0;
+    while !app.world.get::<Heat>(ship).unwrap().overheated {
+        step(&mut app, 1);
+        ticks += 1;
+        assert!(ticks < 600, "the weapon never overheated");
+    }
+    // Still held, but nothing is fired while it cools
+    let locked = energy(&app);
+    step(&mut app, 30);
+    assert!(app.world.get::<Heat>(ship).unwrap().overheated);
+    assert_eq!(energy(&app), locked);
+
+    hold_input(&mut app, 0, PlayerInput::default());
+    step(&mut app, 240);
+    assert!(!app.world.get::<Heat>(ship).unwrap().overheated);
+
+    hold_input(&mut app, 0, PlayerInput { fire: true, ..default() });
+    let cooled = energy(&app);
+    step(&mut app, 1);
+    assert!(energy(&app) < cooled);
+}
+
+// A boosted shot pierces two asteroids in a row, destroying both, and is used up before a third
+#[test]
+fn piercing_shot_destroys_two_asteroids() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    app.world.get_mut::<Weapon>(ship).unwrap().weapon_type = WeaponType::Boosted;
+    assert_eq!(WeaponType::Boosted.pierce(), 2);
+    let asteroids = with_commands(&mut app, |commands| {
+        [60.0, 120.0, 180.0].map(|y| spawn_asteroid(commands, Vec2::new(0.0, y), Vec2::ZERO, 8.0))
+    });
+    // The cannon of a new ship is still cooling down
+    step(&mut app, 30);
+
+    hold_input(&mut app, 0, PlayerInput { fire: true, ..default() });
+    step(&mut app, 1);
+    hold_input(&mut app, 0, PlayerInput::default());
+    step(&mut app, 60);
+
+    assert!(app.world.get_entity(asteroids[0]).is_none());
+    assert!(app.world.get_entity(asteroids[1]).is_none());
+    assert!(app.world.get_entity(asteroids[2]).is_some());
+    assert_eq!(live_projectiles(&mut app), 0);
+}
+
+// A target in the beam's path keeps losing health while the beam is held on it, and one off to the
+// side is left alone
+#[test]
+fn beam_burns_target_in_its_path() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    app.world.get_mut::<Weapon>(ship).unwrap().weapon_type = WeaponType::Beam;
+    let (target, bystander) = with_commands(&mut app, |commands| {
+        (
+            spawn_asteroid(commands, Vec2::new(0.0, 150.0), Vec2::ZERO, 60.0),
+            spawn_asteroid(commands, Vec2::new(150.0, 0.0), Vec2::ZERO, 20.0),
+        )
+    });
+    let health = |app: &App, entity: Entity| app.world.get::<Health>(entity).unwrap().current;
+    let full = health(&app, target);
+
+    hold_input(&mut app, 0, PlayerInput { fire: true, ..default() });
+    step(&mut app, 15);
+    let burned = health(&app, target);
+    assert!(burned < full, "target still at {}", burned);
+    step(&mut app, 15);
+    assert!(health(&app, target) < burned);
+    assert_eq!(health(&app, bystander), 20.0);
+}
+
+// A ship whose cannon has finished the cooldown it starts with, fitted with the given weapon
+fn armed_ship(weapon_type: WeaponType) -> (App, Entity) {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    app.world.get_mut::<Weapon>(ship).unwrap().weapon_type = weapon_type;
+    step(&mut app, 30);
+    (app, ship)
+}
+
+// A full-auto cannon keeps firing, once every cooldown, for as long as the trigger is held
+#[test]
+fn full_auto_fires_while_held() {
+    let (mut app, _) = armed_ship(WeaponType::Standard);
+    hold_input(&mut app, 0, PlayerInput { fire: true, ..default() });
+    step(&mut app, 1);
+    assert_eq!(live_projectiles(&mut app), 1);
+    step(&mut app, 59);
+    assert!(live_projectiles(&mut app) >= 4, "{} shots in a second", live_projectiles(&mut app));
+}
+
+// A semi-auto cannon fires once for each press, however long the trigger is held
+#[test]
+fn semi_auto_fires_once_per_press() {
+    let (mut app, _) = armed_ship(WeaponType::Boosted);
+    hold_input(&mut app, 0, PlayerInput { fire: true, ..default() });
+    step(&mut app, 60);
+    assert_eq!(live_projectiles(&mut app), 1);
+
+    hold_input(&mut app, 0, PlayerInput::default());
+    step(&mut app, 1);
+    hold_input(&mut app, 0, PlayerInput { fire: true, ..default() });
+    step(&mut app, 1);
+    assert_eq!(live_projectiles(&mut app), 2);
+}
+
+// Picking up a semi-auto cannon while firing doesn't fire it until the trigger is pressed again
+#[test]
+fn semi_auto_picked_up_mid_hold_waits_for_a_press() {
+    let (mut app, ship) = armed_ship(WeaponType::Standard);
+    hold_input(&mut app, 0, PlayerInput { fire: true, ..default() });
+    step(&mut app, 1);
+    let fired = live_projectiles(&mut app);
+    app.world.get_mut::<Weapon>(ship).unwrap().weapon_type = WeaponType::Boosted;
+    step(&mut app, 30);
+    assert_eq!(live_projectiles(&mut app), fired);
+
+    hold_input(&mut app, 0, PlayerInput::default());
+    step(&mut app, 1);
+    hold_input(&mut app, 0, PlayerInput { fire: true, ..default() });
+    step(&mut app, 1);
+    assert_eq!(live_projectiles(&mut app), fired + 1);
+}
+
+// With auto-fire on the cannon fires by itself, nothing held, once every cooldown
+#[test]
+fn auto_fire_shoots_at_cooldown_cadence() {
+    let (mut app, ship) = armed_ship(WeaponType::Standard);
+    let cooldown = app.world.get::<Weapon>(ship).unwrap().cooldown.duration().as_secs_f64();
+    app.world.insert_resource(AutoFire(true));
+
+    let mut shot_ticks = Vec::new();
+    for tick in 0..60 {
+        let before = live_projectiles(&mut app);
+        step(&mut app, 1);
+        if live_projectiles(&mut app) > before {
+            shot_ticks.push(tick);
+        }
+    }
+    assert_eq!(shot_ticks.first(), Some(&0));
+    let cadence = (cooldown * FIXED_TICK_RATE).round() as u32;
+    assert!(shot_ticks.len() >= 4, "shots on ticks {:?}", shot_ticks);
+    assert!(shot_ticks.windows(2).all(|pair| pair[1] - pair[0] == cadence), "shots on ticks {:?}", shot_ticks);
+}
+
+// The turret rides along with its hull wherever it flies, and keeps pointing where it is aimed
+// however the hull turns under it
+#[test]
+fn turret_tracks_its_hull() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    let aim = 0.5;
+    hold_input(&mut app, 0, PlayerInput { thrust: 1.0, rotate: 1.0, aim: Some(aim), ..default() });
+
+    for _ in 0..4 {
+        step(&mut app, 15);
+        let hull = *app.world.get::<Transform>(ship).unwrap();
+        let turret = app
+            .world
+            .query_filtered::<(&Parent, &GlobalTransform), With<Turret>>()
+            .iter(&app.world)
+            .find(|(parent, _)| parent.get() == ship)
+            .map(|(_, transform)| transform.compute_transform())
+            .unwrap();
+        let (hull_position, turret_position) = (hull.translation.truncate(), turret.translation.truncate());
+        assert!(hull_position.abs_diff_eq(turret_position, 1e-3), "hull {} turret {}", hull_position, turret_position);
+        let turret_angle = turret.rotation.to_euler(EulerRot::ZYX).0;
+        assert!((turret_angle - aim).abs() < 1e-4, "turret points at {}", turret_angle);
+    }
+}
+
+// A smart bomb destroys what is within its reach of the ship and leaves the rest, and the ship
+// that set it off
+#[test]
+fn bomb_destroys_only_what_is_in_range() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    let ship = spawn_test_ship(&mut app);
+    let radius = app.world.resource::<BombSettings>().radius;
+    let (inside, outside) = with_commands(&mut app, |commands| {
+        (
+            spawn_asteroid(commands, Vec2::new(radius - 50.0, 0.0), Vec2::ZERO, 10.0),
+            spawn_asteroid(commands, Vec2::new(radius + 50.0, 0.0), Vec2::ZERO, 10.0),
+        )
+    });
+
+    hold_input(&mut app, 0, PlayerInput { bomb: true, ..default() });
+    step(&mut app, 1);
+    hold_input(&mut app, 0, PlayerInput::default());
+    step(&mut app, 2);
+
+    assert!(app.world.get_entity(inside).is_none());
+    assert!(app.world.get_entity(outside).is_some());
+    assert!(app.world.get_entity(ship).is_some());
+    assert_eq!(app.world.resource::<Bombs>().0, Bombs::default().0 - 1);
+}
@@ -0,0 +1,46 @@
+// The play area: its size taken from the config rather than the window, and things wrapping at
+// its edges
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowResolution};
+
+use star_ship_game::config::GameConfig;
+use star_ship_game::headless::{HEADLESS_ARENA, headless_app, step, stop_spawning, with_commands};
+use star_ship_game::hostiles::spawn_asteroid;
+use star_ship_game::physics::WorldBounds;
+
+// An asteroid flying out past the window's edge carries on in a bigger world, and only comes back
+// in on the far side once it crosses the edge of the world itself
+#[test]
+fn wrap_happens_at_world_edge_not_window_edge() {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    app.world.spawn((
+        Window {
+            resolution: WindowResolution::new(HEADLESS_ARENA.x, HEADLESS_ARENA.y),
+            ..default()
+        },
+        PrimaryWindow,
+    ));
+    let world_size = HEADLESS_ARENA * 2.5;
+    {
+        let mut config = app.world.resource_mut::<GameConfig>();
+        config.world.width = world_size.x;
+        config.world.height = world_size.y;
+    }
+    step(&mut app, 1);
+    let bounds = app.world.resource::<WorldBounds>();
+    assert_eq!(Vec2::new(bounds.width, bounds.height), world_size);
+
+    let half_window = HEADLESS_ARENA.x / 2.0;
+    let asteroid = with_commands(&mut app, |commands| {
+        spawn_asteroid(commands, Vec2::new(half_window - 10.0, 0.0), Vec2::new(300.0, 0.0), 10.0)
+    });
+    let x = |app: &App| app.world.get::<Transform>(asteroid).unwrap().translation.x;
+    step(&mut app, 10);
+    assert!(x(&app) > half_window, "asteroid at {} inside the window", x(&app));
+
+    let half_world = world_size.x / 2.0;
+    app.world.get_mut::<Transform>(asteroid).unwrap().translation.x = half_world - 10.0;
+    step(&mut app, 10);
+    assert!(x(&app) < -half_world + 50.0, "asteroid at {} never wrapped", x(&app));
+}
@@ -0,0 +1,63 @@
+// The safe zone: ships caught outside it wear down, ships inside or invulnerable don't
+use bevy::prelude::*;
+
+use star_ship_game::combat::{Health, Invulnerable, Shield};
+use star_ship_game::config::GameConfig;
+use star_ship_game::headless::{headless_app, spawn_test_ship, step, stop_spawning};
+use star_ship_game::zone::SafeZone;
+
+// Radius the zone is held at, well inside the arena
+const ZONE_RADIUS: f32 = 150.0;
+
+// A headless app with the zone switched on and held still at ZONE_RADIUS, and a ship at the given spot
+fn zone_app(position: Vec2) -> (App, Entity) {
+    let mut app = headless_app();
+    stop_spawning(&mut app);
+    app.world.resource_mut::<GameConfig>().safe_zone.enabled = true;
+    step(&mut app, 1);
+    let mut zone = app.world.resource_mut::<SafeZone>();
+    zone.radius = ZONE_RADIUS;
+    zone.min_radius = ZONE_RADIUS;
+    zone.shrink_rate = 0.0;
+
+    let ship = spawn_test_ship(&mut app);
+    app.world.get_mut::<Transform>(ship).unwrap().translation = position.extend(0.0);
+    (app, ship)
+}
+
+// What the ship has left to lose, shield and hull together
+fn toughness(app: &App, ship: Entity) -> f32 {
+    app.world.get::<Health>(ship).unwrap().current + app.world.get::<Shield>(ship).unwrap().current
+}
+
+// A ship outside the zone keeps losing health while it stays there
+#[test]
+fn ship_outside_zone_is_hurt() {
+    let (mut app, ship) = zone_app(Vec2::new(ZONE_RADIUS * 2.0, 0.0));
+    let start = toughness(&app, ship);
+    step(&mut app, 60);
+    let after_one_second = toughness(&app, ship);
+    step(&mut app, 60);
+
+    assert!(after_one_second < start, "{} -> {}", start, after_one_second);
+    assert!(toughness(&app, ship) < after_one_second);
+}
+
+// A ship inside the zone is left alone
+#[test]
+fn ship_inside_zone_is_safe() {
+    let (mut app, ship) = zone_app(Vec2::new(ZONE_RADIUS * 0.5, 0.0));
+    let start = toughness(&app, ship);
+    step(&mut app, 120);
+    assert_eq!(toughness(&app, ship), start);
+}
+
+// An invulnerable ship, like one in god mode, is never worn down by the zone
+#[test]
+fn invulnerable_ship_outside_zone_is_safe() {
+    let (mut app, ship) = zone_app(Vec2::new(ZONE_RADIUS * 2.0, 0.0));
+    app.world.entity_mut(ship).insert(Invulnerable);
+    let start = toughness(&app, ship);
+    step(&mut app, 120);
+    assert_eq!(toughness(&app, ship), start);
+}